@@ -0,0 +1,77 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const API_TYPES_PACKAGE: &str = "giga-chess-api-types";
+
+/// Exports the locked version of `giga-chess-api-types`, servers report the version they were built
+/// against and the client compares it with its own. Without a lock file, e.g. when built as a
+/// dependency, the version requirement of the manifest is used instead.
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let manifest_file = manifest_dir.join("Cargo.toml");
+    println!("cargo:rerun-if-changed={}", manifest_file.display());
+
+    let locked_version = find_lock_file(&manifest_dir).and_then(|lock_file| {
+        println!("cargo:rerun-if-changed={}", lock_file.display());
+        let lock = fs::read_to_string(&lock_file).ok()?;
+        get_locked_version(&lock, API_TYPES_PACKAGE).map(str::to_string)
+    });
+    let version = locked_version.or_else(|| {
+        println!(
+            "cargo:warning=No locked version of {API_TYPES_PACKAGE} found, using the version requirement of Cargo.toml"
+        );
+        let manifest = fs::read_to_string(&manifest_file).ok()?;
+        get_required_version(&manifest, API_TYPES_PACKAGE).map(str::to_string)
+    });
+    let version = version.unwrap_or_else(|| {
+        println!("cargo:warning=The version of {API_TYPES_PACKAGE} is unknown");
+        "0.0.0".to_string()
+    });
+    println!("cargo:rustc-env=GIGA_CHESS_API_TYPES_VERSION={version}");
+}
+
+/// Workspace members share the lock file of the workspace root.
+fn find_lock_file(manifest_dir: &Path) -> Option<PathBuf> {
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|lock_file| lock_file.is_file())
+}
+
+/// Reads the version of the package's `[[package]]` entry, the name always comes first.
+fn get_locked_version<'a>(lock: &'a str, package: &str) -> Option<&'a str> {
+    let name_line = format!("name = \"{package}\"");
+    lock.split("[[package]]").find_map(|entry| {
+        let mut lines = entry.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines.next()? != name_line {
+            return None;
+        }
+        lines.find_map(|line| {
+            line.strip_prefix("version = \"")
+                .and_then(|version| version.strip_suffix('"'))
+        })
+    })
+}
+
+/// Reads the version requirement of the dependency, either `package = "1.0"` or
+/// `package = { version = "1.0", ... }`, without its operator.
+fn get_required_version<'a>(manifest: &'a str, package: &str) -> Option<&'a str> {
+    let line = manifest.lines().map(str::trim).find_map(|line| {
+        line.strip_prefix(package)?
+            .trim_start()
+            .strip_prefix('=')
+            .map(str::trim)
+    })?;
+    let requirement = match line.strip_prefix('{') {
+        Some(table) => table
+            .split("version")
+            .nth(1)?
+            .trim_start()
+            .strip_prefix('=')?,
+        None => line,
+    };
+    let requirement = requirement.trim_start().strip_prefix('"')?;
+    let requirement = &requirement[..requirement.find('"')?];
+    Some(requirement.trim_start_matches(['^', '=', '~', ' ']))
+}
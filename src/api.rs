@@ -1,108 +1,424 @@
+use crate::api::account::{AccountInfo, ChangePasswordBody, ChangeUsernameBody, DeleteAccountBody};
+use crate::api::correspondence::{CorrespondenceBody, CorrespondenceGameList};
+use crate::api::error::field_errors::FieldErrors;
 use crate::api::error::{ApiError, ApiResult};
+use crate::api::friends::{Challenge, ChallengeBody, ChallengeList, FriendList, UsernameBody};
+use crate::api::invite_codes::{InviteCode, InviteCodeBody, InviteCodeList};
+use crate::api::leaderboard::{Leaderboard, LeaderboardQuery};
+use crate::api::live::{LiveConnection, LiveTransport};
+use crate::api::login::LoginState;
+use crate::api::matchmaking::{CreateGameBody, QueueBody, QueueStatusResponse};
+use crate::api::network::{NetworkError, NetworkSettings};
+use crate::api::player_profile::{GameHistory, GameRecord, RatingCategory, UserProfile};
+use crate::api::request::{get_request_key, InFlightRequests, RequestHandle, RequestKey};
+use crate::api::retry::{execute_with_retries, parse_retry_after, RetryPolicy, RetryStatus};
+use crate::api::server_info::{Capability, ServerInfo, ServerInfoState};
+use crate::api::server_profile::{HealthReport, HealthResponse, ServerProfile};
+use crate::api::session::{LogoutBody, RefreshBody, RememberedLoginResponse, Session};
+use crate::api::spectating::LiveGameList;
+use crate::api::tournaments::{Tournament, TournamentList};
 use crate::persistence::PersistentObject;
+use crate::types::shared::Shared;
 use giga_chess_api_types::body::login::LoginBody;
 use giga_chess_api_types::body::register::RegisterBody;
+use giga_chess_api_types::body::uuid::UuidBody;
 use giga_chess_api_types::response::login::LoginResponse;
 use giga_chess_api_types::response::message::MessageResponse;
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use giga_chess_api_types::response::room_list::PublicRoomList;
+use log::{debug, info, warn};
 use reqwest::{Client, RequestBuilder, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::any::TypeId;
 use std::error::Error;
+use std::time::Instant;
 use tokio::runtime::Runtime;
 
+pub mod account;
+pub mod correspondence;
 pub mod error;
+pub mod friends;
+pub mod invite_codes;
+pub mod leaderboard;
+pub mod live;
+pub mod login;
+pub mod matchmaking;
+pub mod network;
+pub mod player_profile;
+pub mod request;
+pub mod retry;
+pub mod server_info;
+pub mod server_profile;
+pub mod session;
+pub mod spectating;
+pub mod tournaments;
 
 #[derive(Debug)]
 pub struct MultiplayerClient {
     client: Client,
     runtime: Runtime,
     server_url: Option<String>,
+    /// Used instead of the saved server url for this run only, e.g. for the mock server.
+    server_url_override: Option<String>,
+    live_transport: LiveTransport,
+    server_profiles: Vec<ServerProfile>,
+    retry_policy: RetryPolicy,
+    network_settings: NetworkSettings,
+    /// Why the network settings couldn't be applied, the defaults are used in the meantime.
+    network_error: Option<String>,
+    /// Used for WebSocket connections, None if the default TLS settings suffice.
+    tls_connector: Option<native_tls::TlsConnector>,
+    retry_status: Shared<Option<RetryStatus>>,
+    in_flight: InFlightRequests,
+    server_info: Shared<ServerInfoState>,
+    session: Shared<LoginState>,
 }
 
 impl Default for MultiplayerClient {
     fn default() -> Self {
+        let retry_policy = RetryPolicy::default();
+        let network_settings = NetworkSettings::default();
+        let (client, tls_connector) = build_client(&retry_policy, &network_settings)
+            .unwrap_or_else(|err| {
+                warn!("Failed to build the HTTP client, falling back to defaults: {err}");
+                (Client::new(), None)
+            });
         Self {
-            client: Client::new(),
+            client,
             runtime: Runtime::new().unwrap(),
             server_url: None,
+            server_url_override: None,
+            live_transport: LiveTransport::default(),
+            server_profiles: Vec::new(),
+            retry_policy,
+            network_settings,
+            network_error: None,
+            tls_connector,
+            retry_status: Shared::default(),
+            in_flight: Shared::default(),
+            server_info: Shared::default(),
+            session: Shared::default(),
         }
     }
 }
 
+/// Users are addressed by name, `me` stands for the logged in user.
+fn get_user_path(username: Option<String>) -> String {
+    username.unwrap_or_else(|| "me".to_string())
+}
+
+/// Builds the HTTP client and the matching TLS connector for WebSocket connections.
+fn build_client(
+    retry_policy: &RetryPolicy,
+    network_settings: &NetworkSettings,
+) -> Result<(Client, Option<native_tls::TlsConnector>), NetworkError> {
+    let builder = Client::builder()
+        .connect_timeout(retry_policy.connect_timeout())
+        .timeout(retry_policy.request_timeout());
+    let client = network_settings.apply(builder)?.build()?;
+    let tls_connector = network_settings.build_tls_connector()?;
+    Ok((client, tls_connector))
+}
+
 impl MultiplayerClient {
+    /// The server requests are sent to, an override takes precedence over the saved url.
     pub fn get_server_url(&self) -> Option<&str> {
+        self.server_url_override
+            .as_deref()
+            .or(self.server_url.as_deref())
+    }
+
+    /// The url which is persisted, regardless of an override.
+    pub fn get_saved_server_url(&self) -> Option<&str> {
         self.server_url.as_deref()
     }
 
+    pub fn get_server_url_override(&self) -> Option<&str> {
+        self.server_url_override.as_deref()
+    }
+
+    /// Choosing a server ends an override.
     pub fn set_server_url(&mut self, server_url: impl Into<String>) {
-        self.server_url = Some(server_url.into());
+        self.set_urls(Some(server_url.into()), None);
+    }
+
+    pub fn set_server_url_override(&mut self, server_url_override: Option<String>) {
+        self.set_urls(self.server_url.clone(), server_url_override);
+    }
+
+    fn set_urls(&mut self, server_url: Option<String>, server_url_override: Option<String>) {
+        let previous_url = self.get_server_url().map(str::to_string);
+        self.server_url = server_url;
+        self.server_url_override = server_url_override;
+        if self.get_server_url() != previous_url.as_deref() {
+            self.server_info.set(ServerInfoState::Unknown);
+        }
+    }
+
+    /// What the last handshake found out about the current server.
+    pub fn server_info(&self) -> ServerInfoState {
+        self.server_info.get_clone()
+    }
+
+    /// The live transport which is actually used, falling back if the server lacks the chosen one.
+    /// WebSockets can't be routed through a custom proxy, so long polling is used with one.
+    pub fn get_effective_live_transport(&self) -> LiveTransport {
+        if self.network_settings.uses_custom_proxy() {
+            return LiveTransport::Polling;
+        }
+
+        let server_info = self.server_info.lock();
+        let websocket = server_info.supports(Capability::LiveWebsocket);
+        let polling = server_info.supports(Capability::LivePolling);
+        match self.live_transport {
+            LiveTransport::Auto | LiveTransport::WebSocket if !websocket && polling => {
+                LiveTransport::Polling
+            }
+            LiveTransport::Polling if !polling && websocket => LiveTransport::WebSocket,
+            transport => transport,
+        }
+    }
+
+    pub fn get_live_transport(&self) -> LiveTransport {
+        self.live_transport
+    }
+
+    pub fn set_live_transport(&mut self, live_transport: LiveTransport) {
+        self.live_transport = live_transport;
+    }
+
+    pub fn get_server_profiles(&self) -> &[ServerProfile] {
+        &self.server_profiles
+    }
+
+    /// Replaces a profile with the same name, otherwise adds it.
+    pub fn save_server_profile(&mut self, profile: ServerProfile) {
+        match self
+            .server_profiles
+            .iter_mut()
+            .find(|existing| existing.name == profile.name)
+        {
+            Some(existing) => *existing = profile,
+            None => self.server_profiles.push(profile),
+        }
+    }
+
+    pub fn remove_server_profile(&mut self, name: &str) {
+        self.server_profiles.retain(|profile| profile.name != name);
+    }
+
+    /// The saved profile of the server currently in use, if there is one.
+    pub fn get_active_server_profile(&self) -> Option<&ServerProfile> {
+        let server_url = self.get_server_url()?;
+        self.server_profiles
+            .iter()
+            .find(|profile| profile.url == server_url)
+    }
+
+    pub fn get_retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Rebuilds the HTTP client, requests which are already running keep the old timeouts.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        if self.retry_policy != retry_policy {
+            self.retry_policy = retry_policy;
+            self.rebuild_client();
+        }
+    }
+
+    pub fn get_network_settings(&self) -> &NetworkSettings {
+        &self.network_settings
+    }
+
+    /// Why the persisted network settings couldn't be applied, if they couldn't.
+    pub fn get_network_error(&self) -> Option<&str> {
+        self.network_error.as_deref()
+    }
+
+    /// Rebuilds the HTTP client, invalid settings are rejected and the current ones are kept.
+    pub fn set_network_settings(
+        &mut self,
+        network_settings: NetworkSettings,
+    ) -> Result<(), NetworkError> {
+        if self.network_settings == network_settings && self.network_error.is_none() {
+            return Ok(());
+        }
+
+        let (client, tls_connector) = build_client(&self.retry_policy, &network_settings)?;
+        self.client = client;
+        self.tls_connector = tls_connector;
+        self.network_settings = network_settings;
+        self.network_error = None;
+        Ok(())
+    }
+
+    /// Persisted settings might have become invalid, e.g. if a certificate was deleted. The client
+    /// falls back to the default network settings then and remembers the error.
+    fn rebuild_client(&mut self) {
+        let result = match build_client(&self.retry_policy, &self.network_settings) {
+            Ok(built) => {
+                self.network_error = None;
+                Ok(built)
+            }
+            Err(err) => {
+                warn!("Failed to apply the network settings, falling back to defaults: {err}");
+                self.network_error = Some(err.to_string());
+                build_client(&self.retry_policy, &NetworkSettings::default())
+            }
+        };
+
+        match result {
+            Ok((client, tls_connector)) => {
+                self.client = client;
+                self.tls_connector = tls_connector;
+            }
+            Err(err) => warn!("Failed to build the HTTP client: {err}"),
+        }
+    }
+
+    /// The request currently waiting to be retried, if any.
+    pub fn retry_status(&self) -> Option<RetryStatus> {
+        self.retry_status.get_clone()
+    }
+
+    /// Applies persisted settings to the running client without replacing the runtime or session.
+    /// Like choosing a server, this ends an override.
+    pub fn apply_settings(&mut self, settings: MultiplayerClientPersist) {
+        self.set_urls(settings.server_url, None);
+        self.live_transport = settings.live_transport;
+        self.server_profiles = settings.server_profiles;
+        self.retry_policy = settings.retry_policy;
+        self.network_settings = settings.network_settings;
+        self.rebuild_client();
+    }
+
+    /// The login state is shared with the app so token refreshes are visible immediately.
+    pub fn session(&self) -> &Shared<LoginState> {
+        &self.session
     }
 
     pub fn is_ready(&self) -> bool {
-        self.server_url.is_some()
+        self.get_server_url().is_some()
     }
 
-    fn spawn_request<T, F>(&self, request: RequestBuilder, callback: F)
+    /// Runs the request in the background unless the same caller already runs an identical one, in
+    /// which case the running request's handle is returned and the callback is dropped.
+    fn spawn_deduplicated<T, F, R>(
+        &self,
+        key: Option<RequestKey>,
+        request: R,
+        callback: F,
+    ) -> RequestHandle
     where
-        T: DeserializeOwned + Send + 'static,
+        T: Send + 'static,
         F: FnOnce(ApiResult<T>) + Send + 'static,
+        R: Future<Output = ApiResult<T>> + Send + 'static,
     {
-        self.runtime.spawn(async move {
-            let result = match request.send().await {
-                Ok(response) if response.status().is_success() => {
-                    match response.json::<T>().await {
-                        Ok(data) => Ok(data),
-                        Err(error) => Err(error.into()),
-                    }
-                }
-                Ok(response) => match response.status() {
-                    StatusCode::BAD_REQUEST => Err(ApiError::BadRequest(
-                        response.text().await.unwrap_or_default(),
-                    )),
-                    StatusCode::CONFLICT => Err(ApiError::Collision(
-                        response.text().await.unwrap_or_default(),
-                    )),
-                    StatusCode::NOT_FOUND => Err(ApiError::NotFound(
-                        response.text().await.unwrap_or_default(),
-                    )),
-                    StatusCode::UNAUTHORIZED => Err(ApiError::Unauthorized(
-                        response.text().await.unwrap_or_default(),
-                    )),
-                    StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimited(
-                        response.text().await.unwrap_or_default(),
-                    )),
-                    _ => Err(ApiError::Unexpected(format!(
-                        "[{}]: {}",
-                        response.status(),
-                        response.text().await.unwrap_or_default()
-                    ))),
-                },
-                Err(error) => {
-                    let error_source = error
-                        .source()
-                        .map(|e| e.to_string())
-                        .unwrap_or_else(|| error.to_string());
-
-                    if error.is_builder() {
-                        Err(ApiError::InvalidServerUrl)
-                    } else if error.is_request() || error.is_connect() {
-                        Err(ApiError::Connection(format!(
-                            "{error} (source: {error_source})"
-                        )))
-                    } else if error.is_body() || error.is_decode() {
-                        Err(ApiError::Communication(format!(
-                            "{error} (source: {error_source})"
-                        )))
-                    } else if error.is_timeout() {
-                        Err(ApiError::ConnectionTimeout)
-                    } else {
-                        Err(error.into())
-                    }
+        let handle = {
+            let mut in_flight = self.in_flight.lock();
+            if let Some(key) = key
+                && let Some(running) = in_flight.get(&key)
+                && !running.is_finished()
+            {
+                debug!("Skipping a request identical to one which is still running");
+                return running.clone();
+            }
+
+            let handle = RequestHandle::new();
+            if let Some(key) = key {
+                in_flight.insert(key, handle.clone());
+            }
+            handle
+        };
+
+        let in_flight = self.in_flight.clone();
+        let task_handle = handle.clone();
+        let task = self.runtime.spawn(async move {
+            let result = request.await;
+            match &result {
+                Err(error) if error.is_expected() || task_handle.is_cancelled() => {
+                    debug!("API request failed: {error}");
                 }
-            };
+                Err(error) => warn!("API request failed: {error}"),
+                Ok(_) => {}
+            }
 
-            callback(result);
+            if let Some(key) = key {
+                in_flight.with(|in_flight| {
+                    if in_flight
+                        .get(&key)
+                        .is_some_and(|running| running.is_same(&task_handle))
+                    {
+                        in_flight.remove(&key);
+                    }
+                });
+            }
+            task_handle.finish(|| callback(result));
         });
+        handle.set_abort_handle(task.abort_handle());
+        handle
+    }
+
+    fn spawn_request<T, F>(&self, request: RequestBuilder, callback: F) -> RequestHandle
+    where
+        T: DeserializeOwned + Send + 'static,
+        F: FnOnce(ApiResult<T>) + Send + 'static,
+    {
+        let key = get_request_key(&request, TypeId::of::<F>());
+        let retry_policy = self.retry_policy;
+        let retry_status = self.retry_status.clone();
+        self.spawn_deduplicated(
+            key,
+            async move { execute_with_retries(request, retry_policy, &retry_status).await },
+            callback,
+        )
+    }
+
+    /// Sends a request authenticated with the current session. Sessions with a refresh token
+    /// are refreshed shortly before they expire and when the server rejects the token, in which case
+    /// the request is retried once. Sessions which can't be refreshed expire on rejection.
+    /// Temporary failures are retried according to the retry policy.
+    fn spawn_authenticated_request<T, F, B>(&self, build_request: B, callback: F) -> RequestHandle
+    where
+        T: DeserializeOwned + Send + 'static,
+        F: FnOnce(ApiResult<T>) + Send + 'static,
+        B: Fn(&Client, &str) -> RequestBuilder + Send + 'static,
+    {
+        let Some(server_url) = self.get_server_url().map(str::to_string) else {
+            callback(Err(ApiError::MissingServerUrl));
+            return RequestHandle::completed();
+        };
+
+        // Read right away, so requests issued just before a logout still use the old session
+        let current = self.session.lock().get_session().cloned();
+        let Some(current) = current else {
+            callback(Err(ApiError::Unauthorized("Not logged in".to_string())));
+            return RequestHandle::completed();
+        };
+
+        let key = get_request_key(&build_request(&self.client, &server_url), TypeId::of::<F>());
+        let client = self.client.clone();
+        let session = self.session.clone();
+        let retry_policy = self.retry_policy;
+        let retry_status = self.retry_status.clone();
+        self.spawn_deduplicated(
+            key,
+            async move {
+                execute_authenticated_request(
+                    &client,
+                    &server_url,
+                    &session,
+                    current,
+                    build_request,
+                    retry_policy,
+                    &retry_status,
+                )
+                .await
+            },
+            callback,
+        )
     }
 
     pub fn register<F>(
@@ -111,12 +427,13 @@ impl MultiplayerClient {
         password: impl Into<String>,
         invite_code: impl Into<String>,
         callback: F,
-    ) where
+    ) -> RequestHandle
+    where
         F: FnOnce(ApiResult<LoginResponse>) + Send + 'static,
     {
-        let Some(server_url) = &self.server_url else {
+        let Some(server_url) = self.get_server_url() else {
             callback(Err(ApiError::MissingServerUrl));
-            return;
+            return RequestHandle::completed();
         };
 
         let body = RegisterBody {
@@ -129,16 +446,21 @@ impl MultiplayerClient {
             .client
             .post(format!("{server_url}/register"))
             .json(&body);
-        self.spawn_request(request, callback);
+        self.spawn_request(request, callback)
     }
 
-    pub fn login<F>(&self, username: impl Into<String>, password: impl Into<String>, callback: F)
+    pub fn login<F>(
+        &self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+        callback: F,
+    ) -> RequestHandle
     where
         F: FnOnce(ApiResult<LoginResponse>) + Send + 'static,
     {
-        let Some(server_url) = &self.server_url else {
+        let Some(server_url) = self.get_server_url() else {
             callback(Err(ApiError::MissingServerUrl));
-            return;
+            return RequestHandle::completed();
         };
 
         let body = LoginBody {
@@ -147,29 +469,731 @@ impl MultiplayerClient {
         };
 
         let request = self.client.post(format!("{server_url}/login")).json(&body);
-        self.spawn_request(request, callback);
+        self.spawn_request(request, callback)
     }
 
-    pub fn ping<F>(&self, token: &str, callback: F)
+    /// Like `login`, but the server also issues a refresh token, which keeps the session alive
+    /// without storing the password. Requires `Capability::RefreshTokens`.
+    pub fn login_remembered<F>(
+        &self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+        callback: F,
+    ) -> RequestHandle
     where
-        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+        F: FnOnce(ApiResult<RememberedLoginResponse>) + Send + 'static,
     {
-        let Some(server_url) = &self.server_url else {
+        let Some(server_url) = self.get_server_url() else {
             callback(Err(ApiError::MissingServerUrl));
-            return;
+            return RequestHandle::completed();
+        };
+
+        let body = LoginBody {
+            username: username.into(),
+            password: password.into(),
         };
 
         let request = self
             .client
-            .post(format!("{server_url}/ping"))
-            .bearer_auth(token);
-        self.spawn_request(request, callback);
+            .post(format!("{server_url}/login/remember"))
+            .json(&body);
+        self.spawn_request(request, callback)
+    }
+
+    /// Revokes the current token on the server together with the refresh token of a remembered
+    /// login, so neither can be used on this device anymore.
+    pub fn logout<F>(&self, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        let body = LogoutBody {
+            refresh_token: self
+                .session
+                .lock()
+                .get_session()
+                .and_then(|session| session.refresh_token.clone()),
+        };
+        self.spawn_authenticated_request(
+            move |client, server_url| client.post(format!("{server_url}/logout")).json(&body),
+            callback,
+        )
+    }
+
+    pub fn ping<F>(&self, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            |client, server_url| client.post(format!("{server_url}/ping")),
+            callback,
+        )
+    }
+
+    /// Asks the server for its API version and capabilities. Servers without the endpoint are
+    /// assumed to be compatible legacy servers.
+    pub fn check_server<F>(&self, callback: F) -> RequestHandle
+    where
+        F: FnOnce(&ServerInfoState) + Send + 'static,
+    {
+        let Some(server_url) = self.get_server_url() else {
+            return RequestHandle::completed();
+        };
+
+        let server_info = self.server_info.clone();
+        server_info.set(ServerInfoState::Checking);
+        let request = self.client.get(format!("{server_url}/version"));
+        self.spawn_request(request, move |result: ApiResult<ServerInfo>| {
+            let info = match result {
+                Ok(info) => Ok(info),
+                Err(ApiError::NotFound(_)) => Ok(ServerInfo::legacy()),
+                Err(err) => Err(err),
+            };
+            let state = match info {
+                Ok(info) => match info.check_compatibility() {
+                    Ok(()) => ServerInfoState::Compatible(info),
+                    Err(reason) => {
+                        warn!("Incompatible server: {reason}");
+                        ServerInfoState::Incompatible(reason)
+                    }
+                },
+                Err(err) => ServerInfoState::Error(err.to_string()),
+            };
+            server_info.set(state.clone());
+            callback(&state);
+        })
+    }
+
+    /// Measures the round trip to any server, not just the active one. Servers without a health
+    /// endpoint count as reachable, they just don't report a version.
+    pub fn check_health<F>(&self, server_url: &str, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<HealthReport>) + Send + 'static,
+    {
+        let request = self
+            .client
+            .get(format!("{}/health", server_url.trim_end_matches('/')));
+        let key = get_request_key(&request, TypeId::of::<F>());
+        self.spawn_deduplicated(
+            key,
+            async move {
+                let started_at = Instant::now();
+                let version = match execute_request::<HealthResponse>(request).await {
+                    Ok(response) => response.version,
+                    Err(ApiError::NotFound(_)) => None,
+                    Err(error) => return Err(error),
+                };
+                Ok(HealthReport {
+                    latency: started_at.elapsed(),
+                    version,
+                })
+            },
+            callback,
+        )
+    }
+
+    /// Opens the real-time connection of a room, `notify` is called whenever a new event arrived.
+    pub fn connect_live<N>(&self, room_uuid: &str, notify: N) -> ApiResult<LiveConnection>
+    where
+        N: Fn() + Send + Sync + 'static,
+    {
+        let Some(server_url) = self.get_server_url() else {
+            return Err(ApiError::MissingServerUrl);
+        };
+
+        LiveConnection::open(self, server_url, room_uuid, notify)
+    }
+
+    pub fn create_game<F>(&self, body: CreateGameBody, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<PrivateRoomInfo>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| client.post(format!("{server_url}/room")).json(&body),
+            callback,
+        )
+    }
+
+    pub fn list_open_games<F>(&self, page: i64, limit: i64, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<PublicRoomList>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client
+                    .get(format!("{server_url}/room/public"))
+                    .query(&[("page", page), ("limit", limit)])
+            },
+            callback,
+        )
+    }
+
+    pub fn create_correspondence_game<F>(
+        &self,
+        body: CorrespondenceBody,
+        callback: F,
+    ) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<PrivateRoomInfo>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client
+                    .post(format!("{server_url}/correspondence"))
+                    .json(&body)
+            },
+            callback,
+        )
+    }
+
+    pub fn list_correspondence_games<F>(&self, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<CorrespondenceGameList>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            |client, server_url| client.get(format!("{server_url}/correspondence")),
+            callback,
+        )
+    }
+
+    pub fn list_live_games<F>(&self, page: i64, limit: i64, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<LiveGameList>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client
+                    .get(format!("{server_url}/room/live"))
+                    .query(&[("page", page), ("limit", limit)])
+            },
+            callback,
+        )
+    }
+
+    pub fn get_room<F>(&self, uuid: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<PrivateRoomInfo>) + Send + 'static,
+    {
+        let uuid = uuid.into();
+        self.spawn_authenticated_request(
+            move |client, server_url| client.get(format!("{server_url}/room/{uuid}")),
+            callback,
+        )
+    }
+
+    /// The profile of the given user, or of the logged in one if None.
+    pub fn get_user_profile<F>(&self, username: Option<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<UserProfile>) + Send + 'static,
+    {
+        let user = get_user_path(username);
+        self.spawn_authenticated_request(
+            move |client, server_url| client.get(format!("{server_url}/user/{user}")),
+            callback,
+        )
+    }
+
+    /// Finished games of the given user, or of the logged in one if None.
+    pub fn get_game_history<F>(
+        &self,
+        username: Option<String>,
+        page: i64,
+        limit: i64,
+        callback: F,
+    ) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<GameHistory>) + Send + 'static,
+    {
+        let user = get_user_path(username);
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client
+                    .get(format!("{server_url}/user/{user}/games"))
+                    .query(&[("page", page), ("limit", limit)])
+            },
+            callback,
+        )
+    }
+
+    pub fn get_game_record<F>(&self, uuid: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<GameRecord>) + Send + 'static,
+    {
+        let uuid = uuid.into();
+        self.spawn_authenticated_request(
+            move |client, server_url| client.get(format!("{server_url}/game/{uuid}")),
+            callback,
+        )
+    }
+
+    pub fn join_game<F>(&self, uuid: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<PrivateRoomInfo>) + Send + 'static,
+    {
+        let body = UuidBody { uuid: uuid.into() };
+        self.spawn_authenticated_request(
+            move |client, server_url| client.post(format!("{server_url}/room/join")).json(&body),
+            callback,
+        )
+    }
+
+    pub fn leave_game<F>(&self, uuid: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        let body = UuidBody { uuid: uuid.into() };
+        self.spawn_authenticated_request(
+            move |client, server_url| client.post(format!("{server_url}/room/leave")).json(&body),
+            callback,
+        )
+    }
+
+    pub fn join_queue<F>(&self, body: QueueBody, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| client.post(format!("{server_url}/queue")).json(&body),
+            callback,
+        )
+    }
+
+    pub fn get_queue_status<F>(&self, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<QueueStatusResponse>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            |client, server_url| client.get(format!("{server_url}/queue")),
+            callback,
+        )
+    }
+
+    pub fn leave_queue<F>(&self, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            |client, server_url| client.delete(format!("{server_url}/queue")),
+            callback,
+        )
+    }
+
+    /// Friends with their presence and pending friend requests.
+    pub fn get_leaderboard<F>(
+        &self,
+        category: RatingCategory,
+        page: i64,
+        limit: i64,
+        callback: F,
+    ) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<Leaderboard>) + Send + 'static,
+    {
+        let query = LeaderboardQuery {
+            category,
+            page,
+            limit,
+        };
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client
+                    .get(format!("{server_url}/leaderboard"))
+                    .query(&query)
+            },
+            callback,
+        )
+    }
+
+    pub fn get_friends<F>(&self, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<FriendList>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            |client, server_url| client.get(format!("{server_url}/friends")),
+            callback,
+        )
+    }
+
+    /// Sends a friend request, or accepts it if the other user already sent one.
+    pub fn add_friend<F>(&self, username: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        let body = UsernameBody {
+            username: username.into(),
+        };
+        self.spawn_authenticated_request(
+            move |client, server_url| client.post(format!("{server_url}/friends")).json(&body),
+            callback,
+        )
+    }
+
+    /// Removes a friend, declines their request or withdraws an own request.
+    pub fn remove_friend<F>(&self, username: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        let username = username.into();
+        self.spawn_authenticated_request(
+            move |client, server_url| client.delete(format!("{server_url}/friends/{username}")),
+            callback,
+        )
+    }
+
+    pub fn get_challenges<F>(&self, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<ChallengeList>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            |client, server_url| client.get(format!("{server_url}/challenges")),
+            callback,
+        )
+    }
+
+    pub fn send_challenge<F>(&self, body: ChallengeBody, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<Challenge>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| client.post(format!("{server_url}/challenges")).json(&body),
+            callback,
+        )
+    }
+
+    /// Seats both players in a new room, which is returned.
+    pub fn accept_challenge<F>(&self, uuid: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<PrivateRoomInfo>) + Send + 'static,
+    {
+        let uuid = uuid.into();
+        self.spawn_authenticated_request(
+            move |client, server_url| client.post(format!("{server_url}/challenges/{uuid}/accept")),
+            callback,
+        )
+    }
+
+    /// Declines an incoming challenge or withdraws an own one.
+    pub fn decline_challenge<F>(&self, uuid: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        let uuid = uuid.into();
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client.post(format!("{server_url}/challenges/{uuid}/decline"))
+            },
+            callback,
+        )
+    }
+
+    pub fn list_tournaments<F>(&self, page: i64, limit: i64, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<TournamentList>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client
+                    .get(format!("{server_url}/tournaments"))
+                    .query(&[("page", page), ("limit", limit)])
+            },
+            callback,
+        )
+    }
+
+    /// The standings and pairings of a tournament, including the running game of the user.
+    pub fn get_tournament<F>(&self, uuid: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<Tournament>) + Send + 'static,
+    {
+        let uuid = uuid.into();
+        self.spawn_authenticated_request(
+            move |client, server_url| client.get(format!("{server_url}/tournaments/{uuid}")),
+            callback,
+        )
+    }
+
+    pub fn join_tournament<F>(&self, uuid: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        let uuid = uuid.into();
+        self.spawn_authenticated_request(
+            move |client, server_url| client.post(format!("{server_url}/tournaments/{uuid}/join")),
+            callback,
+        )
+    }
+
+    /// Withdrawn players aren't paired anymore, a running game has to be finished separately.
+    pub fn withdraw_from_tournament<F>(&self, uuid: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        let uuid = uuid.into();
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client.post(format!("{server_url}/tournaments/{uuid}/withdraw"))
+            },
+            callback,
+        )
+    }
+
+    /// The logged in user including the role, which decides about admin features.
+    pub fn get_account<F>(&self, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<AccountInfo>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            |client, server_url| client.get(format!("{server_url}/account")),
+            callback,
+        )
+    }
+
+    /// A wrong current password is reported as a validation error of the `password` field.
+    pub fn change_password<F>(&self, body: ChangePasswordBody, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client
+                    .post(format!("{server_url}/account/password"))
+                    .json(&body)
+            },
+            callback,
+        )
+    }
+
+    /// The token stays valid, taken usernames are rejected with `ApiError::Collision`.
+    pub fn change_username<F>(&self, body: ChangeUsernameBody, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<AccountInfo>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client
+                    .post(format!("{server_url}/account/username"))
+                    .json(&body)
+            },
+            callback,
+        )
+    }
+
+    /// Deletes the account for good, every token of it is invalidated.
+    pub fn delete_account<F>(&self, body: DeleteAccountBody, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| client.delete(format!("{server_url}/account")).json(&body),
+            callback,
+        )
+    }
+
+    /// Only available to admins, other users get `ApiError::Forbidden`.
+    pub fn list_invite_codes<F>(&self, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<InviteCodeList>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            |client, server_url| client.get(format!("{server_url}/invite-codes")),
+            callback,
+        )
+    }
+
+    pub fn create_invite_code<F>(&self, body: InviteCodeBody, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<InviteCode>) + Send + 'static,
+    {
+        self.spawn_authenticated_request(
+            move |client, server_url| {
+                client
+                    .post(format!("{server_url}/invite-codes"))
+                    .json(&body)
+            },
+            callback,
+        )
+    }
+
+    /// Revoked codes stay listed but can't be used for registering anymore.
+    pub fn revoke_invite_code<F>(&self, code: impl Into<String>, callback: F) -> RequestHandle
+    where
+        F: FnOnce(ApiResult<MessageResponse>) + Send + 'static,
+    {
+        let code = code.into();
+        self.spawn_authenticated_request(
+            move |client, server_url| client.delete(format!("{server_url}/invite-codes/{code}")),
+            callback,
+        )
+    }
+}
+
+async fn execute_request<T>(request: RequestBuilder) -> ApiResult<T>
+where
+    T: DeserializeOwned,
+{
+    match request.send().await {
+        Ok(response) if response.status().is_success() => match response.json::<T>().await {
+            Ok(data) => Ok(data),
+            Err(error) => Err(error.into()),
+        },
+        Ok(response) => match response.status() {
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+                let body = response.text().await.unwrap_or_default();
+                match FieldErrors::parse(&body) {
+                    Some(field_errors) => Err(ApiError::Validation(field_errors)),
+                    None => Err(ApiError::BadRequest(body)),
+                }
+            }
+            StatusCode::CONFLICT => Err(ApiError::Collision(
+                response.text().await.unwrap_or_default(),
+            )),
+            StatusCode::NOT_FOUND => Err(ApiError::NotFound(
+                response.text().await.unwrap_or_default(),
+            )),
+            StatusCode::FORBIDDEN => Err(ApiError::Forbidden(
+                response.text().await.unwrap_or_default(),
+            )),
+            StatusCode::UNAUTHORIZED => Err(ApiError::Unauthorized(
+                response.text().await.unwrap_or_default(),
+            )),
+            StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimited {
+                retry_after: parse_retry_after(response.headers()),
+                message: response.text().await.unwrap_or_default(),
+            }),
+            StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => Err(ApiError::ServiceUnavailable {
+                retry_after: parse_retry_after(response.headers()),
+                message: response.text().await.unwrap_or_default(),
+            }),
+            _ => Err(ApiError::Unexpected(format!(
+                "[{}]: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ))),
+        },
+        Err(error) => {
+            let error_source = error
+                .source()
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| error.to_string());
+
+            // Timeouts are also request errors, so they have to be checked first
+            if error.is_builder() {
+                Err(ApiError::InvalidServerUrl)
+            } else if error.is_timeout() {
+                Err(ApiError::ConnectionTimeout)
+            } else if error.is_request() || error.is_connect() {
+                Err(ApiError::Connection(format!(
+                    "{error} (source: {error_source})"
+                )))
+            } else if error.is_body() || error.is_decode() {
+                Err(ApiError::Communication(format!(
+                    "{error} (source: {error_source})"
+                )))
+            } else {
+                Err(error.into())
+            }
+        }
+    }
+}
+
+async fn execute_authenticated_request<T, B>(
+    client: &Client,
+    server_url: &str,
+    session: &Shared<LoginState>,
+    mut current: Session,
+    build_request: B,
+    retry_policy: RetryPolicy,
+    retry_status: &Shared<Option<RetryStatus>>,
+) -> ApiResult<T>
+where
+    T: DeserializeOwned,
+    B: Fn(&Client, &str) -> RequestBuilder,
+{
+    if current.is_expiring() && current.refresh_token.is_some() {
+        current = refresh_session(client, server_url, session, &current).await?;
+    }
+
+    let request = build_request(client, server_url).bearer_auth(&current.token);
+    match execute_with_retries(request, retry_policy, retry_status).await {
+        Err(ApiError::Unauthorized(message)) => {
+            if current.refresh_token.is_none() {
+                expire_session(session, &current);
+                return Err(ApiError::Unauthorized(message));
+            }
+
+            let refreshed = refresh_session(client, server_url, session, &current).await?;
+            let request = build_request(client, server_url).bearer_auth(&refreshed.token);
+            execute_with_retries(request, retry_policy, retry_status).await
+        }
+        result => result,
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Exchanges the refresh token of a remembered session for a new token, expiring the session if
+/// it was rejected. The login state is left alone if the user logged out or switched accounts
+/// while the request was running.
+async fn refresh_session(
+    client: &Client,
+    server_url: &str,
+    session: &Shared<LoginState>,
+    current: &Session,
+) -> ApiResult<Session> {
+    let Some(refresh_token) = current.refresh_token.clone() else {
+        return Err(ApiError::Unauthorized(
+            "The session can't be refreshed".to_string(),
+        ));
+    };
+    info!(
+        "Refreshing session of {}",
+        current.username.as_deref().unwrap_or("unknown user")
+    );
+    let request = client
+        .post(format!("{server_url}/refresh"))
+        .json(&RefreshBody { refresh_token });
+    let result = execute_request::<LoginResponse>(request).await;
+
+    session.with(|login_state| {
+        let is_current = matches!(
+            login_state,
+            LoginState::Success(active) if active.refresh_token == current.refresh_token
+        );
+        match result {
+            Ok(response) if is_current => {
+                let refreshed = current.refreshed(response);
+                *login_state = LoginState::Success(refreshed.clone());
+                Ok(refreshed)
+            }
+            Ok(_) => Err(ApiError::Unauthorized(
+                "The session ended while it was refreshed".to_string(),
+            )),
+            Err(error) => {
+                if is_current && matches!(error, ApiError::Unauthorized(_)) {
+                    *login_state = LoginState::Expired;
+                }
+                Err(error)
+            }
+        }
+    })
+}
+
+/// Marks the session as expired unless the user logged out or in again in the meantime.
+fn expire_session(session: &Shared<LoginState>, current: &Session) {
+    session.with(|login_state| {
+        if matches!(login_state, LoginState::Success(active) if active.token == current.token) {
+            *login_state = LoginState::Expired;
+        }
+    });
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MultiplayerClientPersist {
     pub server_url: Option<String>,
+    pub live_transport: LiveTransport,
+    pub server_profiles: Vec<ServerProfile>,
+    pub retry_policy: RetryPolicy,
+    pub network_settings: NetworkSettings,
 }
 
 impl PersistentObject for MultiplayerClient {
@@ -178,13 +1202,16 @@ impl PersistentObject for MultiplayerClient {
     fn save_state(&self) -> Self::PersistentType {
         MultiplayerClientPersist {
             server_url: self.server_url.clone(),
+            live_transport: self.live_transport,
+            server_profiles: self.server_profiles.clone(),
+            retry_policy: self.retry_policy,
+            network_settings: self.network_settings.clone(),
         }
     }
 
     fn load_from_state(state: Self::PersistentType) -> Self {
-        Self {
-            server_url: state.server_url,
-            ..Default::default()
-        }
+        let mut client = Self::default();
+        client.apply_settings(state);
+        client
     }
 }
@@ -0,0 +1,65 @@
+use crate::api::matchmaking::ColorPreference;
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use giga_chess_api_types::response::user_info::PublicUserInfo;
+use serde::{Deserialize, Serialize};
+
+pub const DAYS_PER_MOVE_PRESETS: [u32; 5] = [1, 2, 3, 5, 7];
+pub const MICROS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000;
+
+/// Creates a game in which every move has to be made within a number of days.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrespondenceBody {
+    pub name: Option<String>,
+    pub public: bool,
+    pub days_per_move: u32,
+    pub color: ColorPreference,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorrespondenceGame {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub public: bool,
+    pub white: Option<PublicUserInfo>,
+    pub black: Option<PublicUserInfo>,
+    pub days_per_move: u32,
+    pub move_count: u32,
+    /// None while waiting for an opponent.
+    pub player_to_move: Option<String>,
+    /// Unix timestamp in seconds until which the player to move has to move.
+    pub deadline: Option<i64>,
+}
+
+impl CorrespondenceGame {
+    pub fn is_started(&self) -> bool {
+        self.white.is_some() && self.black.is_some()
+    }
+
+    pub fn is_turn_of(&self, username: &str) -> bool {
+        self.player_to_move.as_deref() == Some(username)
+    }
+
+    pub fn get_opponent(&self, username: &str) -> Option<&PublicUserInfo> {
+        [&self.white, &self.black]
+            .into_iter()
+            .flatten()
+            .find(|player| player.name != username)
+    }
+
+    /// The room the live connection is opened for when the game is picked up.
+    pub fn to_room_info(&self) -> PrivateRoomInfo {
+        PrivateRoomInfo {
+            uuid: self.uuid.clone(),
+            name: self.name.clone(),
+            public: self.public,
+            white: self.white.clone(),
+            black: self.black.clone(),
+        }
+    }
+}
+
+/// The running correspondence games of the user, including ones still waiting for an opponent.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorrespondenceGameList {
+    pub games: Vec<CorrespondenceGame>,
+}
@@ -1,21 +1,37 @@
+use crate::api::error::field_errors::FieldErrors;
+use std::time::Duration;
 use thiserror::Error;
 
+pub mod field_errors;
+
 pub type ApiResult<T> = Result<T, ApiError>;
 
 #[derive(Debug, Error)]
 pub enum ApiError {
     #[error("Bad request: {0}")]
     BadRequest(String),
+    #[error("Invalid input: {0}")]
+    Validation(FieldErrors),
     #[error("Collision: {0}")]
     Collision(String),
     #[error("Not found: {0}")]
     NotFound(String),
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
     #[error("Unexpected error: {0}")]
     Unexpected(String),
-    #[error("Rate limited: {0}")]
-    RateLimited(String),
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("Service unavailable: {message}")]
+    ServiceUnavailable {
+        message: String,
+        retry_after: Option<Duration>,
+    },
     #[error("Reqwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
     #[error("Missing server url")]
@@ -29,3 +45,29 @@ pub enum ApiError {
     #[error("Connection timeout")]
     ConnectionTimeout,
 }
+
+impl ApiError {
+    /// Answers the server gave on purpose, e.g. a missing room or a taken name. Callers handle
+    /// them, so they aren't worth a warning.
+    pub fn is_expected(&self) -> bool {
+        matches!(
+            self,
+            Self::BadRequest(_)
+                | Self::Validation(_)
+                | Self::Collision(_)
+                | Self::NotFound(_)
+                | Self::Unauthorized(_)
+                | Self::Forbidden(_)
+                | Self::RateLimited { .. }
+        )
+    }
+
+    /// How long the server asked to wait before trying again, if it did.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after, .. }
+            | Self::ServiceUnavailable { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
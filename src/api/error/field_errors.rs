@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Validation errors of a rejected request body, keyed by the name of the offending field.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldErrors(BTreeMap<String, Vec<String>>);
+
+impl FieldErrors {
+    /// Parses the errors of the `validator` crate, either as the whole body or nested in an
+    /// `errors` or `fields` object. Returns None if the body contains no field errors.
+    pub fn parse(body: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(body).ok()?;
+        let object = value.as_object()?;
+        let nested = ["errors", "fields"]
+            .iter()
+            .find_map(|key| object.get(*key)?.as_object());
+        // Top-level strings are usually a general message rather than a field error
+        let fields = nested.unwrap_or(object);
+        let allow_plain_messages = nested.is_some();
+
+        let mut errors = BTreeMap::new();
+        for (field, value) in fields {
+            let messages: Vec<String> = match value {
+                Value::Array(items) => items.iter().filter_map(describe_error).collect(),
+                Value::String(message) if allow_plain_messages => vec![message.clone()],
+                _ => Vec::new(),
+            };
+            if !messages.is_empty() {
+                errors.insert(field.clone(), messages);
+            }
+        }
+
+        (!errors.is_empty()).then_some(Self(errors))
+    }
+
+    pub fn get(&self, field: &str) -> Option<&[String]> {
+        self.0.get(field).map(Vec::as_slice)
+    }
+
+    /// Forgets the errors of a field, e.g. once the user edited it.
+    pub fn remove(&mut self, field: &str) {
+        self.0.remove(field);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for FieldErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<String> = self
+            .0
+            .iter()
+            .map(|(field, messages)| format!("{field}: {}", messages.join(" ")))
+            .collect();
+        write!(f, "{}", errors.join(", "))
+    }
+}
+
+/// Errors are either plain messages or objects with a code, an optional message and parameters.
+fn describe_error(value: &Value) -> Option<String> {
+    match value {
+        Value::String(message) => Some(message.clone()),
+        Value::Object(error) => {
+            if let Some(message) = error.get("message").and_then(Value::as_str) {
+                return Some(message.to_string());
+            }
+            let code = error.get("code")?.as_str()?;
+            Some(describe_code(
+                code,
+                error.get("params").and_then(Value::as_object),
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn describe_code(code: &str, params: Option<&Map<String, Value>>) -> String {
+    let param = |name: &str| params?.get(name)?.as_i64();
+    match (code, param("min"), param("max")) {
+        ("length", Some(min), Some(max)) => {
+            format!("Must be between {min} and {max} characters long.")
+        }
+        ("length", Some(min), None) => format!("Must be at least {min} characters long."),
+        ("length", None, Some(max)) => format!("Must be at most {max} characters long."),
+        ("range", Some(min), Some(max)) => format!("Must be between {min} and {max}."),
+        ("range", Some(min), None) => format!("Must be at least {min}."),
+        ("range", None, Some(max)) => format!("Must be at most {max}."),
+        ("email", _, _) => "Must be a valid email address.".to_string(),
+        ("required", _, _) => "Is required.".to_string(),
+        // Custom validators use a readable description as their code, e.g. "must be alphanumeric"
+        (code, _, _) if code.contains(' ') => {
+            let mut chars = code.chars();
+            let first = chars.next().map(|c| c.to_uppercase().collect::<String>());
+            format!("{}{}.", first.unwrap_or_default(), chars.as_str())
+        }
+        (code, _, _) => format!("Is invalid ({code})."),
+    }
+}
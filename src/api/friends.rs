@@ -0,0 +1,114 @@
+use crate::api::matchmaking::{ColorPreference, TimeControl};
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use giga_chess_api_types::response::user_info::PublicUserInfo;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Presence {
+    #[default]
+    Offline,
+    Online,
+    /// Online and in a running game.
+    Playing,
+}
+
+impl Presence {
+    pub fn is_online(&self) -> bool {
+        *self != Self::Offline
+    }
+}
+
+impl fmt::Display for Presence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Offline => write!(f, "Offline"),
+            Self::Online => write!(f, "Online"),
+            Self::Playing => write!(f, "Playing"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Friend {
+    pub name: String,
+    #[serde(default)]
+    pub presence: Presence,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FriendList {
+    pub friends: Vec<Friend>,
+    /// Users who want to become friends with the logged in user.
+    pub incoming_requests: Vec<PublicUserInfo>,
+    pub outgoing_requests: Vec<PublicUserInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsernameBody {
+    pub username: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ChallengeStatus {
+    Pending,
+    /// Both players were seated in the room, the game starts once they connect.
+    Accepted {
+        room: PrivateRoomInfo,
+    },
+    Declined,
+}
+
+/// A direct game invitation from one user to another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Challenge {
+    pub uuid: String,
+    pub challenger: PublicUserInfo,
+    pub challenged: PublicUserInfo,
+    pub time_micros: Option<i64>,
+    pub increment_micros: Option<i64>,
+    /// The colour the challenger asked for.
+    pub color: ColorPreference,
+    #[serde(flatten)]
+    pub status: ChallengeStatus,
+}
+
+impl Challenge {
+    pub fn time_control(&self) -> Option<TimeControl> {
+        TimeControl::from_micros(self.time_micros, self.increment_micros)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeBody {
+    pub username: String,
+    pub time_micros: Option<i64>,
+    pub increment_micros: Option<i64>,
+    pub color: ColorPreference,
+}
+
+impl ChallengeBody {
+    pub fn new(
+        username: impl Into<String>,
+        time_control: Option<TimeControl>,
+        color: ColorPreference,
+    ) -> Self {
+        Self {
+            username: username.into(),
+            time_micros: time_control.map(|time_control| time_control.time_micros()),
+            increment_micros: time_control.and_then(|time_control| time_control.increment_micros()),
+            color,
+        }
+    }
+}
+
+/// Pending challenges for the user and the ones sent by the user, which includes answered ones.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChallengeList {
+    pub incoming: Vec<Challenge>,
+    pub outgoing: Vec<Challenge>,
+}
@@ -0,0 +1,57 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// Options of a new invite code, every limit is optional.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InviteCodeBody {
+    pub expires_in_days: Option<u32>,
+    pub max_uses: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InviteCodeStatus {
+    Active,
+    Revoked,
+    Expired,
+    UsedUp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InviteCode {
+    /// The UUID which is entered on registration.
+    pub code: String,
+    pub created_by: String,
+    /// Unix timestamps in seconds.
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+    pub revoked: bool,
+}
+
+impl InviteCode {
+    pub fn get_status(&self) -> InviteCodeStatus {
+        if self.revoked {
+            InviteCodeStatus::Revoked
+        } else if self
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now().timestamp())
+        {
+            InviteCodeStatus::Expired
+        } else if self.max_uses.is_some_and(|max_uses| self.uses >= max_uses) {
+            InviteCodeStatus::UsedUp
+        } else {
+            InviteCodeStatus::Active
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.get_status() == InviteCodeStatus::Active
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InviteCodeList {
+    /// The newest code first.
+    pub codes: Vec<InviteCode>,
+}
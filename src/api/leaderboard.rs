@@ -0,0 +1,29 @@
+use crate::api::player_profile::RatingCategory;
+use giga_chess_api_types::general::pagination::Pagination;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    /// Starts at 1 for the highest rated player.
+    pub rank: i64,
+    pub name: String,
+    pub rating: i32,
+    pub games: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardQuery {
+    pub category: RatingCategory,
+    pub page: i64,
+    pub limit: i64,
+}
+
+/// Players ranked by their rating in one category, only players with rated games are listed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub category: RatingCategory,
+    pub entries: Vec<LeaderboardEntry>,
+    pub pagination: Pagination,
+    /// The entry of the requesting user, None if they haven't played in this category.
+    pub own_entry: Option<LeaderboardEntry>,
+}
@@ -0,0 +1,314 @@
+use crate::api::error::{ApiError, ApiResult};
+use crate::api::login::LoginState;
+use crate::api::{expire_session, refresh_session, MultiplayerClient};
+use crate::types::shared::Shared;
+use log::{info, warn};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use url::Url;
+
+mod polling;
+mod websocket;
+
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+/// Longer chat messages are rejected by the server, counted in characters.
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 200;
+
+/// How the live connection talks to the server. Auto prefers WebSockets and falls back to
+/// long polling if the socket can't be opened, e.g. because a proxy blocks it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveTransport {
+    #[default]
+    Auto,
+    WebSocket,
+    Polling,
+}
+
+impl LiveTransport {
+    pub const ALL: [Self; 3] = [Self::Auto, Self::WebSocket, Self::Polling];
+}
+
+impl fmt::Display for LiveTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => write!(f, "Automatic"),
+            Self::WebSocket => write!(f, "WebSocket"),
+            Self::Polling => write!(f, "Long polling"),
+        }
+    }
+}
+
+/// Remaining clock times as reported by the server, None for untimed games.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockUpdate {
+    pub white_time_left_micros: Option<i64>,
+    pub black_time_left_micros: Option<i64>,
+}
+
+/// A chat message in a game room, `sent_at` is a unix timestamp in seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub from: String,
+    pub text: String,
+    pub sent_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Move { uci: String },
+    Resign,
+    Chat { text: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// The full move list and chat, sent after connecting and whenever the client got out of sync.
+    Sync {
+        moves: Vec<String>,
+        clock: ClockUpdate,
+        #[serde(default)]
+        chat: Vec<ChatMessage>,
+        /// Set for correspondence games, the clock then shows the time until the move deadline.
+        #[serde(default)]
+        days_per_move: Option<u32>,
+    },
+    Move {
+        uci: String,
+        clock: ClockUpdate,
+    },
+    Clock {
+        clock: ClockUpdate,
+    },
+    /// The result in PGN notation, e.g. `1-0`, and a human-readable reason.
+    GameEnd {
+        result: String,
+        reason: String,
+    },
+    Chat(ChatMessage),
+    /// How many users watch the game, sent after connecting and whenever it changes.
+    Spectators {
+        count: u32,
+    },
+    Error {
+        message: String,
+    },
+    /// The sender has to wait before the server accepts further messages.
+    RateLimited {
+        message: String,
+        retry_after_secs: Option<u64>,
+    },
+}
+
+/// Messages newer than the requested cursor, the server holds the request until there are any.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollResponse {
+    pub cursor: u64,
+    pub messages: Vec<ServerMessage>,
+}
+
+#[derive(Debug, Clone)]
+pub enum LiveEvent {
+    Connected,
+    Disconnected {
+        retry_in: Duration,
+    },
+    Message(ServerMessage),
+    /// The connection was given up on, e.g. because the session expired.
+    Closed(String),
+}
+
+/// A WebSocket connection to a game room which reconnects with exponential backoff.
+/// The connection is closed once this handle is dropped.
+#[derive(Debug)]
+pub struct LiveConnection {
+    outgoing: UnboundedSender<ClientMessage>,
+    incoming: mpsc::Receiver<LiveEvent>,
+}
+
+impl LiveConnection {
+    pub(super) fn open<N>(
+        api: &MultiplayerClient,
+        server_url: &str,
+        room_uuid: &str,
+        notify: N,
+    ) -> ApiResult<Self>
+    where
+        N: Fn() + Send + Sync + 'static,
+    {
+        let websocket_url = get_websocket_url(server_url, room_uuid)?;
+        let (outgoing, outgoing_receiver) = unbounded_channel();
+        let (event_sender, incoming) = mpsc::channel();
+
+        let task = LiveTask {
+            client: api.client.clone(),
+            server_url: server_url.to_string(),
+            room_uuid: room_uuid.to_string(),
+            websocket_url,
+            transport: api.get_effective_live_transport(),
+            connect_timeout: api.retry_policy.connect_timeout(),
+            tls_connector: api.tls_connector.clone(),
+            session: api.session.clone(),
+            events: event_sender,
+            notify: Box::new(notify),
+        };
+        api.runtime.spawn(task.run(outgoing_receiver));
+
+        Ok(Self { outgoing, incoming })
+    }
+
+    pub fn send(&self, message: ClientMessage) {
+        if self.outgoing.send(message).is_err() {
+            warn!("Tried to send a message over a closed live connection");
+        }
+    }
+
+    /// Returns all events received since the last call without blocking.
+    pub fn poll_events(&self) -> Vec<LiveEvent> {
+        self.incoming.try_iter().collect()
+    }
+}
+
+/// Converts the HTTP server url to the WebSocket url of the given room.
+fn get_websocket_url(server_url: &str, room_uuid: &str) -> ApiResult<Url> {
+    let mut url = Url::parse(server_url).map_err(|_| ApiError::InvalidServerUrl)?;
+    let scheme = match url.scheme() {
+        "https" => "wss",
+        _ => "ws",
+    };
+    url.set_scheme(scheme)
+        .map_err(|_| ApiError::InvalidServerUrl)?;
+    url.path_segments_mut()
+        .map_err(|_| ApiError::InvalidServerUrl)?
+        .pop_if_empty()
+        .extend(["room", room_uuid, "live"]);
+    Ok(url)
+}
+
+/// Carried across reconnects and reset once a connection succeeds.
+struct Reconnect {
+    delay: Duration,
+    /// The session is refreshed at most once until the next successful connection.
+    refreshed: bool,
+}
+
+enum ConnectionEnd {
+    /// The handle was dropped, nobody is interested in the game anymore.
+    Dropped,
+    /// The connection could not be established in the first place.
+    Unavailable(String),
+    Lost(String),
+    Rejected(String),
+}
+
+struct LiveTask {
+    client: Client,
+    server_url: String,
+    room_uuid: String,
+    websocket_url: Url,
+    transport: LiveTransport,
+    connect_timeout: Duration,
+    tls_connector: Option<native_tls::TlsConnector>,
+    session: Shared<LoginState>,
+    events: mpsc::Sender<LiveEvent>,
+    notify: Box<dyn Fn() + Send + Sync>,
+}
+
+impl LiveTask {
+    fn emit(&self, event: LiveEvent) -> bool {
+        let delivered = self.events.send(event).is_ok();
+        (self.notify)();
+        delivered
+    }
+
+    /// Resets the backoff and tells the app, returns false if nobody is listening anymore.
+    fn on_connected(&self, reconnect: &mut Reconnect) -> bool {
+        reconnect.delay = INITIAL_RECONNECT_DELAY;
+        reconnect.refreshed = false;
+        self.emit(LiveEvent::Connected)
+    }
+
+    fn get_token(&self) -> Option<String> {
+        self.session
+            .lock()
+            .get_session()
+            .map(|session| session.token.clone())
+    }
+
+    async fn run(self, mut outgoing: UnboundedReceiver<ClientMessage>) {
+        let mut reconnect = Reconnect {
+            delay: INITIAL_RECONNECT_DELAY,
+            refreshed: false,
+        };
+        let mut use_polling = self.transport == LiveTransport::Polling;
+
+        loop {
+            let end = if use_polling {
+                polling::connect(&self, &mut outgoing, &mut reconnect).await
+            } else {
+                websocket::connect(&self, &mut outgoing, &mut reconnect).await
+            };
+
+            match end {
+                ConnectionEnd::Dropped => return,
+                ConnectionEnd::Unavailable(reason)
+                    if self.transport == LiveTransport::Auto && !use_polling =>
+                {
+                    info!("WebSocket unavailable ({reason}), falling back to long polling");
+                    use_polling = true;
+                }
+                ConnectionEnd::Rejected(reason) => {
+                    let current = self.session.lock().get_session().cloned();
+                    match current {
+                        Some(current) if current.refresh_token.is_none() => {
+                            expire_session(&self.session, &current);
+                        }
+                        Some(current) if !reconnect.refreshed => {
+                            reconnect.refreshed = true;
+                            let refresh_result = refresh_session(
+                                &self.client,
+                                &self.server_url,
+                                &self.session,
+                                &current,
+                            )
+                            .await;
+                            if refresh_result.is_ok() {
+                                continue;
+                            }
+                        }
+                        // Already refreshed once or logged out in the meantime
+                        _ => {}
+                    }
+                    self.emit(LiveEvent::Closed(reason));
+                    return;
+                }
+                ConnectionEnd::Unavailable(reason) | ConnectionEnd::Lost(reason) => {
+                    warn!("Live connection lost: {reason}");
+                    if !self.emit(LiveEvent::Disconnected {
+                        retry_in: reconnect.delay,
+                    }) {
+                        return;
+                    }
+
+                    tokio::select! {
+                        _ = tokio::time::sleep(reconnect.delay) => {}
+                        _ = outgoing_closed(&mut outgoing) => return,
+                    }
+                    reconnect.delay = (reconnect.delay * 2).min(MAX_RECONNECT_DELAY);
+                }
+            }
+        }
+    }
+}
+
+/// Resolves once the handle was dropped, discarding messages queued while disconnected.
+async fn outgoing_closed(outgoing: &mut UnboundedReceiver<ClientMessage>) {
+    while outgoing.recv().await.is_some() {}
+}
@@ -0,0 +1,114 @@
+use crate::api::error::{ApiError, ApiResult};
+use crate::api::execute_request;
+use crate::api::live::{
+    ClientMessage, ConnectionEnd, LiveEvent, LiveTask, PollResponse, Reconnect, ServerMessage,
+};
+use giga_chess_api_types::response::message::MessageResponse;
+use log::info;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// The server holds polls open until there are new messages, so they outlive the usual timeout.
+const POLL_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub(super) async fn connect(
+    task: &LiveTask,
+    outgoing: &mut UnboundedReceiver<ClientMessage>,
+    reconnect: &mut Reconnect,
+) -> ConnectionEnd {
+    // Without a cursor the server starts with a full sync
+    let mut cursor = None;
+    let mut connected = false;
+    let mut poll = Box::pin(poll_messages(task, cursor));
+
+    loop {
+        tokio::select! {
+            message = outgoing.recv() => {
+                let Some(message) = message else {
+                    return ConnectionEnd::Dropped;
+                };
+                match send_message(task, &message).await {
+                    Ok(_) => {}
+                    // Rejected moves are reported like the WebSocket does, the connection stays up
+                    Err(ApiError::BadRequest(message)) => {
+                        if !task.emit(LiveEvent::Message(ServerMessage::Error { message })) {
+                            return ConnectionEnd::Dropped;
+                        }
+                    }
+                    Err(ApiError::RateLimited { message, retry_after }) => {
+                        let retry_after_secs = retry_after.map(|retry_after| retry_after.as_secs());
+                        let rate_limited = ServerMessage::RateLimited { message, retry_after_secs };
+                        if !task.emit(LiveEvent::Message(rate_limited)) {
+                            return ConnectionEnd::Dropped;
+                        }
+                    }
+                    Err(error) => return get_connection_end(error, connected),
+                }
+            }
+            result = &mut poll => {
+                let response = match result {
+                    Ok(response) => response,
+                    Err(error) => return get_connection_end(error, connected),
+                };
+
+                if !connected {
+                    connected = true;
+                    info!("Connected to room {} via long polling", task.room_uuid);
+                    if !task.on_connected(reconnect) {
+                        return ConnectionEnd::Dropped;
+                    }
+                }
+
+                for message in response.messages {
+                    if !task.emit(LiveEvent::Message(message)) {
+                        return ConnectionEnd::Dropped;
+                    }
+                }
+                cursor = Some(response.cursor);
+                poll = Box::pin(poll_messages(task, cursor));
+            }
+        }
+    }
+}
+
+fn get_connection_end(error: ApiError, connected: bool) -> ConnectionEnd {
+    match error {
+        ApiError::Unauthorized(_) => {
+            ConnectionEnd::Rejected("The session was rejected".to_string())
+        }
+        error if connected => ConnectionEnd::Lost(error.to_string()),
+        error => ConnectionEnd::Unavailable(error.to_string()),
+    }
+}
+
+async fn poll_messages(task: &LiveTask, cursor: Option<u64>) -> ApiResult<PollResponse> {
+    let token = task
+        .get_token()
+        .ok_or_else(|| ApiError::Unauthorized("Not logged in".to_string()))?;
+
+    let mut request = task
+        .client
+        .get(format!(
+            "{}/room/{}/live/poll",
+            task.server_url, task.room_uuid
+        ))
+        .bearer_auth(token)
+        .timeout(POLL_TIMEOUT);
+    if let Some(cursor) = cursor {
+        request = request.query(&[("cursor", cursor)]);
+    }
+    execute_request(request).await
+}
+
+async fn send_message(task: &LiveTask, message: &ClientMessage) -> ApiResult<MessageResponse> {
+    let token = task
+        .get_token()
+        .ok_or_else(|| ApiError::Unauthorized("Not logged in".to_string()))?;
+
+    let request = task
+        .client
+        .post(format!("{}/room/{}/live", task.server_url, task.room_uuid))
+        .bearer_auth(token)
+        .json(message);
+    execute_request(request).await
+}
@@ -0,0 +1,85 @@
+use crate::api::live::{
+    ClientMessage, ConnectionEnd, LiveEvent, LiveTask, Reconnect, ServerMessage,
+};
+use futures_util::{SinkExt, StreamExt};
+use log::{info, warn};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderValue, StatusCode};
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::Connector;
+
+pub(super) async fn connect(
+    task: &LiveTask,
+    outgoing: &mut UnboundedReceiver<ClientMessage>,
+    reconnect: &mut Reconnect,
+) -> ConnectionEnd {
+    let Some(token) = task.get_token() else {
+        return ConnectionEnd::Rejected("Not logged in".to_string());
+    };
+
+    let mut request = match task.websocket_url.as_str().into_client_request() {
+        Ok(request) => request,
+        Err(error) => return ConnectionEnd::Rejected(error.to_string()),
+    };
+    match HeaderValue::from_str(&format!("Bearer {token}")) {
+        Ok(value) => request.headers_mut().insert("Authorization", value),
+        Err(error) => return ConnectionEnd::Rejected(error.to_string()),
+    };
+
+    let connector = task.tls_connector.clone().map(Connector::NativeTls);
+    let connecting =
+        tokio_tungstenite::connect_async_tls_with_config(request, None, false, connector);
+    let mut stream = match tokio::time::timeout(task.connect_timeout, connecting).await {
+        Ok(Ok((stream, _))) => stream,
+        Ok(Err(WsError::Http(response))) if response.status() == StatusCode::UNAUTHORIZED => {
+            return ConnectionEnd::Rejected("The session was rejected".to_string());
+        }
+        Ok(Err(error)) => return ConnectionEnd::Unavailable(error.to_string()),
+        Err(_) => return ConnectionEnd::Unavailable("Connection timed out".to_string()),
+    };
+
+    info!("Connected to {} via WebSocket", task.websocket_url);
+    if !task.on_connected(reconnect) {
+        return ConnectionEnd::Dropped;
+    }
+
+    loop {
+        tokio::select! {
+            message = outgoing.recv() => {
+                let Some(message) = message else {
+                    let _ = stream.close(None).await;
+                    return ConnectionEnd::Dropped;
+                };
+                let text = match serde_json::to_string(&message) {
+                    Ok(text) => text,
+                    Err(error) => {
+                        warn!("Failed to serialize live message: {error}");
+                        continue;
+                    }
+                };
+                if let Err(error) = stream.send(Message::text(text)).await {
+                    return ConnectionEnd::Lost(error.to_string());
+                }
+            }
+            message = stream.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => {
+                        return ConnectionEnd::Lost("Closed by the server".to_string());
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(error)) => return ConnectionEnd::Lost(error.to_string()),
+                };
+                match serde_json::from_str::<ServerMessage>(&text) {
+                    Ok(message) => {
+                        if !task.emit(LiveEvent::Message(message)) {
+                            return ConnectionEnd::Dropped;
+                        }
+                    }
+                    Err(error) => warn!("Received invalid live message: {error}"),
+                }
+            }
+        }
+    }
+}
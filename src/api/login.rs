@@ -0,0 +1,81 @@
+use crate::api::error::field_errors::FieldErrors;
+use crate::api::session::Session;
+use serde::{Deserialize, Serialize};
+use std::cmp::PartialEq;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum LoginStateStatus {
+    #[default]
+    Idle,
+    Loading,
+    Success,
+    Error,
+    Expired,
+}
+
+impl LoginStateStatus {
+    pub fn is_not_successful_nor_loading(&self) -> bool {
+        *self != Self::Loading && *self != Self::Success
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub enum LoginState {
+    #[default]
+    Idle,
+    Loading,
+    Success(Session),
+    Error(String),
+    /// The server rejected some of the entered values, shown next to the matching fields.
+    Invalid(FieldErrors),
+    /// The server rejected the persisted token, the user has to log in again.
+    Expired,
+}
+
+impl LoginState {
+    pub fn get_status(&self) -> LoginStateStatus {
+        match self {
+            Self::Idle => LoginStateStatus::Idle,
+            Self::Loading => LoginStateStatus::Loading,
+            Self::Success(_) => LoginStateStatus::Success,
+            Self::Error(_) | Self::Invalid(_) => LoginStateStatus::Error,
+            Self::Expired => LoginStateStatus::Expired,
+        }
+    }
+
+    pub fn get_session(&self) -> Option<&Session> {
+        match self {
+            Self::Success(session) => Some(session),
+            _ => None,
+        }
+    }
+
+    pub fn get_error(&self) -> Option<&str> {
+        match self {
+            Self::Error(error) => Some(error),
+            Self::Invalid(_) => Some("Please correct the marked fields."),
+            _ => None,
+        }
+    }
+
+    pub fn get_field_errors(&self) -> Option<&FieldErrors> {
+        match self {
+            Self::Invalid(field_errors) => Some(field_errors),
+            _ => None,
+        }
+    }
+
+    /// Forgets the server's complaint about a field once the user edited it.
+    pub fn clear_field_error(&mut self, field: &str) {
+        if let Self::Invalid(field_errors) = self {
+            field_errors.remove(field);
+            if field_errors.is_empty() {
+                *self = Self::Idle;
+            }
+        }
+    }
+
+    pub fn error(error: impl Into<String>) -> Self {
+        Self::Error(error.into())
+    }
+}
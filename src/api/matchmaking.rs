@@ -0,0 +1,144 @@
+use giga_chess_api_types::body::room_creation::RoomCreationBody;
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorPreference {
+    #[default]
+    Random,
+    White,
+    Black,
+}
+
+impl ColorPreference {
+    pub const ALL: [Self; 3] = [Self::Random, Self::White, Self::Black];
+
+    /// The preference from the point of view of the opponent.
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Random => Self::Random,
+            Self::White => Self::Black,
+            Self::Black => Self::White,
+        }
+    }
+}
+
+impl fmt::Display for ColorPreference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Random => write!(f, "Random"),
+            Self::White => write!(f, "White"),
+            Self::Black => write!(f, "Black"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeControl {
+    pub minutes: u32,
+    pub increment_secs: u32,
+}
+
+impl TimeControl {
+    pub const PRESETS: [Self; 6] = [
+        Self::new(1, 0),
+        Self::new(3, 2),
+        Self::new(5, 0),
+        Self::new(10, 0),
+        Self::new(15, 10),
+        Self::new(30, 0),
+    ];
+
+    pub const fn new(minutes: u32, increment_secs: u32) -> Self {
+        Self {
+            minutes,
+            increment_secs,
+        }
+    }
+
+    /// The inverse of the body fields, None for untimed games.
+    pub fn from_micros(time_micros: Option<i64>, increment_micros: Option<i64>) -> Option<Self> {
+        let time_secs = time_micros? / MICROS_PER_SECOND;
+        let increment_secs = increment_micros.unwrap_or(0) / MICROS_PER_SECOND;
+        Some(Self::new(
+            u32::try_from(time_secs / 60).unwrap_or(u32::MAX),
+            u32::try_from(increment_secs).unwrap_or(u32::MAX),
+        ))
+    }
+
+    pub fn time_micros(&self) -> i64 {
+        i64::from(self.minutes) * 60 * MICROS_PER_SECOND
+    }
+
+    /// The server requires increments of at least one second, so no increment is sent as None.
+    pub fn increment_micros(&self) -> Option<i64> {
+        (self.increment_secs > 0).then(|| i64::from(self.increment_secs) * MICROS_PER_SECOND)
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} + {}", self.minutes, self.increment_secs)
+    }
+}
+
+/// Formats an optional time control, where None stands for an untimed game.
+pub fn format_time_control(time_control: Option<TimeControl>) -> String {
+    time_control
+        .map(|time_control| time_control.to_string())
+        .unwrap_or_else(|| "Unlimited".to_string())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateGameBody {
+    #[serde(flatten)]
+    pub room: RoomCreationBody,
+    pub color: ColorPreference,
+}
+
+impl CreateGameBody {
+    pub fn new(
+        name: Option<String>,
+        public: bool,
+        time_control: Option<TimeControl>,
+        color: ColorPreference,
+    ) -> Self {
+        Self {
+            room: RoomCreationBody {
+                name,
+                public,
+                time_micros: time_control.map(|time_control| time_control.time_micros()),
+                increment_micros: time_control
+                    .and_then(|time_control| time_control.increment_micros()),
+            },
+            color,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueBody {
+    pub time_micros: Option<i64>,
+    pub increment_micros: Option<i64>,
+    pub color: ColorPreference,
+}
+
+impl QueueBody {
+    pub fn new(time_control: Option<TimeControl>, color: ColorPreference) -> Self {
+        Self {
+            time_micros: time_control.map(|time_control| time_control.time_micros()),
+            increment_micros: time_control.and_then(|time_control| time_control.increment_micros()),
+            color,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueStatusResponse {
+    /// The room of the match, None while still waiting for an opponent.
+    pub room: Option<PrivateRoomInfo>,
+}
@@ -0,0 +1,190 @@
+use reqwest::{ClientBuilder, Proxy};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use url::Url;
+
+const PEM_CERTIFICATE_END: &str = "-----END CERTIFICATE-----";
+
+#[derive(Debug, Error)]
+pub enum NetworkError {
+    #[error("Failed to read '{}': {source}", path.display())]
+    ReadFile { path: PathBuf, source: io::Error },
+    #[error("No certificates found in '{}'", path.display())]
+    NoCertificates { path: PathBuf },
+    #[error("Invalid certificate in '{}': {message}", path.display())]
+    InvalidCertificate { path: PathBuf, message: String },
+    #[error("Invalid client certificate: {0}")]
+    InvalidClientCertificate(String),
+    #[error("Invalid proxy url: {0}")]
+    InvalidProxy(String),
+    #[error("Failed to set up the HTTP client: {0}")]
+    Client(#[from] reqwest::Error),
+    #[error("Failed to set up TLS: {0}")]
+    Tls(#[from] native_tls::Error),
+}
+
+/// Where API requests are routed through.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyMode {
+    /// Uses the proxy from the environment, e.g. `HTTPS_PROXY`.
+    #[default]
+    System,
+    None,
+    Custom,
+}
+
+impl ProxyMode {
+    pub const ALL: [Self; 3] = [Self::System, Self::None, Self::Custom];
+}
+
+impl fmt::Display for ProxyMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::System => write!(f, "System"),
+            Self::None => write!(f, "None"),
+            Self::Custom => write!(f, "Custom"),
+        }
+    }
+}
+
+/// A PEM certificate and its PKCS#8 PEM key, presented to servers which require client authentication.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientCertificate {
+    pub certificate_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+/// TLS and proxy configuration of the HTTP client and live connections.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// PEM files with certificates which are trusted in addition to the system ones.
+    pub root_certificates: Vec<PathBuf>,
+    pub client_certificate: Option<ClientCertificate>,
+    pub proxy_mode: ProxyMode,
+    /// An `http`, `https`, `socks5` or `socks5h` url, credentials can be included.
+    pub proxy_url: String,
+    /// Skips certificate and hostname verification, only meant for local development.
+    pub allow_insecure: bool,
+}
+
+impl NetworkSettings {
+    pub fn uses_custom_proxy(&self) -> bool {
+        self.proxy_mode == ProxyMode::Custom
+    }
+
+    /// A copy without the proxy credentials, so they don't end up in exported settings.
+    pub fn without_secrets(&self) -> Self {
+        let mut settings = self.clone();
+        if let Ok(mut url) = Url::parse(&settings.proxy_url)
+            && (!url.username().is_empty() || url.password().is_some())
+        {
+            let _ = url.set_username("");
+            let _ = url.set_password(None);
+            settings.proxy_url = url.to_string();
+        }
+        settings
+    }
+
+    pub(super) fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder, NetworkError> {
+        for path in &self.root_certificates {
+            for pem in read_certificates(path)? {
+                let certificate =
+                    reqwest::Certificate::from_pem(pem.as_bytes()).map_err(|err| {
+                        NetworkError::InvalidCertificate {
+                            path: path.clone(),
+                            message: err.to_string(),
+                        }
+                    })?;
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        if let Some(client_certificate) = &self.client_certificate {
+            let certificate = read_file(&client_certificate.certificate_path)?;
+            let key = read_file(&client_certificate.key_path)?;
+            let identity = reqwest::Identity::from_pkcs8_pem(&certificate, &key)
+                .map_err(|err| NetworkError::InvalidClientCertificate(err.to_string()))?;
+            builder = builder.identity(identity);
+        }
+
+        builder = match self.proxy_mode {
+            ProxyMode::System => builder,
+            ProxyMode::None => builder.no_proxy(),
+            ProxyMode::Custom => {
+                let proxy = Proxy::all(self.proxy_url.trim())
+                    .map_err(|err| NetworkError::InvalidProxy(err.to_string()))?;
+                builder.proxy(proxy)
+            }
+        };
+
+        Ok(builder.danger_accept_invalid_certs(self.allow_insecure))
+    }
+
+    /// The same trust settings for WebSocket connections, None if the defaults suffice.
+    pub(super) fn build_tls_connector(
+        &self,
+    ) -> Result<Option<native_tls::TlsConnector>, NetworkError> {
+        if self.root_certificates.is_empty()
+            && self.client_certificate.is_none()
+            && !self.allow_insecure
+        {
+            return Ok(None);
+        }
+
+        let mut builder = native_tls::TlsConnector::builder();
+        for path in &self.root_certificates {
+            for pem in read_certificates(path)? {
+                let certificate =
+                    native_tls::Certificate::from_pem(pem.as_bytes()).map_err(|err| {
+                        NetworkError::InvalidCertificate {
+                            path: path.clone(),
+                            message: err.to_string(),
+                        }
+                    })?;
+                builder.add_root_certificate(certificate);
+            }
+        }
+
+        if let Some(client_certificate) = &self.client_certificate {
+            let certificate = read_file(&client_certificate.certificate_path)?;
+            let key = read_file(&client_certificate.key_path)?;
+            let identity = native_tls::Identity::from_pkcs8(&certificate, &key)
+                .map_err(|err| NetworkError::InvalidClientCertificate(err.to_string()))?;
+            builder.identity(identity);
+        }
+
+        builder
+            .danger_accept_invalid_certs(self.allow_insecure)
+            .danger_accept_invalid_hostnames(self.allow_insecure);
+        Ok(Some(builder.build()?))
+    }
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, NetworkError> {
+    std::fs::read(path).map_err(|source| NetworkError::ReadFile {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Splits a PEM bundle into its certificates, since native-tls only parses one at a time.
+fn read_certificates(path: &Path) -> Result<Vec<String>, NetworkError> {
+    let content = String::from_utf8_lossy(&read_file(path)?).into_owned();
+    let certificates: Vec<String> = content
+        .split_inclusive(PEM_CERTIFICATE_END)
+        .filter(|block| block.contains(PEM_CERTIFICATE_END))
+        .map(|block| block.trim().to_string())
+        .collect();
+
+    if certificates.is_empty() {
+        return Err(NetworkError::NoCertificates {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(certificates)
+}
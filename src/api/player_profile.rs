@@ -0,0 +1,187 @@
+use crate::api::matchmaking::TimeControl;
+use giga_chess::prelude::Color;
+use giga_chess_api_types::general::pagination::Pagination;
+use giga_chess_api_types::response::user_info::PublicUserInfo;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Ratings are tracked separately per pace, derived from the time control of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RatingCategory {
+    Bullet,
+    Blitz,
+    Rapid,
+    Classical,
+    Unlimited,
+}
+
+impl RatingCategory {
+    pub const ALL: [Self; 5] = [
+        Self::Bullet,
+        Self::Blitz,
+        Self::Rapid,
+        Self::Classical,
+        Self::Unlimited,
+    ];
+
+    /// Categorized by the estimated duration of a game with 40 moves per player.
+    pub fn from_time_control(time_control: Option<TimeControl>) -> Self {
+        let Some(time_control) = time_control else {
+            return Self::Unlimited;
+        };
+        let estimated_secs = time_control
+            .minutes
+            .saturating_mul(60)
+            .saturating_add(time_control.increment_secs.saturating_mul(40));
+        match estimated_secs {
+            0..180 => Self::Bullet,
+            180..480 => Self::Blitz,
+            480..1500 => Self::Rapid,
+            _ => Self::Classical,
+        }
+    }
+}
+
+impl fmt::Display for RatingCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bullet => write!(f, "Bullet"),
+            Self::Blitz => write!(f, "Blitz"),
+            Self::Rapid => write!(f, "Rapid"),
+            Self::Classical => write!(f, "Classical"),
+            Self::Unlimited => write!(f, "Unlimited"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rating {
+    pub category: RatingCategory,
+    pub rating: i32,
+    /// Rated games played in this category.
+    pub games: u32,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameStats {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl GameStats {
+    pub fn total(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    pub fn add(&mut self, outcome: GameOutcome) {
+        match outcome {
+            GameOutcome::Win => self.wins += 1,
+            GameOutcome::Draw => self.draws += 1,
+            GameOutcome::Loss => self.losses += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserProfile {
+    pub name: String,
+    /// Only categories the user has played in.
+    #[serde(default)]
+    pub ratings: Vec<Rating>,
+    #[serde(default)]
+    pub stats: GameStats,
+}
+
+impl UserProfile {
+    pub fn get_rating(&self, category: RatingCategory) -> Option<&Rating> {
+        self.ratings
+            .iter()
+            .find(|rating| rating.category == category)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOutcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Win => write!(f, "Win"),
+            Self::Draw => write!(f, "Draw"),
+            Self::Loss => write!(f, "Loss"),
+        }
+    }
+}
+
+/// A finished online game as listed in the history of a player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameSummary {
+    pub uuid: String,
+    pub white: PublicUserInfo,
+    pub black: PublicUserInfo,
+    pub time_micros: Option<i64>,
+    pub increment_micros: Option<i64>,
+    /// The result in PGN notation, e.g. `1-0`.
+    pub result: String,
+    pub reason: String,
+    /// Unix timestamp in seconds.
+    pub ended_at: i64,
+}
+
+impl GameSummary {
+    pub fn time_control(&self) -> Option<TimeControl> {
+        TimeControl::from_micros(self.time_micros, self.increment_micros)
+    }
+
+    pub fn get_color(&self, username: &str) -> Option<Color> {
+        if self.white.name == username {
+            Some(Color::White)
+        } else if self.black.name == username {
+            Some(Color::Black)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_opponent(&self, username: &str) -> Option<&PublicUserInfo> {
+        match self.get_color(username)? {
+            Color::White => Some(&self.black),
+            Color::Black => Some(&self.white),
+        }
+    }
+
+    /// The outcome from the perspective of the given player, None if they didn't play or the
+    /// result is unknown, e.g. "*" for an aborted game.
+    pub fn get_outcome(&self, username: &str) -> Option<GameOutcome> {
+        let color = self.get_color(username)?;
+        let outcome = match (self.result.as_str(), color) {
+            ("1-0", Color::White) | ("0-1", Color::Black) => GameOutcome::Win,
+            ("1-0", Color::Black) | ("0-1", Color::White) => GameOutcome::Loss,
+            ("1/2-1/2" | "½-½" | "½–½", _) => GameOutcome::Draw,
+            _ => return None,
+        };
+        Some(outcome)
+    }
+}
+
+/// A finished game including its moves, used to replay it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRecord {
+    #[serde(flatten)]
+    pub summary: GameSummary,
+    /// Moves in UCI notation.
+    pub moves: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameHistory {
+    /// Newest games first.
+    pub games: Vec<GameSummary>,
+    pub pagination: Pagination,
+}
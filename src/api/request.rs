@@ -0,0 +1,128 @@
+use crate::types::shared::Shared;
+use reqwest::RequestBuilder;
+use std::any::TypeId;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio::task::AbortHandle;
+
+/// Hash of caller, method, url and body, identical requests of the same caller share the same key.
+pub(super) type RequestKey = u64;
+
+/// Requests which are still running, used to skip identical concurrent ones.
+pub(super) type InFlightRequests = Shared<HashMap<RequestKey, RequestHandle>>;
+
+#[derive(Debug)]
+struct RequestInner {
+    cancelled: AtomicBool,
+    finished: watch::Sender<bool>,
+    abort_handle: Mutex<Option<AbortHandle>>,
+}
+
+/// Handle to a request running in the background. Cancelling it drops the callback, so state owned
+/// by a closed modal or view isn't overwritten by a stale response. Dropping the handle does not
+/// cancel the request.
+#[derive(Debug, Clone)]
+pub struct RequestHandle {
+    inner: Arc<RequestInner>,
+}
+
+impl RequestHandle {
+    pub(super) fn new() -> Self {
+        Self {
+            inner: Arc::new(RequestInner {
+                cancelled: AtomicBool::new(false),
+                finished: watch::channel(false).0,
+                abort_handle: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// A handle for requests which failed before being sent, their callback was already called.
+    pub(super) fn completed() -> Self {
+        let handle = Self::new();
+        handle.inner.finished.send_replace(true);
+        handle
+    }
+
+    pub(super) fn set_abort_handle(&self, abort_handle: AbortHandle) {
+        *self.inner.abort_handle.lock().unwrap() = Some(abort_handle);
+    }
+
+    /// Calls the callback unless the request was cancelled and marks it as finished.
+    pub(super) fn finish<F: FnOnce()>(&self, callback: F) {
+        if !self.is_cancelled() {
+            callback();
+        }
+        self.inner.finished.send_replace(true);
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        if let Some(abort_handle) = self.inner.abort_handle.lock().unwrap().take() {
+            abort_handle.abort();
+        }
+        self.inner.finished.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        *self.inner.finished.borrow()
+    }
+
+    /// Resolves once the callback ran or the request was cancelled.
+    pub async fn wait(&self) {
+        let mut finished = self.inner.finished.subscribe();
+        let _ = finished.wait_for(|finished| *finished).await;
+    }
+
+    pub fn is_same(&self, other: &RequestHandle) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+/// Keeps the latest of a series of requests whose responses replace each other, e.g. the pages of
+/// a list. Starting the next one cancels the previous, so a slow stale response can't overwrite a
+/// newer one.
+#[derive(Debug, Default)]
+pub struct LatestRequest(Option<RequestHandle>);
+
+impl LatestRequest {
+    pub fn replace(&mut self, handle: RequestHandle) {
+        // Deduplicated requests hand out the handle of the running one again
+        if let Some(previous) = &self.0
+            && !previous.is_same(&handle)
+        {
+            previous.cancel();
+        }
+        self.0 = Some(handle);
+    }
+
+    pub fn cancel(&mut self) {
+        if let Some(previous) = self.0.take() {
+            previous.cancel();
+        }
+    }
+}
+
+/// None for requests which can't be inspected, those are never deduplicated. The caller is told
+/// apart by the type of its callback, every closure has its own. Identical requests of different
+/// callers are sent separately, so every callback gets called and can be cancelled on its own.
+pub(super) fn get_request_key(request: &RequestBuilder, caller: TypeId) -> Option<RequestKey> {
+    let request = request.try_clone()?.build().ok()?;
+    let mut hasher = DefaultHasher::new();
+    caller.hash(&mut hasher);
+    request.method().hash(&mut hasher);
+    request.url().hash(&mut hasher);
+    request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .hash(&mut hasher);
+    Some(hasher.finish())
+}
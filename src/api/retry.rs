@@ -0,0 +1,172 @@
+use crate::api::error::{ApiError, ApiResult};
+use crate::api::execute_request;
+use crate::types::shared::Shared;
+use chrono::{DateTime, Utc};
+use log::warn;
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Servers asking to wait longer than this are not retried, the error is returned right away.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Timeouts and retry behaviour of API requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub connect_timeout_secs: u64,
+    pub request_timeout_secs: u64,
+    /// Retries after the first attempt, 0 disables retrying.
+    pub max_retries: u32,
+    pub initial_backoff_millis: u64,
+    pub max_backoff_millis: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 5,
+            request_timeout_secs: 15,
+            max_retries: 3,
+            initial_backoff_millis: 500,
+            max_backoff_millis: 10_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs)
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs)
+    }
+
+    /// Exponential backoff with jitter, so clients which failed together don't retry together.
+    pub fn get_backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff_millis
+            .saturating_mul(2u64.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff_millis);
+        let jittered = rand::rng().random_range(backoff / 2..=backoff);
+        Duration::from_millis(jittered)
+    }
+}
+
+/// A request waiting to be retried, shown to the user so slow responses don't look like a hang.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryStatus {
+    request_id: u64,
+    pub attempt: u32,
+    pub max_retries: u32,
+    pub retry_at: Instant,
+    pub reason: String,
+}
+
+/// Clears the status of the request once it stops waiting, unless another request took it over.
+struct RetryStatusGuard<'a> {
+    status: &'a Shared<Option<RetryStatus>>,
+    request_id: u64,
+}
+
+impl Drop for RetryStatusGuard<'_> {
+    fn drop(&mut self) {
+        self.status.with(|status| {
+            if status
+                .as_ref()
+                .is_some_and(|status| status.request_id == self.request_id)
+            {
+                *status = None;
+            }
+        });
+    }
+}
+
+/// Reads the `Retry-After` header, which is either a number of seconds or an HTTP date.
+pub(super) fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.with_timezone(&Utc) - Utc::now();
+    Some(delay.to_std().unwrap_or_default())
+}
+
+/// Requests which can be sent again without changing the outcome.
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+/// Rate limited requests were never processed, so they are retried regardless of the method.
+fn is_retryable(error: &ApiError, idempotent: bool) -> bool {
+    match error {
+        ApiError::RateLimited { .. } => true,
+        ApiError::Connection(_)
+        | ApiError::ConnectionTimeout
+        | ApiError::ServiceUnavailable { .. } => idempotent,
+        _ => false,
+    }
+}
+
+/// Sends the request, retrying failures which are likely temporary according to the policy.
+pub(super) async fn execute_with_retries<T>(
+    request: RequestBuilder,
+    policy: RetryPolicy,
+    status: &Shared<Option<RetryStatus>>,
+) -> ApiResult<T>
+where
+    T: DeserializeOwned,
+{
+    let idempotent = request
+        .try_clone()
+        .and_then(|request| request.build().ok())
+        .is_some_and(|request| is_idempotent(request.method()));
+    let request_id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+
+    let mut attempt = 0;
+    loop {
+        // Streamed bodies can't be cloned, those requests are only sent once
+        let Some(current) = request.try_clone() else {
+            return execute_request(request).await;
+        };
+
+        let error = match execute_request(current).await {
+            Err(error) if attempt < policy.max_retries && is_retryable(&error, idempotent) => error,
+            result => return result,
+        };
+
+        let delay = match error.retry_after() {
+            Some(retry_after) if retry_after > MAX_RETRY_AFTER => return Err(error),
+            Some(retry_after) => retry_after,
+            None => policy.get_backoff(attempt + 1),
+        };
+        attempt += 1;
+
+        warn!(
+            "Request failed ({error}), retrying in {delay:?} (attempt {attempt}/{})",
+            policy.max_retries
+        );
+        status.set(Some(RetryStatus {
+            request_id,
+            attempt,
+            max_retries: policy.max_retries,
+            retry_at: Instant::now() + delay,
+            reason: error.to_string(),
+        }));
+        // Also clears the status if the request is cancelled while waiting
+        let _guard = RetryStatusGuard { status, request_id };
+        tokio::time::sleep(delay).await;
+    }
+}
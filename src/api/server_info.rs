@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Version of `giga-chess-api-types` this client was built against, read from Cargo.lock.
+pub const API_VERSION: &str = env!("GIGA_CHESS_API_TYPES_VERSION");
+
+/// Optional features a server can offer, unknown ones are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    OpenGames,
+    Queue,
+    LiveWebsocket,
+    LivePolling,
+    /// Player profiles, ratings and game histories.
+    Profiles,
+    /// Friend lists, presence and direct challenges.
+    Friends,
+    /// Watching running games of other players.
+    Spectating,
+    /// Rankings of the players per rating category.
+    Leaderboards,
+    /// Games with per-move deadlines in days.
+    Correspondence,
+    /// Swiss, round robin and arena tournaments.
+    Tournaments,
+    /// Admins can issue and revoke invite codes.
+    InviteCodes,
+    /// Users can change their password and username or delete their account.
+    AccountManagement,
+    /// Remembered logins get a refresh token, so the password doesn't have to be stored.
+    RefreshTokens,
+    #[serde(other)]
+    Unknown,
+}
+
+impl Capability {
+    /// Everything servers offered before they reported their capabilities.
+    pub const BASELINE: [Self; 4] = [
+        Self::OpenGames,
+        Self::Queue,
+        Self::LiveWebsocket,
+        Self::LivePolling,
+    ];
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OpenGames => write!(f, "Open games"),
+            Self::Queue => write!(f, "Quick match"),
+            Self::LiveWebsocket => write!(f, "WebSocket connections"),
+            Self::LivePolling => write!(f, "Long polling"),
+            Self::Profiles => write!(f, "Player profiles"),
+            Self::Friends => write!(f, "Friends"),
+            Self::Spectating => write!(f, "Spectating"),
+            Self::Leaderboards => write!(f, "Leaderboards"),
+            Self::Correspondence => write!(f, "Correspondence games"),
+            Self::Tournaments => write!(f, "Tournaments"),
+            Self::InviteCodes => write!(f, "Invite codes"),
+            Self::AccountManagement => write!(f, "Account management"),
+            Self::RefreshTokens => write!(f, "Remembered logins"),
+            Self::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerInfo {
+    /// Version of `giga-chess-api-types` the server was built against.
+    pub api_version: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+}
+
+impl ServerInfo {
+    /// Assumed for servers which predate the version endpoint.
+    pub fn legacy() -> Self {
+        Self {
+            api_version: API_VERSION.to_string(),
+            version: None,
+            capabilities: Capability::BASELINE.to_vec(),
+        }
+    }
+
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    /// Versions are compatible by Cargo's rules, the left-most non-zero component has to match.
+    pub fn check_compatibility(&self) -> Result<(), String> {
+        let server = get_compatibility_key(&self.api_version);
+        let client = get_compatibility_key(API_VERSION);
+        match (server, client) {
+            (Some(server), Some(client)) if server == client => Ok(()),
+            (Some(server), client) => Err(format!(
+                "The server speaks API version {} but this client was built for {API_VERSION}, \
+                 please update the {}.",
+                self.api_version,
+                if Some(server) > client {
+                    "client"
+                } else {
+                    "server"
+                }
+            )),
+            (None, _) => Err(format!(
+                "The server reported an invalid API version '{}'.",
+                self.api_version
+            )),
+        }
+    }
+}
+
+/// Reduces a version to the components which have to match, e.g. `1.4.2` to `(1, 0, 0)`.
+fn get_compatibility_key(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(str::parse::<u64>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+
+    Some(match (major, minor) {
+        (0, 0) => (0, 0, patch),
+        (0, minor) => (0, minor, 0),
+        (major, _) => (major, 0, 0),
+    })
+}
+
+/// Outcome of the handshake with the current server.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum ServerInfoState {
+    #[default]
+    Unknown,
+    Checking,
+    Compatible(ServerInfo),
+    Incompatible(String),
+    /// The server couldn't be asked, features aren't restricted until it can.
+    Error(String),
+}
+
+impl ServerInfoState {
+    pub fn is_incompatible(&self) -> bool {
+        matches!(self, Self::Incompatible(_))
+    }
+
+    /// Only capabilities a compatible server didn't report are considered missing.
+    pub fn supports(&self, capability: Capability) -> bool {
+        match self {
+            Self::Compatible(info) => info.supports(capability),
+            Self::Incompatible(_) => false,
+            Self::Unknown | Self::Checking | Self::Error(_) => true,
+        }
+    }
+}
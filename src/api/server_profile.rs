@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A named server the user can switch to, e.g. a local, staging or production instance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerProfile {
+    pub name: String,
+    pub url: String,
+}
+
+impl ServerProfile {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthResponse {
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    pub latency: Duration,
+    /// None if the server doesn't report its version.
+    pub version: Option<String>,
+}
@@ -0,0 +1,142 @@
+use crate::api::login::LoginState;
+use chrono::Utc;
+use giga_chess_api_types::response::login::LoginResponse;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+/// Tokens are refreshed this many seconds before they expire.
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "SessionRepr")]
+pub struct Session {
+    pub token: String,
+    /// None for sessions persisted before the username was stored.
+    pub username: Option<String>,
+    /// Unix timestamp in seconds, None if unknown.
+    pub expires_at: Option<i64>,
+    /// Only present if the user chose to be remembered, exchanged for a new token once the
+    /// current one expires. The password itself is never stored.
+    pub refresh_token: Option<String>,
+}
+
+impl Session {
+    pub fn new(
+        username: impl Into<String>,
+        response: LoginResponse,
+        refresh_token: Option<String>,
+    ) -> Self {
+        Self {
+            token: response.token,
+            username: Some(username.into()),
+            expires_at: Some(get_expiry(response.expires_in_secs)),
+            refresh_token,
+        }
+    }
+
+    /// The same session with the token the refresh token was exchanged for.
+    pub fn refreshed(&self, response: LoginResponse) -> Self {
+        Self {
+            token: response.token,
+            username: self.username.clone(),
+            expires_at: Some(get_expiry(response.expires_in_secs)),
+            refresh_token: self.refresh_token.clone(),
+        }
+    }
+
+    pub fn is_expiring(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at - REFRESH_MARGIN_SECS <= Utc::now().timestamp())
+    }
+}
+
+fn get_expiry(expires_in_secs: u64) -> i64 {
+    let expires_in_secs = i64::try_from(expires_in_secs).unwrap_or(i64::MAX);
+    Utc::now().timestamp().saturating_add(expires_in_secs)
+}
+
+/// The answer to a remembered login, the refresh token stays valid until the user logs out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RememberedLoginResponse {
+    #[serde(flatten)]
+    pub login: LoginResponse,
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshBody {
+    pub refresh_token: String,
+}
+
+/// Ends the session on the server, the refresh token of a remembered login is revoked with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogoutBody {
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+/// Saves from before sessions existed only stored the bare token. Passwords remembered by older
+/// versions are dropped, those sessions have to log in again once they expire.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SessionRepr {
+    Token(String),
+    Full {
+        token: String,
+        #[serde(default)]
+        username: Option<String>,
+        expires_at: Option<i64>,
+        #[serde(default)]
+        refresh_token: Option<String>,
+    },
+}
+
+impl From<SessionRepr> for Session {
+    fn from(repr: SessionRepr) -> Self {
+        match repr {
+            SessionRepr::Token(token) => Self {
+                token,
+                username: None,
+                expires_at: None,
+                refresh_token: None,
+            },
+            SessionRepr::Full {
+                token,
+                username,
+                expires_at,
+                refresh_token,
+            } => Self {
+                token,
+                username,
+                expires_at,
+                refresh_token,
+            },
+        }
+    }
+}
+
+/// Older saves parked the whole login state of every server, only their sessions are kept.
+pub fn deserialize_parked_sessions<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, Session>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let parked = HashMap::<String, ParkedSessionRepr>::deserialize(deserializer)?;
+    Ok(parked
+        .into_iter()
+        .filter_map(|(url, parked)| match parked {
+            ParkedSessionRepr::Session(session)
+            | ParkedSessionRepr::LoginState(LoginState::Success(session)) => Some((url, session)),
+            ParkedSessionRepr::LoginState(_) => None,
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ParkedSessionRepr {
+    // Tried first, otherwise states like "Expired" would pass as bare tokens
+    LoginState(LoginState),
+    Session(Session),
+}
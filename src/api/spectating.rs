@@ -0,0 +1,42 @@
+use crate::api::matchmaking::TimeControl;
+use giga_chess_api_types::general::pagination::Pagination;
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use giga_chess_api_types::response::user_info::PublicUserInfo;
+use serde::{Deserialize, Serialize};
+
+/// A running public game which can be watched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveGame {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub white: PublicUserInfo,
+    pub black: PublicUserInfo,
+    pub time_micros: Option<i64>,
+    pub increment_micros: Option<i64>,
+    pub move_count: u32,
+    pub spectators: u32,
+}
+
+impl LiveGame {
+    pub fn time_control(&self) -> Option<TimeControl> {
+        TimeControl::from_micros(self.time_micros, self.increment_micros)
+    }
+
+    /// The room the live connection of a spectator is opened for.
+    pub fn to_room_info(&self) -> PrivateRoomInfo {
+        PrivateRoomInfo {
+            uuid: self.uuid.clone(),
+            name: self.name.clone(),
+            public: true,
+            white: Some(self.white.clone()),
+            black: Some(self.black.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveGameList {
+    /// Most watched games first.
+    pub games: Vec<LiveGame>,
+    pub pagination: Pagination,
+}
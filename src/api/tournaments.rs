@@ -0,0 +1,153 @@
+use crate::api::matchmaking::TimeControl;
+use giga_chess_api_types::general::pagination::Pagination;
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use giga_chess_api_types::response::user_info::PublicUserInfo;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TournamentFormat {
+    /// Players with similar scores are paired each round.
+    Swiss,
+    /// Everyone plays everyone once.
+    RoundRobin,
+    /// Players are paired again as soon as their game ended, until the time is up.
+    Arena,
+}
+
+impl fmt::Display for TournamentFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Swiss => write!(f, "Swiss"),
+            Self::RoundRobin => write!(f, "Round robin"),
+            Self::Arena => write!(f, "Arena"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TournamentStatus {
+    Upcoming,
+    Running,
+    Finished,
+}
+
+impl fmt::Display for TournamentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Upcoming => write!(f, "Upcoming"),
+            Self::Running => write!(f, "Running"),
+            Self::Finished => write!(f, "Finished"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TournamentSummary {
+    pub uuid: String,
+    pub name: String,
+    pub format: TournamentFormat,
+    pub status: TournamentStatus,
+    pub time_micros: Option<i64>,
+    pub increment_micros: Option<i64>,
+    /// The planned number of rounds, None for arenas, which run until `ends_at`.
+    pub rounds: Option<u32>,
+    /// 0 until the first round was paired.
+    pub current_round: u32,
+    pub player_count: u32,
+    /// Whether the requesting user takes part and hasn't withdrawn.
+    pub joined: bool,
+    /// Unix timestamps in seconds.
+    pub starts_at: Option<i64>,
+    pub ends_at: Option<i64>,
+}
+
+impl TournamentSummary {
+    pub fn time_control(&self) -> Option<TimeControl> {
+        TimeControl::from_micros(self.time_micros, self.increment_micros)
+    }
+
+    /// Arenas can still be joined while they are running.
+    pub fn can_join(&self) -> bool {
+        match self.status {
+            TournamentStatus::Upcoming => true,
+            TournamentStatus::Running => self.format == TournamentFormat::Arena,
+            TournamentStatus::Finished => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TournamentList {
+    /// Running tournaments first, then upcoming and finished ones.
+    pub tournaments: Vec<TournamentSummary>,
+    pub pagination: Pagination,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Standing {
+    pub rank: i64,
+    pub name: String,
+    pub points: f32,
+    pub games: u32,
+    /// Withdrawn players keep their points but aren't paired anymore.
+    pub withdrawn: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pairing {
+    pub round: u32,
+    pub white: PublicUserInfo,
+    /// None if the white player got a bye.
+    pub black: Option<PublicUserInfo>,
+    pub room_uuid: Option<String>,
+    /// The result in PGN notation once the game ended, e.g. `1-0`.
+    pub result: Option<String>,
+}
+
+impl Pairing {
+    pub fn is_running(&self) -> bool {
+        self.room_uuid.is_some() && self.result.is_none()
+    }
+
+    pub fn includes(&self, username: &str) -> bool {
+        self.white.name == username
+            || self
+                .black
+                .as_ref()
+                .is_some_and(|black| black.name == username)
+    }
+
+    /// The room the live connection of a spectator is opened for, None for byes.
+    pub fn to_room_info(&self) -> Option<PrivateRoomInfo> {
+        Some(PrivateRoomInfo {
+            uuid: self.room_uuid.clone()?,
+            name: None,
+            public: false,
+            white: Some(self.white.clone()),
+            black: self.black.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tournament {
+    #[serde(flatten)]
+    pub summary: TournamentSummary,
+    /// The best player first.
+    pub standings: Vec<Standing>,
+    /// The pairings of every round, the latest round first.
+    pub pairings: Vec<Pairing>,
+    /// The running game of the requesting user, the client opens it once it is paired.
+    pub own_game: Option<PrivateRoomInfo>,
+}
+
+impl Tournament {
+    pub fn get_round(&self, round: u32) -> impl Iterator<Item = &Pairing> {
+        self.pairings
+            .iter()
+            .filter(move |pairing| pairing.round == round)
+    }
+}
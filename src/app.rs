@@ -1,9 +1,17 @@
 use crate::app::state::{AppState, AppStatePersist};
 use crate::app::views::{View, ViewManager};
+use crate::cli::CliArgs;
+use crate::logging;
+#[cfg(feature = "mock-server")]
+use crate::mock_server::MockServer;
 use crate::persistence;
+use crate::persistence::profile;
 use crate::persistence::PersistentObject;
 use eframe::{App, Frame};
 use egui::Context;
+#[cfg(feature = "mock-server")]
+use log::error;
+use log::info;
 use serde::{Deserialize, Serialize};
 
 mod asset_server;
@@ -14,10 +22,14 @@ mod validation;
 mod views;
 mod windows;
 
+pub use state::online_game::OnlineGame;
+
 #[derive(Debug)]
 pub struct GigaChessApp {
     state: AppState,
     view_manager: ViewManager,
+    #[cfg(feature = "mock-server")]
+    mock_server: Option<MockServer>,
 }
 
 impl Default for GigaChessApp {
@@ -25,28 +37,112 @@ impl Default for GigaChessApp {
         Self {
             state: AppState::default(),
             view_manager: ViewManager::new(),
+            #[cfg(feature = "mock-server")]
+            mock_server: None,
         }
     }
 }
 
 impl GigaChessApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        if let Some(state) = persistence::restore() {
-            Self::load_from_state(state)
+    pub fn new(_cc: &eframe::CreationContext<'_>, args: CliArgs) -> Self {
+        profile::migrate_legacy_save();
+
+        let profile = args
+            .profile
+            .clone()
+            .unwrap_or_else(profile::get_last_profile);
+        let mut app = if args.reset_state {
+            Self::empty_profile(profile)
         } else {
-            Self::default()
+            Self::load_profile(profile)
+        };
+
+        let has_profile_choice = args.profile.is_none() && args.open.is_none();
+        if has_profile_choice && profile::list_profiles().len() > 1 {
+            app.view_manager.open_profile_picker(&mut app.state);
+        }
+
+        app.state.apply_cli_args(&args);
+        #[cfg(feature = "mock-server")]
+        if args.mock_server {
+            match MockServer::start() {
+                Ok(mock_server) => app.mock_server = Some(mock_server),
+                Err(err) => error!("Failed to start the mock server: {err}"),
+            }
+        }
+        app.connect();
+        app
+    }
+
+    /// The mock server replaces the profile's server for this run, without being saved.
+    fn connect(&mut self) {
+        #[cfg(feature = "mock-server")]
+        if let Some(mock_server) = &self.mock_server {
+            self.state.set_server_url_override(mock_server.url());
+            return;
+        }
+        self.state.connect();
+    }
+
+    fn empty_profile(profile: String) -> Self {
+        profile::ensure_profile(&profile);
+        let mut app = Self::default();
+        app.state.profile = profile;
+        app
+    }
+
+    fn load_profile(profile: String) -> Self {
+        let Some(state) = persistence::restore(&profile) else {
+            return Self::empty_profile(profile);
+        };
+
+        profile::ensure_profile(&profile);
+        info!("Loaded profile '{profile}'");
+        let mut app = Self::load_from_state(state);
+        app.state.profile = profile;
+        if let Some(level) = app.state.log_level {
+            logging::set_level(level);
         }
+        app
+    }
+
+    fn save_profile(&self) {
+        let persistent_state = self.save_state();
+        persistence::persist(&self.state.profile, persistent_state);
+        profile::set_last_profile(&self.state.profile);
+    }
+
+    fn switch_profile(&mut self, profile: String) {
+        self.save_profile();
+        #[cfg(feature = "mock-server")]
+        let mock_server = self.mock_server.take();
+        *self = Self::load_profile(profile);
+        profile::set_last_profile(&self.state.profile);
+
+        // The mock server outlives profile switches
+        #[cfg(feature = "mock-server")]
+        {
+            self.mock_server = mock_server;
+        }
+        self.connect();
     }
 }
 
 impl App for GigaChessApp {
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
         self.view_manager.render(ctx, &mut self.state);
+
+        if std::mem::take(&mut self.state.requested_save) {
+            self.save_profile();
+        }
+
+        if let Some(profile) = self.state.requested_profile.take() {
+            self.switch_profile(profile);
+        }
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        let persistent_state = self.save_state();
-        persistence::persist(persistent_state);
+        self.save_profile();
     }
 }
 
@@ -22,17 +22,13 @@ pub struct AssetServer {
 }
 
 impl AssetServer {
-    pub fn new() -> Self {
-        Self::default()
-    }
-
     pub fn get_piece_image(
         &mut self,
         ctx: &Context,
         piece: Piece,
         color: Color,
         size: f32,
-    ) -> Image {
+    ) -> Image<'_> {
         let svg_size = size as u32;
         let piece_color_key = piece as u8 + color as u8 * 6;
 
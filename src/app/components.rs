@@ -1,2 +1,4 @@
 pub mod chess_board;
+pub mod game_options;
+pub mod retry_status;
 pub mod validated_field;
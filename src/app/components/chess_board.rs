@@ -1,21 +1,13 @@
+use crate::app::state::board_theme::BoardTheme;
 use crate::app::state::AppState;
 use crate::game::AppGame;
-use crate::persistence::color::Color32Persist;
-use crate::persistence::PersistentObject;
 use egui::epaint::CircleShape;
-use egui::{Align2, Color32, FontId, Id, Painter, Pos2, Rect, Sense, Stroke, Ui, Vec2};
+use egui::{Align2, Color32, FontId, Id, Painter, Pos2, Rect, Sense, Stroke, Ui, UiBuilder, Vec2};
 use giga_chess::prelude::{Color, Square};
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct ChessBoardComponent {
-    light_color: Color32,
-    light_color_highlight: Color32,
-    light_color_threat: Color32,
-    dark_color: Color32,
-    dark_color_highlight: Color32,
-    dark_color_threat: Color32,
     dragging_from: Option<Square>,
     threat_squares: Vec<Square>,
     target_square_map: HashMap<Square, Vec<Square>>,
@@ -31,12 +23,6 @@ impl Default for ChessBoardComponent {
 impl ChessBoardComponent {
     pub fn new() -> Self {
         Self {
-            light_color: Color32::from_rgb(255, 247, 228),
-            light_color_highlight: Color32::from_rgb(255, 197, 178),
-            light_color_threat: Color32::from_rgb(249, 130, 132),
-            dark_color: Color32::from_rgb(135, 168, 137),
-            dark_color_highlight: Color32::from_rgb(200, 168, 137),
-            dark_color_threat: Color32::from_rgb(199, 103, 105),
             dragging_from: None,
             threat_squares: Vec::new(),
             target_square_map: HashMap::new(),
@@ -98,7 +84,7 @@ impl ChessBoardComponent {
         let last_move = app_game.game.latest_move();
         let last_from = last_move.map(|mv| Square::new(mv.get_from()));
         let last_to = last_move.map(|mv| Square::new(mv.get_to()));
-        let color = self.get_square_color(square, last_from, last_to);
+        let color = self.get_square_color(&state.board_theme, square, last_from, last_to);
         painter.rect_filled(square_rect, 0.0, color);
 
         self.render_piece(ui, state, app_game, square, square_rect, square_size);
@@ -107,12 +93,11 @@ impl ChessBoardComponent {
             self.on_drag_drop(*dragged_square, square, app_game, state);
         }
 
-        if let Some(dragging_from) = self.dragging_from {
-            if let Some(target_squares) = self.target_square_map.get(&dragging_from) {
-                if target_squares.contains(&square) {
-                    painter.add(self.get_target_circle(square_rect));
-                }
-            }
+        if let Some(dragging_from) = self.dragging_from
+            && let Some(target_squares) = self.target_square_map.get(&dragging_from)
+            && target_squares.contains(&square)
+        {
+            painter.add(self.get_target_circle(&state.board_theme, square_rect));
         }
     }
 
@@ -127,7 +112,7 @@ impl ChessBoardComponent {
     ) {
         if let Some((piece, color)) = app_game.game.board().get_piece_at(square.get_value()) {
             let piece_id = Id::new(format!("piece_{square}"));
-            ui.allocate_ui_at_rect(square_rect, |ui| {
+            ui.allocate_new_ui(UiBuilder::new().max_rect(square_rect), |ui| {
                 if app_game.can_color_move(color) {
                     let drag_inner = ui.dnd_drag_source(piece_id, square, |ui| {
                         let image =
@@ -248,6 +233,7 @@ impl ChessBoardComponent {
 
     fn get_square_color(
         &self,
+        theme: &BoardTheme,
         square: Square,
         last_from: Option<Square>,
         last_to: Option<Square>,
@@ -257,29 +243,29 @@ impl ChessBoardComponent {
 
         if square.is_white() {
             if is_threat {
-                self.light_color_threat
+                theme.light_color_threat
             } else if is_highlight {
-                self.light_color_highlight
+                theme.light_color_highlight
             } else {
-                self.light_color
+                theme.light_color
             }
         } else if is_threat {
-            self.dark_color_threat
+            theme.dark_color_threat
         } else if is_highlight {
-            self.dark_color_highlight
+            theme.dark_color_highlight
         } else {
-            self.dark_color
+            theme.dark_color
         }
     }
 
-    fn get_target_circle(&self, square_rect: Rect) -> CircleShape {
+    fn get_target_circle(&self, theme: &BoardTheme, square_rect: Rect) -> CircleShape {
         let center = square_rect.center();
         let radius = square_rect.width() / 7.0;
         CircleShape {
             center,
             radius,
-            fill: self.light_color,
-            stroke: Stroke::new(radius / 3.0, self.dark_color),
+            fill: theme.light_color,
+            stroke: Stroke::new(radius / 3.0, theme.dark_color),
         }
     }
 
@@ -291,43 +277,3 @@ impl ChessBoardComponent {
         }
     }
 }
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ChessBoardComponentPersist {
-    light_color: Color32Persist,
-    light_color_highlight: Color32Persist,
-    light_color_threat: Color32Persist,
-    dark_color: Color32Persist,
-    dark_color_highlight: Color32Persist,
-    dark_color_threat: Color32Persist,
-}
-
-impl PersistentObject for ChessBoardComponent {
-    type PersistentType = ChessBoardComponentPersist;
-
-    fn save_state(&self) -> Self::PersistentType {
-        ChessBoardComponentPersist {
-            light_color: self.light_color.save_state(),
-            light_color_highlight: self.light_color_highlight.save_state(),
-            light_color_threat: self.light_color_threat.save_state(),
-            dark_color: self.dark_color.save_state(),
-            dark_color_highlight: self.dark_color_highlight.save_state(),
-            dark_color_threat: self.dark_color_threat.save_state(),
-        }
-    }
-
-    fn load_from_state(state: Self::PersistentType) -> Self {
-        Self {
-            light_color: Color32::load_from_state(state.light_color),
-            light_color_highlight: Color32::load_from_state(state.light_color_highlight),
-            light_color_threat: Color32::load_from_state(state.light_color_threat),
-            dark_color: Color32::load_from_state(state.dark_color),
-            dark_color_highlight: Color32::load_from_state(state.dark_color_highlight),
-            dark_color_threat: Color32::load_from_state(state.dark_color_threat),
-            dragging_from: None,
-            threat_squares: vec![],
-            target_square_map: Default::default(),
-            dirty: true,
-        }
-    }
-}
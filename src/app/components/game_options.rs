@@ -0,0 +1,56 @@
+use crate::api::matchmaking::{format_time_control, ColorPreference, TimeControl};
+use egui::{ComboBox, Grid, Ui};
+
+/// Time control and colour selection shared by game creation and quick matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameOptionsComponent {
+    pub time_control: Option<TimeControl>,
+    pub color: ColorPreference,
+}
+
+impl Default for GameOptionsComponent {
+    fn default() -> Self {
+        Self {
+            time_control: Some(TimeControl::new(10, 0)),
+            color: ColorPreference::default(),
+        }
+    }
+}
+
+impl GameOptionsComponent {
+    pub fn render(&mut self, ui: &mut Ui, id_salt: &str) {
+        Grid::new(format!("{id_salt}_grid"))
+            .num_columns(2)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("Time control");
+                ComboBox::from_id_salt(format!("{id_salt}_time_control"))
+                    .selected_text(format_time_control(self.time_control))
+                    .show_ui(ui, |ui| {
+                        for time_control in TimeControl::PRESETS {
+                            ui.selectable_value(
+                                &mut self.time_control,
+                                Some(time_control),
+                                time_control.to_string(),
+                            );
+                        }
+                        ui.selectable_value(
+                            &mut self.time_control,
+                            None,
+                            format_time_control(None),
+                        );
+                    });
+                ui.end_row();
+
+                ui.label("Colour");
+                ComboBox::from_id_salt(format!("{id_salt}_color"))
+                    .selected_text(self.color.to_string())
+                    .show_ui(ui, |ui| {
+                        for color in ColorPreference::ALL {
+                            ui.selectable_value(&mut self.color, color, color.to_string());
+                        }
+                    });
+                ui.end_row();
+            });
+    }
+}
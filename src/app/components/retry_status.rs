@@ -0,0 +1,25 @@
+use crate::api::retry::RetryStatus;
+use egui::Ui;
+use std::time::{Duration, Instant};
+
+/// Shows a spinner, plus the countdown while a failed request waits to be retried.
+pub fn render_loading(ui: &mut Ui, retry_status: Option<RetryStatus>) {
+    ui.horizontal(|ui| {
+        ui.spinner();
+        let Some(retry_status) = retry_status else {
+            return;
+        };
+
+        let retry_in = retry_status
+            .retry_at
+            .saturating_duration_since(Instant::now());
+        ui.label(format!(
+            "Retrying in {}s (attempt {}/{})...",
+            retry_in.as_secs() + 1,
+            retry_status.attempt,
+            retry_status.max_retries
+        ))
+        .on_hover_text(&retry_status.reason);
+        ui.ctx().request_repaint_after(Duration::from_millis(200));
+    });
+}
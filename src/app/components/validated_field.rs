@@ -5,6 +5,7 @@ pub struct ValidatedField<'a> {
     value: &'a mut String,
     validator: Box<dyn Fn(&str) -> bool>,
     error_message: &'a str,
+    server_errors: &'a [String],
     is_password: bool,
     label_width: f32,
 }
@@ -21,6 +22,7 @@ impl<'a> ValidatedField<'a> {
             value,
             validator: Box::new(|_| true),
             error_message: "",
+            server_errors: &[],
             is_password: false,
             label_width: 50.0,
         }
@@ -36,6 +38,12 @@ impl<'a> ValidatedField<'a> {
         self
     }
 
+    /// Errors the server reported for the last submitted value, shown below the field.
+    pub fn server_errors(mut self, errors: Option<&'a [String]>) -> Self {
+        self.server_errors = errors.unwrap_or_default();
+        self
+    }
+
     pub fn password(mut self) -> Self {
         self.is_password = true;
         self
@@ -60,7 +68,7 @@ impl<'a> ValidatedField<'a> {
                     text_edit_response.show_tooltip_text(self.error_message);
                 }
 
-                if is_valid {
+                if is_valid && self.server_errors.is_empty() {
                     ui.label("✅");
                 } else {
                     ui.colored_label(ui.visuals().error_fg_color, "❌");
@@ -70,6 +78,10 @@ impl<'a> ValidatedField<'a> {
             })
             .inner;
 
+        for error in self.server_errors {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
         ValidatedFieldResponse { is_valid, response }
     }
 }
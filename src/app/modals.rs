@@ -1,20 +1,27 @@
 use crate::app::state::AppState;
 use egui::{Context, Id, Ui};
 
+pub mod challenge;
+pub mod create_game;
 pub mod login;
 pub mod login_or_register;
+pub mod profiles;
 pub mod register;
 pub mod server_settings;
+pub mod settings_bundle;
 
 pub trait Modal {
     fn id(&self) -> Id;
     fn set_open(&mut self, open: bool);
     fn is_open(&self) -> bool;
 
-    fn render_content(&mut self, ui: &mut Ui, state: &mut AppState) -> ModalEvent {
+    fn render_content(&mut self, _ui: &mut Ui, _state: &mut AppState) -> ModalEvent {
         ModalEvent::None
     }
 
+    /// Called when the user dismisses the modal, e.g. to cancel requests it started.
+    fn on_dismiss(&mut self, _state: &mut AppState) {}
+
     fn render(&mut self, ctx: &Context, state: &mut AppState) -> ModalEvent {
         if !self.is_open() {
             return ModalEvent::None;
@@ -27,6 +34,7 @@ pub trait Modal {
 
         if modal_response.should_close() {
             self.set_open(false);
+            self.on_dismiss(state);
         }
 
         modal_event
@@ -42,4 +50,7 @@ pub enum ModalEvent {
     LoginSuccess,
     RegisterSuccess,
     SetServerSettings,
+    SelectProfile,
+    CreateGame,
+    SendChallenge,
 }
@@ -0,0 +1,59 @@
+use crate::app::components::game_options::GameOptionsComponent;
+use crate::app::modals::{Modal, ModalEvent};
+use crate::app::state::AppState;
+use egui::{Button, Id, Ui};
+
+/// Picks the time control and colour for a challenge to a friend.
+#[derive(Debug, Default)]
+pub struct ChallengeModal {
+    open: bool,
+    username: String,
+    options: GameOptionsComponent,
+}
+
+impl ChallengeModal {
+    pub fn open_for(&mut self, username: impl Into<String>) {
+        self.username = username.into();
+        self.set_open(true);
+    }
+}
+
+impl Modal for ChallengeModal {
+    fn id(&self) -> Id {
+        Id::new("challenge_modal")
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn render_content(&mut self, ui: &mut Ui, state: &mut AppState) -> ModalEvent {
+        ui.heading(format!("Challenge {}", self.username));
+        self.options.render(ui, "challenge_options");
+
+        let mut event = ModalEvent::None;
+        ui.horizontal(|ui| {
+            let can_challenge = state.matchmaking.lock().is_idle();
+            if ui
+                .add_enabled(can_challenge, Button::new("Send Challenge"))
+                .clicked()
+            {
+                state.challenge(
+                    &self.username,
+                    self.options.time_control,
+                    self.options.color,
+                );
+                event = ModalEvent::SendChallenge;
+            }
+            if ui.button("Cancel").clicked() {
+                self.set_open(false);
+            }
+        });
+
+        event
+    }
+}
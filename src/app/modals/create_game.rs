@@ -0,0 +1,73 @@
+use crate::app::components::game_options::GameOptionsComponent;
+use crate::app::components::validated_field::ValidatedField;
+use crate::app::modals::{Modal, ModalEvent};
+use crate::app::state::AppState;
+use crate::app::validation::validate_room_name;
+use egui::{Button, Id, Ui};
+
+#[derive(Debug)]
+pub struct CreateGameModal {
+    open: bool,
+    name: String,
+    public: bool,
+    options: GameOptionsComponent,
+}
+
+impl Default for CreateGameModal {
+    fn default() -> Self {
+        Self {
+            open: false,
+            name: String::new(),
+            public: true,
+            options: GameOptionsComponent::default(),
+        }
+    }
+}
+
+impl Modal for CreateGameModal {
+    fn id(&self) -> Id {
+        Id::new("create_game_modal")
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn render_content(&mut self, ui: &mut Ui, state: &mut AppState) -> ModalEvent {
+        ui.heading("Create Game");
+
+        let name_response = ValidatedField::new("Name", &mut self.name)
+            .label_width(60.0)
+            .validator(validate_room_name)
+            .error_message("Name is optional, otherwise it must be between 3 and 50 characters and only contain alphanumeric characters.")
+            .show(ui);
+
+        ui.checkbox(&mut self.public, "Show in open games")
+            .on_hover_text("Private games can only be joined by sharing their ID.");
+        self.options.render(ui, "create_game_options");
+
+        let mut event = ModalEvent::None;
+        ui.horizontal(|ui| {
+            let can_create = name_response.is_valid && state.matchmaking.lock().is_idle();
+            if ui.add_enabled(can_create, Button::new("Create")).clicked() {
+                let name = (!self.name.is_empty()).then(|| self.name.clone());
+                state.create_game(
+                    name,
+                    self.public,
+                    self.options.time_control,
+                    self.options.color,
+                );
+                event = ModalEvent::CreateGame;
+            }
+            if ui.button("Cancel").clicked() {
+                self.set_open(false);
+            }
+        });
+
+        event
+    }
+}
@@ -1,6 +1,9 @@
+use crate::api::login::{LoginState, LoginStateStatus};
+use crate::api::request::RequestHandle;
+use crate::api::server_info::Capability;
+use crate::app::components::retry_status::render_loading;
 use crate::app::components::validated_field::ValidatedField;
 use crate::app::modals::{Modal, ModalEvent};
-use crate::app::state::login::{LoginState, LoginStateStatus};
 use crate::app::state::AppState;
 use crate::app::validation::{validate_password, validate_username};
 use egui::{Button, Id, Ui};
@@ -9,8 +12,11 @@ use egui::{Button, Id, Ui};
 pub struct LoginModal {
     open: bool,
     just_opened: bool,
+    request: Option<RequestHandle>,
+    session_expired: bool,
     username: String,
     password: String,
+    remember_me: bool,
 }
 
 impl Modal for LoginModal {
@@ -27,16 +33,40 @@ impl Modal for LoginModal {
         self.open
     }
 
+    /// A response arriving after the modal was closed must not change the login state anymore.
+    fn on_dismiss(&mut self, state: &mut AppState) {
+        if let Some(request) = self.request.take()
+            && !request.is_finished()
+        {
+            request.cancel();
+            state.login_state().set(LoginState::Idle);
+        }
+    }
+
     fn render_content(&mut self, ui: &mut Ui, state: &mut AppState) -> ModalEvent {
         if self.just_opened {
-            state.login_state.set(LoginState::Idle);
+            self.session_expired =
+                state.login_state().lock().get_status() == LoginStateStatus::Expired;
+            state.login_state().set(LoginState::Idle);
             self.just_opened = false;
         }
 
+        if self.session_expired {
+            ui.label("Your session has expired, please log in again.");
+        }
+
+        let field_errors = state
+            .login_state()
+            .lock()
+            .get_field_errors()
+            .cloned()
+            .unwrap_or_default();
+
         let username_response = ValidatedField::new("Username", &mut self.username)
             .label_width(60.0)
             .validator(validate_username)
             .error_message("Username must be between 3 and 50 characters and only contain alphanumeric characters.")
+            .server_errors(field_errors.get("username"))
             .show(ui);
 
         let password_response = ValidatedField::new("Password", &mut self.password)
@@ -44,9 +74,27 @@ impl Modal for LoginModal {
             .validator(validate_password)
             .error_message("Password must be at least 8 characters long.")
             .password()
+            .server_errors(field_errors.get("password"))
             .show(ui);
 
-        let login_status = state.login_state.lock().get_status();
+        // Errors reported by the server no longer apply once the field was edited
+        for (field, response) in [
+            ("username", &username_response),
+            ("password", &password_response),
+        ] {
+            if response.response.changed() {
+                state
+                    .login_state()
+                    .with(|login_state| login_state.clear_field_error(field));
+            }
+        }
+
+        if state.api.server_info().supports(Capability::RefreshTokens) {
+            ui.checkbox(&mut self.remember_me, "Remember me")
+                .on_hover_text("Keeps you logged in on this device, your password is not stored.");
+        }
+
+        let login_status = state.login_state().lock().get_status();
         if login_status.is_not_successful_nor_loading()
             && ui
                 .add_enabled(
@@ -55,21 +103,21 @@ impl Modal for LoginModal {
                 )
                 .clicked()
         {
-            state.login(&self.username, &self.password);
+            self.request = Some(state.login(&self.username, &self.password, self.remember_me));
         }
 
         let mut event = ModalEvent::None;
         match login_status {
-            LoginStateStatus::Idle => {}
+            LoginStateStatus::Idle | LoginStateStatus::Expired => {}
             LoginStateStatus::Loading => {
-                ui.spinner();
+                render_loading(ui, state.api.retry_status());
             }
             LoginStateStatus::Success => {
                 ui.label("Successfully logged in");
                 event = ModalEvent::LoginSuccess;
             }
             LoginStateStatus::Error => {
-                if let Some(error) = state.login_state.lock().get_error() {
+                if let Some(error) = state.login_state().lock().get_error() {
                     ui.label(error);
                 }
             }
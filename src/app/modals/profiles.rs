@@ -0,0 +1,193 @@
+use crate::app::components::validated_field::ValidatedField;
+use crate::app::modals::{Modal, ModalEvent};
+use crate::app::state::AppState;
+use crate::app::validation::validate_profile_name;
+use crate::persistence::profile;
+use egui::{Button, Grid, Id, RichText, Ui};
+
+const PROFILE_NAME_ERROR: &str = "Profile name must be between 1 and 32 characters and only contain alphanumeric characters, spaces, dashes or underscores.";
+
+#[derive(Debug, Default)]
+pub struct ProfilesModal {
+    open: bool,
+    just_opened: bool,
+    profiles: Vec<String>,
+    new_profile_name: String,
+    renaming: Option<String>,
+    rename_input: String,
+    deleting: Option<String>,
+    error: Option<String>,
+}
+
+impl ProfilesModal {
+    fn refresh_profiles(&mut self) {
+        self.profiles = profile::list_profiles();
+    }
+
+    fn render_profile_list(&mut self, ui: &mut Ui, state: &mut AppState) -> ModalEvent {
+        let mut event = ModalEvent::None;
+        let mut rename_clicked = None;
+        let mut delete_clicked = None;
+
+        Grid::new("profile_list")
+            .num_columns(4)
+            .spacing([10.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for name in &self.profiles {
+                    let is_current = *name == state.profile;
+                    if is_current {
+                        ui.label(RichText::new(name).strong());
+                    } else {
+                        ui.label(name);
+                    }
+
+                    if ui.add_enabled(!is_current, Button::new("Select")).clicked() {
+                        state.request_profile_switch(name);
+                        event = ModalEvent::SelectProfile;
+                    }
+                    if ui.button("Rename").clicked() {
+                        rename_clicked = Some(name.clone());
+                    }
+                    if ui.add_enabled(!is_current, Button::new("Delete")).clicked() {
+                        delete_clicked = Some(name.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(name) = rename_clicked {
+            self.rename_input = name.clone();
+            self.renaming = Some(name);
+            self.deleting = None;
+        }
+        if let Some(name) = delete_clicked {
+            self.deleting = Some(name);
+            self.renaming = None;
+        }
+
+        event
+    }
+
+    fn render_rename(&mut self, ui: &mut Ui, state: &mut AppState) {
+        let Some(old_name) = self.renaming.clone() else {
+            return;
+        };
+
+        ui.separator();
+        let rename_response = ValidatedField::new("New name", &mut self.rename_input)
+            .label_width(80.0)
+            .validator(validate_profile_name)
+            .error_message(PROFILE_NAME_ERROR)
+            .show(ui);
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(rename_response.is_valid, Button::new("Rename"))
+                .clicked()
+            {
+                match profile::rename_profile(&old_name, &self.rename_input) {
+                    Ok(()) => {
+                        if state.profile == old_name {
+                            state.profile = self.rename_input.clone();
+                        }
+                        self.renaming = None;
+                        self.error = None;
+                        self.refresh_profiles();
+                    }
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            if ui.button("Cancel").clicked() {
+                self.renaming = None;
+            }
+        });
+    }
+
+    fn render_delete_confirmation(&mut self, ui: &mut Ui) {
+        let Some(name) = self.deleting.clone() else {
+            return;
+        };
+
+        ui.separator();
+        ui.label(format!(
+            "Delete profile '{name}'? All of its saved data will be lost."
+        ));
+        ui.horizontal(|ui| {
+            if ui.button("Delete").clicked() {
+                match profile::delete_profile(&name) {
+                    Ok(()) => {
+                        self.deleting = None;
+                        self.error = None;
+                        self.refresh_profiles();
+                    }
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            if ui.button("Cancel").clicked() {
+                self.deleting = None;
+            }
+        });
+    }
+
+    fn render_creation(&mut self, ui: &mut Ui) {
+        ui.separator();
+        let name_response = ValidatedField::new("New profile", &mut self.new_profile_name)
+            .label_width(80.0)
+            .validator(validate_profile_name)
+            .error_message(PROFILE_NAME_ERROR)
+            .show(ui);
+
+        if ui
+            .add_enabled(name_response.is_valid, Button::new("Create"))
+            .clicked()
+        {
+            match profile::create_profile(&self.new_profile_name) {
+                Ok(()) => {
+                    self.new_profile_name.clear();
+                    self.error = None;
+                    self.refresh_profiles();
+                }
+                Err(err) => self.error = Some(err.to_string()),
+            }
+        }
+    }
+}
+
+impl Modal for ProfilesModal {
+    fn id(&self) -> Id {
+        Id::new("profiles_modal")
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+        self.just_opened = open;
+    }
+
+    fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn render_content(&mut self, ui: &mut Ui, state: &mut AppState) -> ModalEvent {
+        if self.just_opened {
+            self.refresh_profiles();
+            self.renaming = None;
+            self.deleting = None;
+            self.error = None;
+            self.just_opened = false;
+        }
+
+        ui.vertical_centered(|ui| ui.heading("Profiles"));
+
+        let event = self.render_profile_list(ui, state);
+        self.render_rename(ui, state);
+        self.render_delete_confirmation(ui);
+        self.render_creation(ui);
+
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        event
+    }
+}
@@ -1,6 +1,8 @@
+use crate::api::login::{LoginState, LoginStateStatus};
+use crate::api::request::RequestHandle;
+use crate::app::components::retry_status::render_loading;
 use crate::app::components::validated_field::ValidatedField;
 use crate::app::modals::{Modal, ModalEvent};
-use crate::app::state::login::{LoginState, LoginStateStatus};
 use crate::app::state::AppState;
 use crate::app::validation::{validate_invite_code, validate_password, validate_username};
 use egui::{Button, Id, Ui};
@@ -12,6 +14,7 @@ pub struct RegisterModal {
     invite_code: String,
     open: bool,
     just_opened: bool,
+    request: Option<RequestHandle>,
 }
 
 impl Modal for RegisterModal {
@@ -28,16 +31,34 @@ impl Modal for RegisterModal {
         self.open
     }
 
+    /// A response arriving after the modal was closed must not change the login state anymore.
+    fn on_dismiss(&mut self, state: &mut AppState) {
+        if let Some(request) = self.request.take()
+            && !request.is_finished()
+        {
+            request.cancel();
+            state.login_state().set(LoginState::Idle);
+        }
+    }
+
     fn render_content(&mut self, ui: &mut Ui, state: &mut AppState) -> ModalEvent {
         if self.just_opened {
-            state.login_state.set(LoginState::Idle);
+            state.login_state().set(LoginState::Idle);
             self.just_opened = false;
         }
 
+        let field_errors = state
+            .login_state()
+            .lock()
+            .get_field_errors()
+            .cloned()
+            .unwrap_or_default();
+
         let username_response = ValidatedField::new("Username", &mut self.username)
             .label_width(65.0)
             .validator(validate_username)
             .error_message("Username must be between 3 and 50 characters and only contain alphanumeric characters.")
+            .server_errors(field_errors.get("username"))
             .show(ui);
 
         let password_response = ValidatedField::new("Password", &mut self.password)
@@ -45,15 +66,30 @@ impl Modal for RegisterModal {
             .validator(validate_password)
             .error_message("Password must be at least 8 characters long.")
             .password()
+            .server_errors(field_errors.get("password"))
             .show(ui);
 
         let invite_code_response = ValidatedField::new("Invite code", &mut self.invite_code)
             .label_width(65.0)
             .validator(validate_invite_code)
             .error_message("Invite code must be a valid uuid.")
+            .server_errors(field_errors.get("invite_code"))
             .show(ui);
 
-        let login_status = state.login_state.lock().get_status();
+        // Errors reported by the server no longer apply once the field was edited
+        for (field, response) in [
+            ("username", &username_response),
+            ("password", &password_response),
+            ("invite_code", &invite_code_response),
+        ] {
+            if response.response.changed() {
+                state
+                    .login_state()
+                    .with(|login_state| login_state.clear_field_error(field));
+            }
+        }
+
+        let login_status = state.login_state().lock().get_status();
         if login_status.is_not_successful_nor_loading()
             && ui
                 .add_enabled(
@@ -64,21 +100,21 @@ impl Modal for RegisterModal {
                 )
                 .clicked()
         {
-            state.register(&self.username, &self.password, &self.invite_code);
+            self.request = Some(state.register(&self.username, &self.password, &self.invite_code));
         }
 
         let mut event = ModalEvent::None;
         match login_status {
-            LoginStateStatus::Idle => {}
+            LoginStateStatus::Idle | LoginStateStatus::Expired => {}
             LoginStateStatus::Loading => {
-                ui.spinner();
+                render_loading(ui, state.api.retry_status());
             }
             LoginStateStatus::Success => {
                 ui.label("Successfully registered");
                 event = ModalEvent::RegisterSuccess;
             }
             LoginStateStatus::Error => {
-                if let Some(error) = state.login_state.lock().get_error() {
+                if let Some(error) = state.login_state().lock().get_error() {
                     ui.label(error);
                 }
             }
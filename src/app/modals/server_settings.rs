@@ -1,16 +1,256 @@
+use crate::api::live::LiveTransport;
+use crate::api::network::{ClientCertificate, NetworkSettings, ProxyMode};
+use crate::api::retry::RetryPolicy;
+use crate::api::server_profile::{HealthReport, ServerProfile};
 use crate::app::components::validated_field::ValidatedField;
 use crate::app::modals::{Modal, ModalEvent};
 use crate::app::state::AppState;
-use crate::app::validation::validate_url;
-use egui::{Button, Id, Ui};
+use crate::app::validation::{
+    validate_file_path, validate_proxy_url, validate_server_name, validate_url,
+};
+use crate::types::shared::Shared;
+use egui::{Button, ComboBox, DragValue, Grid, Id, RichText, TextEdit, Ui};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+enum HealthCheck {
+    Running,
+    Done(HealthReport),
+    Failed(String),
+}
 
 #[derive(Debug, Default)]
 pub struct ServerSettingsModal {
     open: bool,
     url: String,
+    profile_name: String,
+    live_transport: LiveTransport,
+    retry_policy: RetryPolicy,
+    network_settings: NetworkSettings,
+    /// One PEM file per line.
+    root_certificates: String,
+    client_certificate_path: String,
+    client_key_path: String,
+    network_error: Option<String>,
+    /// Results of connection tests, keyed by the tested url.
+    health_checks: Shared<HashMap<String, HealthCheck>>,
     initialized: bool,
 }
 
+impl ServerSettingsModal {
+    fn test_connection(&self, ui: &Ui, state: &AppState, url: &str) {
+        let health_checks = self.health_checks.clone();
+        health_checks.with(|checks| checks.insert(url.to_string(), HealthCheck::Running));
+
+        let checked_url = url.to_string();
+        let ctx = ui.ctx().clone();
+        state.api.check_health(url, move |result| {
+            let check = match result {
+                Ok(report) => HealthCheck::Done(report),
+                Err(err) => HealthCheck::Failed(err.to_string()),
+            };
+            health_checks.with(|checks| checks.insert(checked_url, check));
+            ctx.request_repaint();
+        });
+    }
+
+    fn render_health_check(&self, ui: &mut Ui, url: &str) {
+        let check = self.health_checks.lock().get(url).cloned();
+        match check {
+            None => {
+                ui.label("");
+            }
+            Some(HealthCheck::Running) => {
+                ui.spinner();
+            }
+            Some(HealthCheck::Done(report)) => {
+                let version = report.version.as_deref().unwrap_or("unknown version");
+                ui.label(format!("{} ms, {version}", report.latency.as_millis()));
+            }
+            Some(HealthCheck::Failed(error)) => {
+                ui.colored_label(ui.visuals().error_fg_color, "Unreachable")
+                    .on_hover_text(error);
+            }
+        }
+    }
+
+    fn render_server_profiles(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.label(RichText::new("Saved servers").strong());
+        let profiles = state.api.get_server_profiles().to_vec();
+        if profiles.is_empty() {
+            ui.label("No saved servers yet, save the URL below to switch to it quickly.");
+            return;
+        }
+
+        let active_url = state.api.get_server_url().map(str::to_string);
+        let mut used = None;
+        let mut tested = None;
+        let mut removed = None;
+        Grid::new("server_profiles_grid")
+            .num_columns(4)
+            .spacing([10.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for profile in &profiles {
+                    let is_active = active_url.as_deref() == Some(profile.url.as_str());
+                    if is_active {
+                        ui.label(RichText::new(&profile.name).strong())
+                    } else {
+                        ui.label(&profile.name)
+                    }
+                    .on_hover_text(&profile.url);
+
+                    self.render_health_check(ui, &profile.url);
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(!is_active, Button::new("Use")).clicked() {
+                            used = Some(profile.clone());
+                        }
+                        if ui.button("Test").clicked() {
+                            tested = Some(profile.url.clone());
+                        }
+                        if ui.button("Remove").clicked() {
+                            removed = Some(profile.name.clone());
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+        if let Some(profile) = used {
+            self.url = profile.url.clone();
+            state.set_multiplayer_url(profile.url);
+        }
+        if let Some(url) = tested {
+            self.test_connection(ui, state, &url);
+        }
+        if let Some(name) = removed {
+            state.remove_server_profile(&name);
+            state.requested_save = true;
+        }
+    }
+}
+
+impl ServerSettingsModal {
+    fn load_network_settings(&mut self, state: &AppState) {
+        self.network_settings = state.api.get_network_settings().clone();
+        self.root_certificates = self
+            .network_settings
+            .root_certificates
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let client_certificate = self.network_settings.client_certificate.clone();
+        self.client_certificate_path = client_certificate
+            .as_ref()
+            .map(|certificate| certificate.certificate_path.display().to_string())
+            .unwrap_or_default();
+        self.client_key_path = client_certificate
+            .map(|certificate| certificate.key_path.display().to_string())
+            .unwrap_or_default();
+        self.network_error = state.api.get_network_error().map(str::to_string);
+    }
+
+    fn get_network_settings(&self) -> NetworkSettings {
+        let root_certificates = self
+            .root_certificates
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        let client_certificate = match (
+            self.client_certificate_path.trim(),
+            self.client_key_path.trim(),
+        ) {
+            ("", "") => None,
+            (certificate_path, key_path) => Some(ClientCertificate {
+                certificate_path: certificate_path.into(),
+                key_path: key_path.into(),
+            }),
+        };
+        NetworkSettings {
+            root_certificates,
+            client_certificate,
+            proxy_url: self.network_settings.proxy_url.trim().to_string(),
+            ..self.network_settings.clone()
+        }
+    }
+
+    /// Returns whether the inputs are valid, file contents are only checked when saving.
+    fn render_tls_and_proxy(&mut self, ui: &mut Ui) -> bool {
+        let mut is_valid = true;
+        ui.collapsing("TLS & Proxy", |ui| {
+            ui.label("Trusted certificates (PEM files, one per line)");
+            ui.add(
+                TextEdit::multiline(&mut self.root_certificates)
+                    .desired_rows(2)
+                    .desired_width(f32::INFINITY),
+            );
+            is_valid &= self.root_certificates.lines().all(validate_file_path);
+
+            ui.add_space(5.0);
+            ui.label("Client certificate (optional)");
+            is_valid &= ValidatedField::new("Certificate", &mut self.client_certificate_path)
+                .label_width(70.0)
+                .validator(validate_file_path)
+                .error_message("File not found.")
+                .show(ui)
+                .is_valid;
+            is_valid &= ValidatedField::new("Key", &mut self.client_key_path)
+                .label_width(70.0)
+                .validator(validate_file_path)
+                .error_message("File not found.")
+                .show(ui)
+                .is_valid;
+
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Proxy");
+                ComboBox::from_id_salt("proxy_mode_combo")
+                    .selected_text(self.network_settings.proxy_mode.to_string())
+                    .show_ui(ui, |ui| {
+                        for mode in ProxyMode::ALL {
+                            ui.selectable_value(
+                                &mut self.network_settings.proxy_mode,
+                                mode,
+                                mode.to_string(),
+                            );
+                        }
+                    });
+            });
+            if self.network_settings.uses_custom_proxy() {
+                is_valid &= ValidatedField::new("Proxy URL", &mut self.network_settings.proxy_url)
+                    .label_width(70.0)
+                    .validator(validate_proxy_url)
+                    .error_message("Must be an http, https or socks5 URL.")
+                    .show(ui)
+                    .is_valid;
+                ui.label("Online games use long polling, since WebSockets can't use the proxy.");
+            }
+
+            ui.add_space(5.0);
+            ui.checkbox(
+                &mut self.network_settings.allow_insecure,
+                "Allow insecure connections",
+            )
+            .on_hover_text("Skips certificate verification, only use this for local development.");
+            if self.network_settings.allow_insecure {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "Certificates are not verified, anyone on the network could read your data.",
+                );
+            }
+        });
+
+        if let Some(error) = &self.network_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        is_valid
+    }
+}
+
 impl Modal for ServerSettingsModal {
     fn id(&self) -> Id {
         Id::new("server_settings_modal")
@@ -27,21 +267,123 @@ impl Modal for ServerSettingsModal {
     fn render_content(&mut self, ui: &mut Ui, state: &mut AppState) -> ModalEvent {
         if !self.initialized {
             self.url = state.api.get_server_url().unwrap_or_default().to_string();
+            self.live_transport = state.api.get_live_transport();
+            self.retry_policy = state.api.get_retry_policy();
+            self.load_network_settings(state);
             self.initialized = true;
         }
 
+        self.render_server_profiles(ui, state);
+        ui.separator();
+
         let server_url_response = ValidatedField::new("Server URL", &mut self.url)
             .label_width(60.0)
             .validator(validate_url)
             .error_message("Must be a valid URL.")
             .show(ui);
 
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(server_url_response.is_valid, Button::new("Test connection"))
+                .clicked()
+            {
+                self.test_connection(ui, state, &self.url);
+            }
+            self.render_health_check(ui, &self.url);
+        });
+
+        ui.horizontal(|ui| {
+            let name_response = ValidatedField::new("Save as", &mut self.profile_name)
+                .label_width(60.0)
+                .validator(validate_server_name)
+                .error_message("Name must be between 1 and 32 characters.")
+                .show(ui);
+            if ui
+                .add_enabled(
+                    server_url_response.is_valid && name_response.is_valid,
+                    Button::new("Save Server"),
+                )
+                .clicked()
+            {
+                state
+                    .api
+                    .save_server_profile(ServerProfile::new(self.profile_name.trim(), &self.url));
+                state.requested_save = true;
+                self.profile_name.clear();
+            }
+        });
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Online games");
+            ComboBox::from_id_salt("live_transport_combo")
+                .selected_text(self.live_transport.to_string())
+                .show_ui(ui, |ui| {
+                    for transport in LiveTransport::ALL {
+                        ui.selectable_value(
+                            &mut self.live_transport,
+                            transport,
+                            transport.to_string(),
+                        );
+                    }
+                });
+        })
+        .response
+        .on_hover_text("Long polling works on networks which block WebSockets.");
+
+        ui.collapsing("Network", |ui| {
+            Grid::new("server_settings_network_grid")
+                .num_columns(2)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Connect timeout");
+                    ui.add(
+                        DragValue::new(&mut self.retry_policy.connect_timeout_secs)
+                            .range(1..=60)
+                            .suffix(" s"),
+                    );
+                    ui.end_row();
+
+                    ui.label("Request timeout");
+                    ui.add(
+                        DragValue::new(&mut self.retry_policy.request_timeout_secs)
+                            .range(1..=120)
+                            .suffix(" s"),
+                    );
+                    ui.end_row();
+
+                    ui.label("Retries");
+                    ui.add(DragValue::new(&mut self.retry_policy.max_retries).range(0..=10))
+                        .on_hover_text(
+                            "Failed requests are retried with increasing delays if the server \
+                             is unreachable or busy.",
+                        );
+                    ui.end_row();
+                });
+            if ui.button("Reset to defaults").clicked() {
+                self.retry_policy = RetryPolicy::default();
+            }
+        });
+
+        let network_valid = self.render_tls_and_proxy(ui);
+
         let mut event = ModalEvent::None;
         if ui
-            .add_enabled(server_url_response.is_valid, Button::new("Save"))
+            .add_enabled(
+                server_url_response.is_valid && network_valid,
+                Button::new("Save"),
+            )
             .clicked()
         {
-            state.api.set_server_url(&self.url);
+            // Applied first, so the handshake with the new server already uses them
+            if let Err(err) = state.api.set_network_settings(self.get_network_settings()) {
+                self.network_error = Some(err.to_string());
+                return event;
+            }
+            self.network_error = None;
+            state.set_multiplayer_url(&self.url);
+            state.api.set_live_transport(self.live_transport);
+            state.api.set_retry_policy(self.retry_policy);
             self.set_open(false);
             event = ModalEvent::SetServerSettings;
         }
@@ -0,0 +1,173 @@
+use crate::app::components::validated_field::ValidatedField;
+use crate::app::modals::{Modal, ModalEvent};
+use crate::app::state::settings_bundle::{
+    SettingsBundle, SettingsChange, SETTINGS_BUNDLE_FILE_NAME, SETTINGS_BUNDLE_VERSION,
+};
+use crate::app::state::AppState;
+use crate::persistence;
+use egui::{Button, Grid, Id, Ui};
+use std::path::Path;
+
+#[derive(Debug, Default)]
+pub struct SettingsBundleModal {
+    open: bool,
+    just_opened: bool,
+    export_path: String,
+    import_path: String,
+    preview: Option<(SettingsBundle, Vec<SettingsChange>)>,
+    message: Option<String>,
+    error: Option<String>,
+}
+
+impl SettingsBundleModal {
+    fn render_export(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.heading("Export");
+        ui.label("Server settings and board theme, your login is never exported.");
+
+        let path_response = ValidatedField::new("File", &mut self.export_path)
+            .label_width(40.0)
+            .validator(|path| !path.trim().is_empty())
+            .error_message("Must not be empty.")
+            .show(ui);
+
+        if ui
+            .add_enabled(path_response.is_valid, Button::new("Export"))
+            .clicked()
+        {
+            let bundle = state.export_settings();
+            match persistence::export_to_file(Path::new(&self.export_path), &bundle) {
+                Ok(()) => {
+                    self.message = Some(format!("Exported settings to {}", self.export_path));
+                    self.error = None;
+                }
+                Err(err) => self.error = Some(err.to_string()),
+            }
+        }
+    }
+
+    fn render_import(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.heading("Import");
+
+        let path_response = ValidatedField::new("File", &mut self.import_path)
+            .label_width(40.0)
+            .validator(|path| !path.trim().is_empty())
+            .error_message("Must not be empty.")
+            .show(ui);
+
+        if ui
+            .add_enabled(path_response.is_valid, Button::new("Preview"))
+            .clicked()
+        {
+            self.load_preview(state);
+        }
+
+        let Some((bundle, changes)) = &self.preview else {
+            return;
+        };
+
+        if changes.is_empty() {
+            ui.label("The imported settings match the current ones.");
+        } else {
+            Grid::new("settings_import_preview")
+                .num_columns(3)
+                .spacing([20.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Setting");
+                    ui.strong("Current");
+                    ui.strong("Imported");
+                    ui.end_row();
+
+                    for change in changes {
+                        ui.label(change.setting);
+                        ui.label(&change.current);
+                        ui.label(&change.imported);
+                        ui.end_row();
+                    }
+                });
+        }
+
+        let bundle = bundle.clone();
+        ui.horizontal(|ui| {
+            if ui.button("Apply").clicked() {
+                state.import_settings(bundle);
+                self.preview = None;
+                self.message = Some("Imported settings.".to_string());
+                self.error = None;
+            }
+            if ui.button("Cancel").clicked() {
+                self.preview = None;
+            }
+        });
+    }
+
+    fn load_preview(&mut self, state: &AppState) {
+        self.preview = None;
+        self.message = None;
+
+        let bundle: SettingsBundle =
+            match persistence::import_from_file(Path::new(&self.import_path)) {
+                Ok(bundle) => bundle,
+                Err(err) => {
+                    self.error = Some(err.to_string());
+                    return;
+                }
+            };
+
+        if bundle.version > SETTINGS_BUNDLE_VERSION {
+            self.error =
+                Some("These settings were exported by a newer version of Giga Chess.".to_string());
+            return;
+        }
+
+        let changes = state.export_settings().diff(&bundle);
+        self.preview = Some((bundle, changes));
+        self.error = None;
+    }
+}
+
+impl Modal for SettingsBundleModal {
+    fn id(&self) -> Id {
+        Id::new("settings_bundle_modal")
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+        self.just_opened = open;
+    }
+
+    fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn render_content(&mut self, ui: &mut Ui, state: &mut AppState) -> ModalEvent {
+        if self.just_opened {
+            let default_path = persistence::get_default_export_path(SETTINGS_BUNDLE_FILE_NAME)
+                .to_string_lossy()
+                .to_string();
+            if self.export_path.is_empty() {
+                self.export_path = default_path.clone();
+            }
+            if self.import_path.is_empty() {
+                self.import_path = default_path;
+            }
+            self.preview = None;
+            self.message = None;
+            self.error = None;
+            self.just_opened = false;
+        }
+
+        self.render_export(ui, state);
+        ui.separator();
+        self.render_import(ui, state);
+
+        if let Some(message) = &self.message {
+            ui.label(message);
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        ModalEvent::None
+    }
+}
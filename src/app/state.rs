@@ -1,23 +1,123 @@
+use crate::api::account::{ChangePasswordBody, ChangeUsernameBody, DeleteAccountBody};
+use crate::api::correspondence::{CorrespondenceBody, CorrespondenceGame};
 use crate::api::error::ApiError;
+use crate::api::friends::{Challenge, ChallengeBody, ChallengeStatus};
+use crate::api::invite_codes::InviteCodeBody;
+use crate::api::login::{LoginState, LoginStateStatus};
+use crate::api::matchmaking::{ColorPreference, CreateGameBody, QueueBody, TimeControl};
+use crate::api::player_profile::RatingCategory;
+use crate::api::request::{LatestRequest, RequestHandle};
+use crate::api::server_info::Capability;
+use crate::api::session::Session;
+use crate::api::spectating::LiveGame;
+use crate::api::tournaments::TournamentStatus;
 use crate::api::{MultiplayerClient, MultiplayerClientPersist};
 use crate::app::asset_server::AssetServer;
-use crate::app::state::login::LoginState;
+use crate::app::state::account::{AccountState, AccountUpdateState};
+use crate::app::state::board_theme::{BoardTheme, BoardThemePersist};
+use crate::app::state::correspondence::CorrespondenceState;
+use crate::app::state::friends::FriendsState;
+use crate::app::state::invite_codes::InviteCodesState;
+use crate::app::state::leaderboard::LeaderboardState;
+use crate::app::state::matchmaking::{MatchmakingState, OpenGamesState};
+use crate::app::state::online_game::OnlineGame;
+use crate::app::state::player_profile::{GameHistoryState, GameReplayState, PlayerProfileState};
+use crate::app::state::settings_bundle::{SettingsBundle, SETTINGS_BUNDLE_VERSION};
+use crate::app::state::spectating::LiveGamesState;
+use crate::app::state::tournaments::{TournamentListState, TournamentState};
 use crate::app::views::ViewID;
+use crate::cli::CliArgs;
+use crate::game::AppGame;
+use crate::logging;
 use crate::persistence::PersistentObject;
 use crate::types::shared::Shared;
-use giga_chess::prelude::Engine;
+use giga_chess::prelude::{Color, Engine};
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use giga_chess_api_types::response::user_info::PublicUserInfo;
+use log::{error, info, warn, LevelFilter};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-pub mod login;
+/// Running tournaments are listed first, the user hardly takes part in more at once.
+const JOINED_TOURNAMENTS_LIMIT: i64 = 20;
+
+pub mod account;
+pub mod board_theme;
+pub mod correspondence;
+pub mod friends;
+pub mod invite_codes;
+pub mod leaderboard;
+pub mod matchmaking;
+pub mod online_game;
+pub mod player_profile;
+pub mod settings_bundle;
+pub mod spectating;
+pub mod tournaments;
 
 #[derive(Debug, Default)]
 pub struct AppState {
     pub api: MultiplayerClient,
     pub assets: AssetServer,
+    pub board_theme: BoardTheme,
     pub engine: Arc<Engine>,
     pub current_view: ViewID,
-    pub login_state: Shared<LoginState>,
+    pub matchmaking: Shared<MatchmakingState>,
+    pub open_games: Shared<OpenGamesState>,
+    pub live_games: Shared<LiveGamesState>,
+    pub correspondence_games: Shared<CorrespondenceState>,
+    /// The last failed attempt to create a correspondence game.
+    pub correspondence_error: Shared<Option<String>>,
+    pub online_game: Option<OnlineGame>,
+    pub friends: Shared<FriendsState>,
+    pub incoming_challenges: Shared<Vec<Challenge>>,
+    /// The last failed friend request, shown above the friends list.
+    pub friends_error: Shared<Option<String>>,
+    /// The user whose profile is shown, None for the logged in one.
+    pub viewed_player: Option<String>,
+    pub player_profile: Shared<PlayerProfileState>,
+    pub game_history: Shared<GameHistoryState>,
+    pub game_replay: Shared<GameReplayState>,
+    pub leaderboard: Shared<LeaderboardState>,
+    pub tournaments: Shared<TournamentListState>,
+    pub viewed_tournament: Option<String>,
+    pub tournament: Shared<TournamentState>,
+    /// The last failed attempt to join or withdraw.
+    pub tournament_error: Shared<Option<String>>,
+    /// Running tournaments the user takes part in, polled from every view.
+    pub joined_tournaments: Shared<Vec<String>>,
+    /// The running games of the joined tournaments, keyed by tournament.
+    pub tournament_games: Shared<HashMap<String, PrivateRoomInfo>>,
+    /// Tournament games which were already opened, so leaving a finished one doesn't reopen it.
+    pub opened_tournament_games: HashSet<String>,
+    /// The role of the logged in user, loaded once the lobby is opened.
+    pub account: Shared<AccountState>,
+    pub invite_codes: Shared<InviteCodesState>,
+    /// The last failed attempt to create or revoke an invite code.
+    pub invite_code_error: Shared<Option<String>>,
+    /// Every form of the account settings keeps its own state, so errors show up next to it.
+    pub password_change: Shared<AccountUpdateState>,
+    pub username_change: Shared<AccountUpdateState>,
+    pub account_deletion: Shared<AccountUpdateState>,
+    pub profile: String,
+    /// Sessions of the servers which are not in use right now, keyed by their url.
+    pub server_sessions: HashMap<String, Session>,
+    pub requested_profile: Option<String>,
+    pub opened_game: Option<AppGame>,
+    /// Why the game passed with `--open` couldn't be opened, shown by the sandbox.
+    pub open_error: Option<String>,
+    // The latest load of every paged or filtered list, a newer load cancels the previous one
+    player_profile_request: LatestRequest,
+    game_history_request: LatestRequest,
+    game_replay_request: LatestRequest,
+    leaderboard_request: LatestRequest,
+    tournaments_request: LatestRequest,
+    tournament_request: LatestRequest,
+    live_games_request: LatestRequest,
+    open_games_request: LatestRequest,
+    /// The verbosity chosen in the log window, None until it was changed there.
+    pub log_level: Option<LevelFilter>,
+    pub requested_save: bool,
 }
 
 impl AppState {
@@ -25,25 +125,220 @@ impl AppState {
         self.current_view = view;
     }
 
+    pub fn apply_cli_args(&mut self, args: &CliArgs) {
+        if let Some(level) = args.log_level {
+            logging::set_level(level);
+        }
+        if let Some(server_url) = &args.server_url {
+            self.set_multiplayer_url(server_url);
+        }
+
+        if let Some(path) = &args.open {
+            match AppGame::from_file(&self.engine, path) {
+                Ok(game) => {
+                    self.opened_game = Some(game);
+                    self.switch_view(ViewID::Sandbox);
+                }
+                Err(err) => {
+                    error!("Failed to open '{}': {err}", path.display());
+                    self.open_error = Some(format!("Failed to open '{}': {err}", path.display()));
+                    self.switch_view(ViewID::Sandbox);
+                }
+            }
+        }
+    }
+
+    /// The switch is performed by the app after the current frame, since it replaces the whole state.
+    pub fn request_profile_switch(&mut self, profile: impl Into<String>) {
+        self.requested_profile = Some(profile.into());
+    }
+
     pub fn set_multiplayer_url(&mut self, url: impl Into<String>) {
+        let url = url.into();
+        self.switch_session(Some(&url));
         self.api.set_server_url(url);
+        self.connect();
+    }
+
+    /// Uses the server for this run only, the profile keeps its own server and session.
+    #[cfg(feature = "mock-server")]
+    pub fn set_server_url_override(&mut self, url: impl Into<String>) {
+        let url = url.into();
+        self.switch_session(Some(&url));
+        self.api.set_server_url_override(Some(url));
+        self.connect();
+    }
+
+    /// Checks whether the server is compatible and validates the session against it.
+    /// Correspondence games are reloaded, so moves made in the meantime show up right away.
+    pub fn connect(&mut self) {
+        self.api.check_server(|_| {});
+        self.validate_session();
+        if self.is_logged_in() {
+            self.refresh_correspondence_games();
+        }
+    }
+
+    /// Parks the session of the current server and restores the one of the next server, so every
+    /// server keeps its own account. Has to be called before the server url changes.
+    fn switch_session(&mut self, next_url: Option<&str>) {
+        let current_url = self.api.get_server_url().map(str::to_string);
+        if current_url.as_deref() == next_url {
+            return;
+        }
+
+        // Leaving the current game has to reach the server it is played on
+        self.cancel_matchmaking();
+        let session = self.login_state().lock().get_session().cloned();
+        if let Some(current_url) = current_url
+            && let Some(session) = session
+        {
+            self.server_sessions.insert(current_url, session);
+        }
+
+        let restored = next_url
+            .and_then(|url| self.server_sessions.remove(url))
+            .map_or(LoginState::Idle, LoginState::Success);
+        self.login_state().set(restored);
+        self.clear_friends();
+        self.correspondence_games.set(CorrespondenceState::Idle);
+        self.clear_tournaments();
+        self.account.set(AccountState::Idle);
+        if matches!(
+            self.current_view,
+            ViewID::OnlineHome
+                | ViewID::OnlineGame
+                | ViewID::PlayerProfile
+                | ViewID::Leaderboard
+                | ViewID::Tournaments
+                | ViewID::InviteCodes
+                | ViewID::Account
+        ) {
+            self.switch_view(ViewID::MainMenu);
+        }
+        self.requested_save = true;
+    }
+
+    pub fn login_state(&self) -> &Shared<LoginState> {
+        self.api.session()
+    }
+
+    pub fn get_username(&self) -> Option<String> {
+        self.login_state()
+            .lock()
+            .get_session()
+            .and_then(|session| session.username.clone())
     }
 
-    pub fn login(&mut self, username: impl Into<String>, password: impl Into<String>) {
-        let login_state = self.login_state.clone();
+    pub fn is_logged_in(&self) -> bool {
+        self.login_state().lock().get_status() == LoginStateStatus::Success
+    }
+
+    /// Checks the persisted session against the server, remembered sessions are refreshed if needed.
+    pub fn validate_session(&mut self) {
+        if !self.is_logged_in() {
+            return;
+        }
+
+        self.api.ping(|result| {
+            if let Err(ApiError::Unauthorized(_)) = result {
+                info!("Persisted session was rejected by the server");
+            }
+        });
+    }
+
+    /// The session is revoked on the server as well, so a remembered login can't be refreshed
+    /// on this device anymore.
+    pub fn logout(&mut self) {
+        if self.is_logged_in() && self.api.server_info().supports(Capability::RefreshTokens) {
+            self.api.logout(|_| {});
+        }
+        self.cancel_matchmaking();
+        self.clear_friends();
+        self.correspondence_games.set(CorrespondenceState::Idle);
+        self.clear_tournaments();
+        self.account.set(AccountState::Idle);
+        self.login_state().set(LoginState::Idle);
+        self.requested_save = true;
+        self.switch_view(ViewID::MainMenu);
+    }
+
+    /// The parked session of the profile's server is dropped as well, unless another profile
+    /// still points to the same server.
+    pub fn remove_server_profile(&mut self, name: &str) {
+        let removed_url = self
+            .api
+            .get_server_profiles()
+            .iter()
+            .find(|profile| profile.name == name)
+            .map(|profile| profile.url.clone());
+        self.api.remove_server_profile(name);
+        if let Some(url) = removed_url
+            && !self
+                .api
+                .get_server_profiles()
+                .iter()
+                .any(|profile| profile.url == url)
+        {
+            self.server_sessions.remove(&url);
+        }
+    }
+
+    pub fn export_settings(&self) -> SettingsBundle {
+        let mut api = self.api.save_state();
+        api.network_settings = api.network_settings.without_secrets();
+        SettingsBundle {
+            version: SETTINGS_BUNDLE_VERSION,
+            api,
+            board_theme: self.board_theme.save_state(),
+        }
+    }
+
+    pub fn import_settings(&mut self, mut bundle: SettingsBundle) {
+        // Exports don't contain the proxy credentials, keep them if the proxy is the same
+        let current_network = self.api.get_network_settings();
+        if bundle.api.network_settings.proxy_url == current_network.without_secrets().proxy_url {
+            bundle.api.network_settings.proxy_url = current_network.proxy_url.clone();
+        }
+        self.switch_session(bundle.api.server_url.as_deref());
+        self.api.apply_settings(bundle.api);
+        self.connect();
+        self.board_theme = BoardTheme::load_from_state(bundle.board_theme);
+    }
+
+    /// Remembered sessions get a refresh token from the server, which is persisted with the session
+    /// and used to refresh it once it expires. Servers without refresh tokens can't remember logins.
+    pub fn login(
+        &mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+        remember: bool,
+    ) -> RequestHandle {
+        let remember = remember && self.api.server_info().supports(Capability::RefreshTokens);
+        let username = username.into();
+        let login_state = self.login_state().clone();
         login_state.set(LoginState::Loading);
-        self.api
-            .login(username, password, move |result| match result {
-                Ok(response) => {
-                    login_state.set(LoginState::Success(response.token));
-                }
-                Err(err) => match err {
-                    ApiError::BadRequest(_) | ApiError::Unauthorized(_) => {
-                        login_state.set(LoginState::error("Invalid username or password."))
-                    }
-                    _ => login_state.set(LoginState::Error(err.to_string())),
-                },
+        if remember {
+            return self
+                .api
+                .login_remembered(username.clone(), password, move |result| {
+                    login_state.set(match result {
+                        Ok(response) => LoginState::Success(Session::new(
+                            username,
+                            response.login,
+                            Some(response.refresh_token),
+                        )),
+                        Err(err) => get_login_error(err),
+                    });
+                });
+        }
+
+        self.api.login(username.clone(), password, move |result| {
+            login_state.set(match result {
+                Ok(response) => LoginState::Success(Session::new(username, response, None)),
+                Err(err) => get_login_error(err),
             });
+        })
     }
 
     pub fn register(
@@ -51,18 +346,22 @@ impl AppState {
         username: impl Into<String>,
         password: impl Into<String>,
         invite_code: impl Into<String>,
-    ) {
-        let login_state = self.login_state.clone();
+    ) -> RequestHandle {
+        let username = username.into();
+        let login_state = self.login_state().clone();
         login_state.set(LoginState::Loading);
         self.api.register(
-            username,
+            username.clone(),
             password,
             invite_code,
             move |result| match result {
                 Ok(response) => {
-                    login_state.set(LoginState::Success(response.token));
+                    login_state.set(LoginState::Success(Session::new(username, response, None)));
                 }
                 Err(err) => match err {
+                    ApiError::Validation(field_errors) => {
+                        login_state.set(LoginState::Invalid(field_errors))
+                    }
                     ApiError::BadRequest(error) => login_state.set(LoginState::error(error)),
                     ApiError::Collision(_) => login_state.set(LoginState::error(
                         "The username is already taken, please choose another one.",
@@ -70,8 +369,742 @@ impl AppState {
                     _ => login_state.set(LoginState::Error(err.to_string())),
                 },
             },
+        )
+    }
+
+    pub fn create_game(
+        &mut self,
+        name: Option<String>,
+        public: bool,
+        time_control: Option<TimeControl>,
+        color: ColorPreference,
+    ) {
+        let matchmaking = self.matchmaking.clone();
+        matchmaking.set(MatchmakingState::Loading);
+        self.api.create_game(
+            CreateGameBody::new(name, public, time_control, color),
+            move |result| {
+                let outcome = match result {
+                    Ok(room) => MatchmakingState::WaitingForOpponent(room),
+                    Err(ApiError::BadRequest(error)) => MatchmakingState::Error(error),
+                    Err(err) => MatchmakingState::Error(err.to_string()),
+                };
+                MatchmakingState::finish_loading(&matchmaking, outcome);
+            },
+        );
+    }
+
+    pub fn join_game(&mut self, uuid: impl Into<String>) {
+        let matchmaking = self.matchmaking.clone();
+        matchmaking.set(MatchmakingState::Loading);
+        self.api.join_game(uuid, move |result| {
+            let outcome = match result {
+                Ok(room) => MatchmakingState::Matched(room),
+                Err(ApiError::NotFound(_)) | Err(ApiError::Collision(_)) => {
+                    MatchmakingState::error(
+                        "The game is no longer available, please pick another one.",
+                    )
+                }
+                Err(err) => MatchmakingState::Error(err.to_string()),
+            };
+            MatchmakingState::finish_loading(&matchmaking, outcome);
+        });
+    }
+
+    pub fn quick_match(&mut self, time_control: Option<TimeControl>, color: ColorPreference) {
+        let matchmaking = self.matchmaking.clone();
+        matchmaking.set(MatchmakingState::Loading);
+        self.api
+            .join_queue(QueueBody::new(time_control, color), move |result| {
+                let outcome = match result {
+                    Ok(_) => MatchmakingState::Queued(time_control),
+                    Err(err) => MatchmakingState::Error(err.to_string()),
+                };
+                MatchmakingState::finish_loading(&matchmaking, outcome);
+            });
+    }
+
+    /// Checks whether an opponent was found, results are dropped if the search was cancelled meanwhile.
+    pub fn poll_matchmaking(&mut self) {
+        let matchmaking = self.matchmaking.clone();
+        let current = matchmaking.get_clone();
+        match current {
+            MatchmakingState::WaitingForOpponent(room) => {
+                let room_uuid = room.uuid.clone();
+                self.api.get_room(room.uuid, move |result| {
+                    let Ok(room) = result else {
+                        return;
+                    };
+                    matchmaking.with(|state| {
+                        let is_same_room = state
+                            .get_room()
+                            .is_some_and(|current| current.uuid == room_uuid);
+                        if state.is_searching() && is_same_room {
+                            *state = if room.white.is_some() && room.black.is_some() {
+                                MatchmakingState::Matched(room)
+                            } else {
+                                MatchmakingState::WaitingForOpponent(room)
+                            };
+                        }
+                    });
+                });
+            }
+            MatchmakingState::Challenging(challenge) => {
+                let challenge_uuid = challenge.uuid;
+                self.api.get_challenges(move |result| {
+                    let Ok(challenges) = result else {
+                        return;
+                    };
+                    let challenge = challenges
+                        .outgoing
+                        .into_iter()
+                        .find(|challenge| challenge.uuid == challenge_uuid);
+                    matchmaking.with(|state| {
+                        if !matches!(state, MatchmakingState::Challenging(current) if current.uuid == challenge_uuid)
+                        {
+                            return;
+                        }
+                        match challenge {
+                            Some(challenge) => match challenge.status {
+                                ChallengeStatus::Pending => {}
+                                ChallengeStatus::Accepted { room } => {
+                                    *state = MatchmakingState::Matched(room);
+                                }
+                                ChallengeStatus::Declined => {
+                                    *state = MatchmakingState::Error(format!(
+                                        "{} declined your challenge.",
+                                        challenge.challenged.name
+                                    ));
+                                }
+                            },
+                            None => {
+                                *state = MatchmakingState::error("The challenge is no longer available.");
+                            }
+                        }
+                    });
+                });
+            }
+            MatchmakingState::Queued(_) => {
+                self.api.get_queue_status(move |result| {
+                    let Ok(Some(room)) = result.map(|status| status.room) else {
+                        return;
+                    };
+                    matchmaking.with(|state| {
+                        if matches!(state, MatchmakingState::Queued(_)) {
+                            *state = MatchmakingState::Matched(room);
+                        }
+                    });
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Connects to the room of a found match, `notify` is called whenever new game events arrive.
+    pub fn start_online_game<N>(&mut self, room: PrivateRoomInfo, notify: N)
+    where
+        N: Fn() + Send + Sync + 'static,
+    {
+        let connection = match self.api.connect_live(&room.uuid, notify) {
+            Ok(connection) => connection,
+            Err(err) => {
+                self.matchmaking
+                    .set(MatchmakingState::Error(err.to_string()));
+                return;
+            }
+        };
+
+        let username = self.get_username();
+        let is_player = |player: &Option<PublicUserInfo>| {
+            player.as_ref().map(|player| &player.name) == username.as_ref()
+        };
+        let played_color = if is_player(&room.white) {
+            Some(Color::White)
+        } else if is_player(&room.black) {
+            Some(Color::Black)
+        } else {
+            info!("Watching room {} as a spectator", room.uuid);
+            None
+        };
+
+        self.online_game = Some(OnlineGame::new(
+            &self.engine,
+            room,
+            played_color,
+            connection,
+        ));
+        self.switch_view(ViewID::OnlineGame);
+    }
+
+    /// Follows a running game of other players, the board can't be moved on.
+    pub fn spectate_game<N>(&mut self, game: &LiveGame, notify: N)
+    where
+        N: Fn() + Send + Sync + 'static,
+    {
+        self.start_online_game(game.to_room_info(), notify);
+    }
+
+    /// Picks up a correspondence game, the moves made meanwhile arrive with the sync. It counts
+    /// as correspondence right away, so leaving before the sync arrived doesn't resign.
+    pub fn open_correspondence_game<N>(&mut self, game: &CorrespondenceGame, notify: N)
+    where
+        N: Fn() + Send + Sync + 'static,
+    {
+        self.start_online_game(game.to_room_info(), notify);
+        if let Some(online_game) = &mut self.online_game
+            && online_game.room.uuid == game.uuid
+        {
+            online_game.days_per_move = Some(game.days_per_move);
+        }
+    }
+
+    /// Returns to the lobby without leaving the game, it can be continued at any time.
+    pub fn close_correspondence_game(&mut self) {
+        let Some(online_game) = self.online_game.take() else {
+            return;
+        };
+        self.matchmaking.with(|matchmaking| {
+            if matchmaking
+                .get_room()
+                .is_some_and(|room| room.uuid == online_game.room.uuid)
+            {
+                *matchmaking = MatchmakingState::Idle;
+            }
+        });
+        self.correspondence_games.set(CorrespondenceState::Idle);
+        self.switch_view(ViewID::OnlineHome);
+    }
+
+    /// Returns to the lobby while the game goes on, the lobby then offers to return to it. Games
+    /// that didn't come from matchmaking are tracked as matched so no other game is started.
+    pub fn park_online_game(&mut self, online_game: OnlineGame) {
+        self.matchmaking.with(|matchmaking| {
+            if matchmaking.is_idle() {
+                *matchmaking = MatchmakingState::Matched(online_game.room.clone());
+            }
+        });
+        self.online_game = Some(online_game);
+        self.switch_view(ViewID::OnlineHome);
+    }
+
+    /// Leaves the current game or queue, the server side cleanup happens in the background. A
+    /// live game that is still running is resigned.
+    pub fn cancel_matchmaking(&mut self) {
+        if let Some(online_game) = self.online_game.take()
+            && online_game.is_running()
+            && !online_game.is_correspondence()
+        {
+            online_game.resign();
+        }
+        let current = self.matchmaking.get_clone();
+        self.matchmaking.set(MatchmakingState::Idle);
+        match current {
+            MatchmakingState::WaitingForOpponent(room) | MatchmakingState::Matched(room) => {
+                self.api.leave_game(room.uuid, |_| {});
+            }
+            MatchmakingState::Queued(_) => {
+                self.api.leave_queue(|_| {});
+            }
+            MatchmakingState::Challenging(challenge) => {
+                self.api.decline_challenge(challenge.uuid, |_| {});
+            }
+            _ => {}
+        }
+    }
+
+    fn clear_friends(&mut self) {
+        self.friends.set(FriendsState::Idle);
+        self.incoming_challenges.set(Vec::new());
+        self.friends_error.set(None);
+    }
+
+    fn clear_tournaments(&mut self) {
+        self.tournaments_request.cancel();
+        self.tournament_request.cancel();
+        self.joined_tournaments.set(Vec::new());
+        self.tournament_games.set(HashMap::new());
+        self.tournaments.set(TournamentListState::Idle);
+        self.viewed_tournament = None;
+        self.tournament.set(TournamentState::Idle);
+        self.tournament_error.set(None);
+    }
+
+    /// Reloads the friends list and incoming challenges, a loaded list stays visible meanwhile.
+    pub fn refresh_friends(&mut self) {
+        let friends = self.friends.clone();
+        friends.with(|state| {
+            *state = match std::mem::take(state) {
+                FriendsState::Loaded(list) | FriendsState::Outdated(list) => {
+                    FriendsState::Loaded(list)
+                }
+                _ => FriendsState::Loading,
+            };
+        });
+        self.api.get_friends(move |result| match result {
+            Ok(list) => friends.set(FriendsState::Loaded(list)),
+            Err(err) => friends.set(FriendsState::Error(err.to_string())),
+        });
+
+        let incoming_challenges = self.incoming_challenges.clone();
+        self.api.get_challenges(move |result| {
+            if let Ok(challenges) = result {
+                incoming_challenges.set(challenges.incoming);
+            }
+        });
+    }
+
+    /// Sends a friend request, or accepts the request the other user already sent.
+    pub fn add_friend(&mut self, username: impl Into<String>) {
+        let friends = self.friends.clone();
+        let friends_error = self.friends_error.clone();
+        friends_error.set(None);
+        self.api.add_friend(username, move |result| match result {
+            Ok(_) => FriendsState::mark_outdated(&friends),
+            Err(ApiError::NotFound(_)) => {
+                friends_error.set(Some("There is no user with that name.".to_string()))
+            }
+            Err(ApiError::BadRequest(error)) => friends_error.set(Some(error)),
+            Err(err) => friends_error.set(Some(err.to_string())),
+        });
+    }
+
+    /// Removes a friend, which also declines or withdraws a pending friend request.
+    pub fn remove_friend(&mut self, username: impl Into<String>) {
+        let friends = self.friends.clone();
+        let friends_error = self.friends_error.clone();
+        friends_error.set(None);
+        self.api
+            .remove_friend(username, move |result| match result {
+                Ok(_) => FriendsState::mark_outdated(&friends),
+                Err(err) => friends_error.set(Some(err.to_string())),
+            });
+    }
+
+    pub fn challenge(
+        &mut self,
+        username: impl Into<String>,
+        time_control: Option<TimeControl>,
+        color: ColorPreference,
+    ) {
+        let matchmaking = self.matchmaking.clone();
+        matchmaking.set(MatchmakingState::Loading);
+        self.api.send_challenge(
+            ChallengeBody::new(username, time_control, color),
+            move |result| {
+                let outcome = match result {
+                    Ok(challenge) => MatchmakingState::Challenging(challenge),
+                    Err(ApiError::BadRequest(error)) => MatchmakingState::Error(error),
+                    Err(err) => MatchmakingState::Error(err.to_string()),
+                };
+                MatchmakingState::finish_loading(&matchmaking, outcome);
+            },
         );
     }
+
+    pub fn accept_challenge(&mut self, uuid: impl Into<String>) {
+        let uuid = uuid.into();
+        self.incoming_challenges
+            .with(|challenges| challenges.retain(|challenge| challenge.uuid != uuid));
+
+        let matchmaking = self.matchmaking.clone();
+        matchmaking.set(MatchmakingState::Loading);
+        self.api.accept_challenge(uuid, move |result| {
+            let outcome = match result {
+                Ok(room) => MatchmakingState::Matched(room),
+                Err(ApiError::NotFound(_)) => {
+                    MatchmakingState::error("The challenge is no longer available.")
+                }
+                Err(err) => MatchmakingState::Error(err.to_string()),
+            };
+            MatchmakingState::finish_loading(&matchmaking, outcome);
+        });
+    }
+
+    pub fn decline_challenge(&mut self, uuid: impl Into<String>) {
+        let uuid = uuid.into();
+        self.incoming_challenges
+            .with(|challenges| challenges.retain(|challenge| challenge.uuid != uuid));
+        self.api.decline_challenge(uuid, |_| {});
+    }
+
+    /// Switches to the profile of the given user, the view loads it once it is shown.
+    pub fn open_player_profile(&mut self, username: Option<String>) {
+        self.viewed_player = username;
+        self.player_profile.set(PlayerProfileState::Idle);
+        self.switch_view(ViewID::PlayerProfile);
+    }
+
+    /// Loads the profile and the first page of the game history of the viewed user.
+    pub fn load_player_profile(&mut self, history_limit: i64) {
+        self.game_replay.set(GameReplayState::Idle);
+        let player_profile = self.player_profile.clone();
+        player_profile.set(PlayerProfileState::Loading);
+        let request = self
+            .api
+            .get_user_profile(self.viewed_player.clone(), move |result| match result {
+                Ok(profile) => player_profile.set(PlayerProfileState::Loaded(profile)),
+                Err(err) => player_profile.set(PlayerProfileState::Error(err.to_string())),
+            });
+        self.player_profile_request.replace(request);
+        self.load_game_history(1, history_limit);
+    }
+
+    pub fn load_game_history(&mut self, page: i64, limit: i64) {
+        let game_history = self.game_history.clone();
+        game_history.set(GameHistoryState::Loading);
+        let request =
+            self.api
+                .get_game_history(self.viewed_player.clone(), page, limit, move |result| {
+                    match result {
+                        Ok(history) => game_history.set(GameHistoryState::Loaded(history)),
+                        Err(err) => game_history.set(GameHistoryState::Error(err.to_string())),
+                    }
+                });
+        self.game_history_request.replace(request);
+    }
+
+    pub fn load_game_replay(&mut self, uuid: impl Into<String>) {
+        let game_replay = self.game_replay.clone();
+        game_replay.set(GameReplayState::Loading);
+        let request = self.api.get_game_record(uuid, move |result| match result {
+            Ok(record) => game_replay.set(GameReplayState::Loaded(record)),
+            Err(err) => game_replay.set(GameReplayState::Error(err.to_string())),
+        });
+        self.game_replay_request.replace(request);
+    }
+
+    /// Switches to the leaderboard, the view loads it once it is shown.
+    pub fn open_leaderboard(&mut self) {
+        self.leaderboard.set(LeaderboardState::Idle);
+        self.switch_view(ViewID::Leaderboard);
+    }
+
+    pub fn load_leaderboard(&mut self, category: RatingCategory, page: i64, limit: i64) {
+        let leaderboard = self.leaderboard.clone();
+        leaderboard.set(LeaderboardState::Loading);
+        let request = self
+            .api
+            .get_leaderboard(category, page, limit, move |result| match result {
+                Ok(loaded) => leaderboard.set(LeaderboardState::Loaded(loaded)),
+                Err(err) => leaderboard.set(LeaderboardState::Error(err.to_string())),
+            });
+        self.leaderboard_request.replace(request);
+    }
+
+    /// Reloads the dashboard, a loaded list stays visible meanwhile.
+    pub fn refresh_correspondence_games(&mut self) {
+        let correspondence_games = self.correspondence_games.clone();
+        correspondence_games.with(|state| {
+            if !matches!(state, CorrespondenceState::Loaded(_)) {
+                *state = CorrespondenceState::Loading;
+            }
+        });
+        self.api
+            .list_correspondence_games(move |result| match result {
+                Ok(list) => correspondence_games.set(CorrespondenceState::Loaded(list)),
+                Err(err) => correspondence_games.set(CorrespondenceState::Error(err.to_string())),
+            });
+    }
+
+    pub fn create_correspondence_game(&mut self, body: CorrespondenceBody) {
+        let correspondence_games = self.correspondence_games.clone();
+        let correspondence_error = self.correspondence_error.clone();
+        correspondence_error.set(None);
+        self.api
+            .create_correspondence_game(body, move |result| match result {
+                Ok(_) => correspondence_games.set(CorrespondenceState::Idle),
+                Err(ApiError::BadRequest(error)) => correspondence_error.set(Some(error)),
+                Err(err) => correspondence_error.set(Some(err.to_string())),
+            });
+    }
+
+    /// Switches to the tournaments, the view loads the list once it is shown.
+    pub fn open_tournaments(&mut self) {
+        self.tournaments.set(TournamentListState::Idle);
+        self.switch_view(ViewID::Tournaments);
+    }
+
+    pub fn load_tournaments(&mut self, page: i64, limit: i64) {
+        let tournaments = self.tournaments.clone();
+        tournaments.with(|state| {
+            if !matches!(state, TournamentListState::Loaded(_)) {
+                *state = TournamentListState::Loading;
+            }
+        });
+        let request = self
+            .api
+            .list_tournaments(page, limit, move |result| match result {
+                Ok(list) => tournaments.set(TournamentListState::Loaded(list)),
+                Err(err) => tournaments.set(TournamentListState::Error(err.to_string())),
+            });
+        self.tournaments_request.replace(request);
+    }
+
+    pub fn select_tournament(&mut self, uuid: String) {
+        self.tournament_request.cancel();
+        self.viewed_tournament = Some(uuid);
+        self.tournament.set(TournamentState::Idle);
+        self.tournament_error.set(None);
+    }
+
+    /// Reloads the viewed tournament, a loaded one stays visible meanwhile.
+    pub fn load_tournament(&mut self) {
+        let Some(uuid) = self.viewed_tournament.clone() else {
+            return;
+        };
+        let tournament = self.tournament.clone();
+        tournament.with(|state| {
+            if !matches!(state, TournamentState::Loaded(_)) {
+                *state = TournamentState::Loading;
+            }
+        });
+        let request = self.api.get_tournament(uuid, move |result| match result {
+            Ok(loaded) => tournament.set(TournamentState::Loaded(Box::new(loaded))),
+            Err(err) => tournament.set(TournamentState::Error(err.to_string())),
+        });
+        self.tournament_request.replace(request);
+    }
+
+    pub fn join_tournament(&mut self, uuid: String) {
+        let tournaments = self.tournaments.clone();
+        let tournament = self.tournament.clone();
+        let tournament_error = self.tournament_error.clone();
+        tournament_error.set(None);
+        self.api.join_tournament(uuid, move |result| match result {
+            Ok(_) => {
+                tournaments.set(TournamentListState::Idle);
+                tournament.set(TournamentState::Idle);
+            }
+            Err(ApiError::BadRequest(error)) => tournament_error.set(Some(error)),
+            Err(err) => tournament_error.set(Some(err.to_string())),
+        });
+    }
+
+    pub fn withdraw_from_tournament(&mut self, uuid: String) {
+        let tournaments = self.tournaments.clone();
+        let tournament = self.tournament.clone();
+        let tournament_error = self.tournament_error.clone();
+        tournament_error.set(None);
+        self.api
+            .withdraw_from_tournament(uuid, move |result| match result {
+                Ok(_) => {
+                    tournaments.set(TournamentListState::Idle);
+                    tournament.set(TournamentState::Idle);
+                }
+                Err(ApiError::BadRequest(error)) => tournament_error.set(Some(error)),
+                Err(err) => tournament_error.set(Some(err.to_string())),
+            });
+    }
+
+    /// Looks for the games of the joined tournaments. The list of joined tournaments is refreshed
+    /// along the way, so tournaments joined since the last poll are included by the next one.
+    pub fn poll_joined_tournaments(&mut self) {
+        let joined_tournaments = self.joined_tournaments.clone();
+        self.api
+            .list_tournaments(1, JOINED_TOURNAMENTS_LIMIT, move |result| {
+                if let Ok(list) = result {
+                    let joined = list
+                        .tournaments
+                        .into_iter()
+                        .filter(|tournament| {
+                            tournament.joined && tournament.status == TournamentStatus::Running
+                        })
+                        .map(|tournament| tournament.uuid)
+                        .collect();
+                    joined_tournaments.set(joined);
+                }
+            });
+
+        let joined = self.joined_tournaments.get_clone();
+        self.tournament_games
+            .with(|games| games.retain(|uuid, _| joined.contains(uuid)));
+        for uuid in joined {
+            let tournament_games = self.tournament_games.clone();
+            self.api.get_tournament(uuid.clone(), move |result| {
+                if let Ok(tournament) = result {
+                    tournament_games.with(|games| match tournament.own_game {
+                        Some(room) => {
+                            games.insert(uuid, room);
+                        }
+                        None => {
+                            games.remove(&uuid);
+                        }
+                    });
+                }
+            });
+        }
+    }
+
+    /// Drops the user into the board once the next round of a joined tournament was paired,
+    /// unless another game is running.
+    pub fn start_tournament_game<N>(&mut self, notify: N)
+    where
+        N: Fn() + Send + Sync + 'static,
+    {
+        if self.online_game.is_some() || !self.matchmaking.lock().is_idle() {
+            return;
+        }
+        // The viewed tournament is polled more often, its game shows up first
+        let viewed_game = match self.tournament.get_clone() {
+            TournamentState::Loaded(tournament) => tournament.own_game,
+            _ => None,
+        };
+        let Some(room) = viewed_game
+            .into_iter()
+            .chain(self.tournament_games.get_clone().into_values())
+            .find(|room| !self.opened_tournament_games.contains(&room.uuid))
+        else {
+            return;
+        };
+        self.opened_tournament_games.insert(room.uuid.clone());
+        info!("Joining the tournament game in room {}", room.uuid);
+        self.start_online_game(room, notify);
+    }
+
+    pub fn is_tournament_game(&self, room_uuid: &str) -> bool {
+        self.opened_tournament_games.contains(room_uuid)
+    }
+
+    pub fn load_account(&mut self) {
+        let account = self.account.clone();
+        account.set(AccountState::Loading);
+        self.api.get_account(move |result| match result {
+            Ok(loaded) => account.set(AccountState::Loaded(loaded)),
+            Err(err) => {
+                warn!("Failed to load the account: {err}");
+                account.set(AccountState::Unavailable);
+            }
+        });
+    }
+
+    /// Switches to the invite code management, the view loads the codes once it is shown.
+    pub fn open_invite_codes(&mut self) {
+        self.invite_codes.set(InviteCodesState::Idle);
+        self.invite_code_error.set(None);
+        self.switch_view(ViewID::InviteCodes);
+    }
+
+    pub fn load_invite_codes(&mut self) {
+        let invite_codes = self.invite_codes.clone();
+        invite_codes.set(InviteCodesState::Loading);
+        self.api.list_invite_codes(move |result| match result {
+            Ok(list) => invite_codes.set(InviteCodesState::Loaded(list)),
+            Err(err) => invite_codes.set(InviteCodesState::Error(err.to_string())),
+        });
+    }
+
+    pub fn create_invite_code(&mut self, body: InviteCodeBody) {
+        let invite_codes = self.invite_codes.clone();
+        let invite_code_error = self.invite_code_error.clone();
+        invite_code_error.set(None);
+        self.api
+            .create_invite_code(body, move |result| match result {
+                Ok(_) => invite_codes.set(InviteCodesState::Idle),
+                Err(ApiError::BadRequest(error)) => invite_code_error.set(Some(error)),
+                Err(err) => invite_code_error.set(Some(err.to_string())),
+            });
+    }
+
+    pub fn revoke_invite_code(&mut self, code: String) {
+        let invite_codes = self.invite_codes.clone();
+        let invite_code_error = self.invite_code_error.clone();
+        invite_code_error.set(None);
+        self.api
+            .revoke_invite_code(code, move |result| match result {
+                Ok(_) => invite_codes.set(InviteCodesState::Idle),
+                Err(err) => invite_code_error.set(Some(err.to_string())),
+            });
+    }
+
+    pub fn open_account(&mut self) {
+        self.password_change.set(AccountUpdateState::Idle);
+        self.username_change.set(AccountUpdateState::Idle);
+        self.account_deletion.set(AccountUpdateState::Idle);
+        self.switch_view(ViewID::Account);
+    }
+
+    pub fn change_password(&mut self, password: String, new_password: String) {
+        let password_change = self.password_change.clone();
+        password_change.set(AccountUpdateState::Loading);
+        let body = ChangePasswordBody {
+            password,
+            new_password,
+        };
+        self.api.change_password(body, move |result| match result {
+            Ok(_) => password_change.set(AccountUpdateState::Success),
+            Err(err) => password_change.set(AccountUpdateState::from_error(err)),
+        });
+    }
+
+    pub fn change_username(&mut self, username: String, password: String) {
+        let login_state = self.login_state().clone();
+        let account = self.account.clone();
+        let username_change = self.username_change.clone();
+        username_change.set(AccountUpdateState::Loading);
+        let body = ChangeUsernameBody { username, password };
+        self.api.change_username(body, move |result| match result {
+            Ok(info) => {
+                login_state.with(|login_state| {
+                    if let LoginState::Success(session) = login_state {
+                        session.username = Some(info.name.clone());
+                    }
+                });
+                account.set(AccountState::Loaded(info));
+                username_change.set(AccountUpdateState::Success);
+            }
+            Err(err) => username_change.set(AccountUpdateState::from_error(err)),
+        });
+    }
+
+    /// The session is dropped right away, the account view logs out once it sees the success.
+    pub fn delete_account(&mut self, password: String) {
+        let login_state = self.login_state().clone();
+        let account_deletion = self.account_deletion.clone();
+        account_deletion.set(AccountUpdateState::Loading);
+        self.api
+            .delete_account(DeleteAccountBody { password }, move |result| match result {
+                Ok(_) => {
+                    info!("Account was deleted");
+                    login_state.set(LoginState::Idle);
+                    account_deletion.set(AccountUpdateState::Success);
+                }
+                Err(err) => account_deletion.set(AccountUpdateState::from_error(err)),
+            });
+    }
+
+    pub fn refresh_live_games(&mut self, page: i64, limit: i64) {
+        let live_games = self.live_games.clone();
+        live_games.set(LiveGamesState::Loading);
+        let request = self
+            .api
+            .list_live_games(page, limit, move |result| match result {
+                Ok(games) => live_games.set(LiveGamesState::Loaded(games)),
+                Err(err) => live_games.set(LiveGamesState::Error(err.to_string())),
+            });
+        self.live_games_request.replace(request);
+    }
+
+    pub fn refresh_open_games(&mut self, page: i64, limit: i64) {
+        let open_games = self.open_games.clone();
+        open_games.set(OpenGamesState::Loading);
+        let request = self
+            .api
+            .list_open_games(page, limit, move |result| match result {
+                Ok(rooms) => open_games.set(OpenGamesState::Loaded(rooms)),
+                Err(err) => open_games.set(OpenGamesState::Error(err.to_string())),
+            });
+        self.open_games_request.replace(request);
+    }
+}
+
+fn get_login_error(err: ApiError) -> LoginState {
+    match err {
+        ApiError::Validation(field_errors) => LoginState::Invalid(field_errors),
+        ApiError::BadRequest(_) | ApiError::Unauthorized(_) => {
+            LoginState::error("Invalid username or password.")
+        }
+        _ => LoginState::Error(err.to_string()),
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -79,24 +1112,53 @@ pub struct AppStatePersist {
     pub api: MultiplayerClientPersist,
     pub current_view: ViewID,
     pub login_state: LoginState,
+    #[serde(
+        default,
+        deserialize_with = "crate::api::session::deserialize_parked_sessions"
+    )]
+    pub server_sessions: HashMap<String, Session>,
+    #[serde(default)]
+    pub board_theme: BoardThemePersist,
+    #[serde(default)]
+    pub log_level: Option<LevelFilter>,
 }
 
 impl PersistentObject for AppState {
     type PersistentType = AppStatePersist;
 
     fn save_state(&self) -> Self::PersistentType {
+        let mut login_state = self.login_state().get_clone();
+        let mut server_sessions = self.server_sessions.clone();
+        // Sessions on an overriding server end with the run, the saved server keeps its own
+        if let Some(override_url) = self.api.get_server_url_override() {
+            server_sessions.remove(override_url);
+            login_state = self
+                .api
+                .get_saved_server_url()
+                .and_then(|url| server_sessions.remove(url))
+                .map(LoginState::Success)
+                .unwrap_or_default();
+        }
+
         AppStatePersist {
             api: self.api.save_state(),
             current_view: self.current_view,
-            login_state: self.login_state.get_clone(),
+            login_state,
+            server_sessions,
+            board_theme: self.board_theme.save_state(),
+            log_level: self.log_level,
         }
     }
 
     fn load_from_state(state: Self::PersistentType) -> Self {
+        let api = MultiplayerClient::load_from_state(state.api);
+        api.session().set(state.login_state);
         Self {
-            api: MultiplayerClient::load_from_state(state.api),
+            api,
             current_view: state.current_view,
-            login_state: Shared::new(state.login_state),
+            server_sessions: state.server_sessions,
+            board_theme: BoardTheme::load_from_state(state.board_theme),
+            log_level: state.log_level,
             ..Default::default()
         }
     }
@@ -0,0 +1,73 @@
+use crate::api::account::AccountInfo;
+use crate::api::error::field_errors::FieldErrors;
+use crate::api::error::ApiError;
+
+#[derive(Debug, Default, Clone)]
+pub enum AccountState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(AccountInfo),
+    /// Loading failed, admin features stay hidden until the next login.
+    Unavailable,
+}
+
+impl AccountState {
+    pub fn is_admin(&self) -> bool {
+        matches!(self, Self::Loaded(account) if account.is_admin())
+    }
+}
+
+/// The last password change, username change or deletion of the logged in account.
+#[derive(Debug, Default, Clone)]
+pub enum AccountUpdateState {
+    #[default]
+    Idle,
+    Loading,
+    Success,
+    Error(String),
+    /// The server rejected some of the entered values, shown next to the matching fields.
+    Invalid(FieldErrors),
+}
+
+impl AccountUpdateState {
+    pub fn from_error(error: ApiError) -> Self {
+        match error {
+            ApiError::Validation(field_errors) => Self::Invalid(field_errors),
+            ApiError::BadRequest(error) => Self::Error(error),
+            ApiError::Collision(_) => {
+                Self::Error("The username is already taken, please choose another one.".to_string())
+            }
+            _ => Self::Error(error.to_string()),
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+
+    pub fn get_error(&self) -> Option<&str> {
+        match self {
+            Self::Error(error) => Some(error),
+            Self::Invalid(_) => Some("Please correct the marked fields."),
+            _ => None,
+        }
+    }
+
+    pub fn get_field_errors(&self) -> Option<&FieldErrors> {
+        match self {
+            Self::Invalid(field_errors) => Some(field_errors),
+            _ => None,
+        }
+    }
+
+    /// Forgets the server's complaint about a field once the user edited it.
+    pub fn clear_field_error(&mut self, field: &str) {
+        if let Self::Invalid(field_errors) = self {
+            field_errors.remove(field);
+            if field_errors.is_empty() {
+                *self = Self::Idle;
+            }
+        }
+    }
+}
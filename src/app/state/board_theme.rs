@@ -0,0 +1,69 @@
+use crate::persistence::color::Color32Persist;
+use crate::persistence::PersistentObject;
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardTheme {
+    pub light_color: Color32,
+    pub light_color_highlight: Color32,
+    pub light_color_threat: Color32,
+    pub dark_color: Color32,
+    pub dark_color_highlight: Color32,
+    pub dark_color_threat: Color32,
+}
+
+impl Default for BoardTheme {
+    fn default() -> Self {
+        Self {
+            light_color: Color32::from_rgb(255, 247, 228),
+            light_color_highlight: Color32::from_rgb(255, 197, 178),
+            light_color_threat: Color32::from_rgb(249, 130, 132),
+            dark_color: Color32::from_rgb(135, 168, 137),
+            dark_color_highlight: Color32::from_rgb(200, 168, 137),
+            dark_color_threat: Color32::from_rgb(199, 103, 105),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoardThemePersist {
+    pub light_color: Color32Persist,
+    pub light_color_highlight: Color32Persist,
+    pub light_color_threat: Color32Persist,
+    pub dark_color: Color32Persist,
+    pub dark_color_highlight: Color32Persist,
+    pub dark_color_threat: Color32Persist,
+}
+
+impl Default for BoardThemePersist {
+    fn default() -> Self {
+        BoardTheme::default().save_state()
+    }
+}
+
+impl PersistentObject for BoardTheme {
+    type PersistentType = BoardThemePersist;
+
+    fn save_state(&self) -> Self::PersistentType {
+        BoardThemePersist {
+            light_color: self.light_color.save_state(),
+            light_color_highlight: self.light_color_highlight.save_state(),
+            light_color_threat: self.light_color_threat.save_state(),
+            dark_color: self.dark_color.save_state(),
+            dark_color_highlight: self.dark_color_highlight.save_state(),
+            dark_color_threat: self.dark_color_threat.save_state(),
+        }
+    }
+
+    fn load_from_state(state: Self::PersistentType) -> Self {
+        Self {
+            light_color: Color32::load_from_state(state.light_color),
+            light_color_highlight: Color32::load_from_state(state.light_color_highlight),
+            light_color_threat: Color32::load_from_state(state.light_color_threat),
+            dark_color: Color32::load_from_state(state.dark_color),
+            dark_color_highlight: Color32::load_from_state(state.dark_color_highlight),
+            dark_color_threat: Color32::load_from_state(state.dark_color_threat),
+        }
+    }
+}
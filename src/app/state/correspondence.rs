@@ -0,0 +1,24 @@
+use crate::api::correspondence::CorrespondenceGameList;
+
+#[derive(Debug, Default, Clone)]
+pub enum CorrespondenceState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(CorrespondenceGameList),
+    Error(String),
+}
+
+impl CorrespondenceState {
+    /// How many games wait for a move of the given user.
+    pub fn count_own_turns(&self, username: &str) -> usize {
+        match self {
+            Self::Loaded(list) => list
+                .games
+                .iter()
+                .filter(|game| game.is_turn_of(username))
+                .count(),
+            _ => 0,
+        }
+    }
+}
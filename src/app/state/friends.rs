@@ -0,0 +1,29 @@
+use crate::api::friends::FriendList;
+use crate::types::shared::Shared;
+
+#[derive(Debug, Default, Clone)]
+pub enum FriendsState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(FriendList),
+    /// The list changed on the server, the old one is shown until it is reloaded.
+    Outdated(FriendList),
+    Error(String),
+}
+
+impl FriendsState {
+    pub fn needs_refresh(&self) -> bool {
+        matches!(self, Self::Idle | Self::Outdated(_))
+    }
+
+    /// Called after adding or removing friends, so the list is reloaded without flickering.
+    pub fn mark_outdated(friends: &Shared<Self>) {
+        friends.with(|state| {
+            *state = match std::mem::take(state) {
+                Self::Loaded(list) | Self::Outdated(list) => Self::Outdated(list),
+                _ => Self::Idle,
+            };
+        });
+    }
+}
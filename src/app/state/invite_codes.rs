@@ -0,0 +1,10 @@
+use crate::api::invite_codes::InviteCodeList;
+
+#[derive(Debug, Default, Clone)]
+pub enum InviteCodesState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(InviteCodeList),
+    Error(String),
+}
@@ -0,0 +1,10 @@
+use crate::api::leaderboard::Leaderboard;
+
+#[derive(Debug, Default, Clone)]
+pub enum LeaderboardState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(Leaderboard),
+    Error(String),
+}
@@ -0,0 +1,62 @@
+use crate::api::friends::Challenge;
+use crate::api::matchmaking::TimeControl;
+use crate::types::shared::Shared;
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use giga_chess_api_types::response::room_list::PublicRoomList;
+
+#[derive(Debug, Default, Clone)]
+pub enum MatchmakingState {
+    #[default]
+    Idle,
+    Loading,
+    /// The user created an open game and waits for someone to join it.
+    WaitingForOpponent(PrivateRoomInfo),
+    Queued(Option<TimeControl>),
+    /// The user challenged a friend and waits for them to answer.
+    Challenging(Challenge),
+    Matched(PrivateRoomInfo),
+    Error(String),
+}
+
+impl MatchmakingState {
+    /// If the user can currently start looking for a new game.
+    pub fn is_idle(&self) -> bool {
+        matches!(self, Self::Idle | Self::Error(_))
+    }
+
+    pub fn is_searching(&self) -> bool {
+        matches!(
+            self,
+            Self::WaitingForOpponent(_) | Self::Queued(_) | Self::Challenging(_)
+        )
+    }
+
+    pub fn get_room(&self) -> Option<&PrivateRoomInfo> {
+        match self {
+            Self::WaitingForOpponent(room) | Self::Matched(room) => Some(room),
+            _ => None,
+        }
+    }
+
+    pub fn error(error: impl Into<String>) -> Self {
+        Self::Error(error.into())
+    }
+
+    /// Applies the outcome of a request started while loading, unless the user cancelled meanwhile.
+    pub fn finish_loading(matchmaking: &Shared<Self>, outcome: Self) {
+        matchmaking.with(|state| {
+            if matches!(state, Self::Loading) {
+                *state = outcome;
+            }
+        });
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub enum OpenGamesState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(PublicRoomList),
+    Error(String),
+}
@@ -0,0 +1,321 @@
+use crate::api::live::{
+    ChatMessage, ClientMessage, ClockUpdate, LiveConnection, LiveEvent, ServerMessage,
+    MAX_CHAT_MESSAGE_LENGTH,
+};
+use crate::game::uci::{find_uci_move, move_to_uci};
+use crate::game::AppGame;
+use giga_chess::prelude::*;
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use log::warn;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Used when the server reports a rate limit without saying for how long.
+const DEFAULT_CHAT_COOLDOWN: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionStatus {
+    Connecting,
+    Connected,
+    Reconnecting { retry_at: Instant },
+    Closed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameEnd {
+    pub result: String,
+    pub reason: String,
+}
+
+#[derive(Debug)]
+struct Clock {
+    update: ClockUpdate,
+    received_at: Instant,
+}
+
+/// A game played against another user, kept in sync with the server over a live connection.
+#[derive(Debug)]
+pub struct OnlineGame {
+    pub room: PrivateRoomInfo,
+    pub app_game: AppGame,
+    pub connection_status: ConnectionStatus,
+    pub game_end: Option<GameEnd>,
+    pub error: Option<String>,
+    pub spectators: u32,
+    /// Set for correspondence games, which are continued later instead of resigned when left.
+    pub days_per_move: Option<u32>,
+    pub chat: Vec<ChatMessage>,
+    /// Hides the messages of the opponent, they are still received.
+    pub opponent_muted: bool,
+    pub chat_error: Option<String>,
+    /// The server rejects chat messages until then.
+    pub chat_blocked_until: Option<Instant>,
+    connection: LiveConnection,
+    clock: Option<Clock>,
+    /// How many moves the server acknowledged, later ones were only played locally so far.
+    confirmed_moves: usize,
+}
+
+impl OnlineGame {
+    pub fn new(
+        engine: &Arc<Engine>,
+        room: PrivateRoomInfo,
+        played_color: Option<Color>,
+        connection: LiveConnection,
+    ) -> Self {
+        let mut app_game = AppGame::new(engine, Self::get_metadata(&room));
+        app_game.played_color = played_color;
+        app_game.perspective = played_color.unwrap_or(Color::White);
+        // Without a colour every side could be moved, spectators must not move at all
+        app_game.read_only = played_color.is_none();
+
+        Self {
+            room,
+            app_game,
+            connection_status: ConnectionStatus::Connecting,
+            game_end: None,
+            error: None,
+            spectators: 0,
+            days_per_move: None,
+            chat: Vec::new(),
+            opponent_muted: false,
+            chat_error: None,
+            chat_blocked_until: None,
+            connection,
+            clock: None,
+            confirmed_moves: 0,
+        }
+    }
+
+    fn get_metadata(room: &PrivateRoomInfo) -> PGNMetadata {
+        let mut metadata = PGNMetadata::now().event("Giga Chess Online");
+        if let Some(white) = &room.white {
+            metadata = metadata.white(&white.name);
+        }
+        if let Some(black) = &room.black {
+            metadata = metadata.black(&black.name);
+        }
+        metadata
+    }
+
+    pub fn is_correspondence(&self) -> bool {
+        self.days_per_move.is_some()
+    }
+
+    pub fn is_spectating(&self) -> bool {
+        self.app_game.played_color.is_none()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.game_end.is_none() && self.app_game.game.status() == GameStatus::Running
+    }
+
+    /// Applies all events received since the last frame, returns true if the position changed.
+    pub fn process_events(&mut self, engine: &Arc<Engine>) -> bool {
+        let mut position_changed = false;
+        for event in self.connection.poll_events() {
+            match event {
+                LiveEvent::Connected => {
+                    self.connection_status = ConnectionStatus::Connected;
+                    self.error = None;
+                }
+                LiveEvent::Disconnected { retry_in } => {
+                    self.connection_status = ConnectionStatus::Reconnecting {
+                        retry_at: Instant::now() + retry_in,
+                    };
+                }
+                LiveEvent::Closed(reason) => {
+                    self.connection_status = ConnectionStatus::Closed(reason);
+                }
+                LiveEvent::Message(message) => {
+                    position_changed |= self.handle_message(engine, message);
+                }
+            }
+        }
+        position_changed
+    }
+
+    fn handle_message(&mut self, engine: &Arc<Engine>, message: ServerMessage) -> bool {
+        match message {
+            ServerMessage::Sync {
+                moves,
+                clock,
+                chat,
+                days_per_move,
+            } => {
+                self.set_clock(clock);
+                self.days_per_move = days_per_move.or(self.days_per_move);
+                self.chat = chat;
+                self.replay(engine, &moves);
+                self.confirmed_moves = self.app_game.game.move_history().len();
+                true
+            }
+            ServerMessage::Move { uci, clock } => {
+                self.set_clock(clock);
+                // Our own moves are echoed back and were already played locally
+                let position_changed = if self.is_latest_move(&uci) {
+                    false
+                } else {
+                    match find_uci_move(&self.app_game.game, &uci) {
+                        Some(chess_move) => self.app_game.game.play_move(engine, chess_move),
+                        None => {
+                            warn!("Received illegal move '{uci}' from the server");
+                            false
+                        }
+                    }
+                };
+                self.confirmed_moves = self.app_game.game.move_history().len();
+                position_changed
+            }
+            ServerMessage::Clock { clock } => {
+                self.set_clock(clock);
+                false
+            }
+            ServerMessage::GameEnd { result, reason } => {
+                self.game_end = Some(GameEnd { result, reason });
+                false
+            }
+            ServerMessage::Chat(message) => {
+                self.chat.push(message);
+                false
+            }
+            ServerMessage::Spectators { count } => {
+                self.spectators = count;
+                false
+            }
+            ServerMessage::Error { message } => {
+                self.error = Some(message);
+                self.discard_unconfirmed_moves(engine)
+            }
+            ServerMessage::RateLimited {
+                message,
+                retry_after_secs,
+            } => {
+                let cooldown = retry_after_secs
+                    .map(Duration::from_secs)
+                    .unwrap_or(DEFAULT_CHAT_COOLDOWN);
+                self.chat_blocked_until = Some(Instant::now() + cooldown);
+                self.chat_error = Some(message);
+                false
+            }
+        }
+    }
+
+    fn is_latest_move(&self, uci: &str) -> bool {
+        let history = self.app_game.game.move_history();
+        let expected_color = if history.len() % 2 == 1 {
+            Color::White
+        } else {
+            Color::Black
+        };
+        Some(expected_color) == self.app_game.played_color
+            && history
+                .last()
+                .is_some_and(|chess_move| move_to_uci(*chess_move) == uci)
+    }
+
+    /// Rebuilds the game from the authoritative move list of the server.
+    fn replay(&mut self, engine: &Arc<Engine>, moves: &[String]) {
+        let mut game = Game::new(engine, Self::get_metadata(&self.room));
+        for uci in moves {
+            let Some(chess_move) = find_uci_move(&game, uci) else {
+                warn!("Received illegal move '{uci}' while syncing with the server");
+                break;
+            };
+            game.play_move(engine, chess_move);
+        }
+        self.app_game.game = game;
+    }
+
+    /// Takes back moves the server rejected, returns true if there were any.
+    fn discard_unconfirmed_moves(&mut self, engine: &Arc<Engine>) -> bool {
+        let history = self.app_game.game.move_history();
+        if history.len() <= self.confirmed_moves {
+            return false;
+        }
+        let moves: Vec<String> = history[..self.confirmed_moves]
+            .iter()
+            .map(|chess_move| move_to_uci(*chess_move))
+            .collect();
+        self.replay(engine, &moves);
+        true
+    }
+
+    fn set_clock(&mut self, update: ClockUpdate) {
+        self.clock = Some(Clock {
+            update,
+            received_at: Instant::now(),
+        });
+    }
+
+    /// The remaining time of the given side, counting down locally for the side to move.
+    pub fn get_time_left(&self, color: Color) -> Option<Duration> {
+        let clock = self.clock.as_ref()?;
+        let micros = match color {
+            Color::White => clock.update.white_time_left_micros,
+            Color::Black => clock.update.black_time_left_micros,
+        }?;
+
+        let mut time_left = Duration::from_micros(micros.max(0) as u64);
+        if self.is_running() && self.app_game.game.side_to_move() == color {
+            time_left = time_left.saturating_sub(clock.received_at.elapsed());
+        }
+        Some(time_left)
+    }
+
+    pub fn send_move(&self, chess_move: ChessMove) {
+        if self.is_spectating() {
+            return;
+        }
+        self.connection.send(ClientMessage::Move {
+            uci: move_to_uci(chess_move),
+        });
+    }
+
+    pub fn resign(&self) {
+        if self.is_spectating() {
+            return;
+        }
+        self.connection.send(ClientMessage::Resign);
+    }
+
+    /// The remaining time until the server accepts chat messages again.
+    pub fn get_chat_cooldown(&self) -> Option<Duration> {
+        self.chat_blocked_until
+            .map(|blocked_until| blocked_until.saturating_duration_since(Instant::now()))
+            .filter(|cooldown| !cooldown.is_zero())
+    }
+
+    pub fn can_chat(&self) -> bool {
+        !self.is_spectating() && self.get_chat_cooldown().is_none()
+    }
+
+    /// Returns false if the message was empty or too long and was not sent.
+    pub fn send_chat(&mut self, text: &str) -> bool {
+        let text = text.trim();
+        if text.is_empty() || text.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+            return false;
+        }
+        self.chat_error = None;
+        self.connection.send(ClientMessage::Chat {
+            text: text.to_string(),
+        });
+        true
+    }
+
+    pub fn get_opponent_name(&self) -> Option<&str> {
+        let opponent = match self.app_game.played_color? {
+            Color::White => &self.room.black,
+            Color::Black => &self.room.white,
+        };
+        opponent.as_ref().map(|opponent| opponent.name.as_str())
+    }
+
+    /// The chat without the messages of a muted opponent.
+    pub fn get_visible_chat(&self) -> impl Iterator<Item = &ChatMessage> {
+        let muted = self.get_opponent_name().filter(|_| self.opponent_muted);
+        self.chat
+            .iter()
+            .filter(move |message| Some(message.from.as_str()) != muted)
+    }
+}
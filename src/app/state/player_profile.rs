@@ -0,0 +1,102 @@
+use crate::api::player_profile::{GameHistory, GameRecord, UserProfile};
+use crate::game::uci::find_uci_move;
+use crate::game::AppGame;
+use giga_chess::prelude::*;
+use log::warn;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Clone)]
+pub enum PlayerProfileState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(UserProfile),
+    Error(String),
+}
+
+#[derive(Debug, Default, Clone)]
+pub enum GameHistoryState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(GameHistory),
+    Error(String),
+}
+
+/// The record is picked up by the profile view, which turns it into a [`GameReplay`].
+#[derive(Debug, Default, Clone)]
+pub enum GameReplayState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(GameRecord),
+    Error(String),
+}
+
+/// A finished game which can be stepped through move by move, but not played on.
+#[derive(Debug)]
+pub struct GameReplay {
+    pub record: GameRecord,
+    pub app_game: AppGame,
+    moves: Vec<ChessMove>,
+    ply: usize,
+}
+
+impl GameReplay {
+    /// Starts at the final position, moves after an illegal one are dropped.
+    pub fn new(engine: &Arc<Engine>, record: GameRecord, perspective: Color) -> Self {
+        let mut game = Game::new(engine, Self::get_metadata(&record));
+        let mut moves = Vec::with_capacity(record.moves.len());
+        for uci in &record.moves {
+            let Some(chess_move) = find_uci_move(&game, uci) else {
+                warn!(
+                    "Game {} contains the illegal move '{uci}'",
+                    record.summary.uuid
+                );
+                break;
+            };
+            game.play_move(engine, chess_move);
+            moves.push(chess_move);
+        }
+
+        let mut app_game = AppGame::from_game(game);
+        app_game.read_only = true;
+        app_game.perspective = perspective;
+        Self {
+            ply: moves.len(),
+            record,
+            app_game,
+            moves,
+        }
+    }
+
+    fn get_metadata(record: &GameRecord) -> PGNMetadata {
+        PGNMetadata::now()
+            .event("Giga Chess Online")
+            .white(&record.summary.white.name)
+            .black(&record.summary.black.name)
+    }
+
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    pub fn move_count(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Shows the position after the given number of half moves, the game is replayed from the start.
+    pub fn go_to(&mut self, engine: &Arc<Engine>, ply: usize) {
+        let ply = ply.min(self.moves.len());
+        if ply == self.ply {
+            return;
+        }
+
+        let mut game = Game::new(engine, Self::get_metadata(&self.record));
+        for chess_move in &self.moves[..ply] {
+            game.play_move(engine, *chess_move);
+        }
+        self.app_game.game = game;
+        self.ply = ply;
+    }
+}
@@ -0,0 +1,211 @@
+use crate::api::network::{ClientCertificate, NetworkSettings, ProxyMode};
+use crate::api::server_profile::ServerProfile;
+use crate::api::MultiplayerClientPersist;
+use crate::app::state::board_theme::BoardThemePersist;
+use crate::persistence::color::Color32Persist;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub const SETTINGS_BUNDLE_VERSION: u32 = 1;
+pub const SETTINGS_BUNDLE_FILE_NAME: &str = "giga-chess-settings.json";
+
+/// Portable subset of the persisted state, meant to be moved between machines.
+/// Must never contain secrets like the session token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsBundle {
+    pub version: u32,
+    #[serde(default)]
+    pub api: MultiplayerClientPersist,
+    #[serde(default)]
+    pub board_theme: BoardThemePersist,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsChange {
+    pub setting: &'static str,
+    pub current: String,
+    pub imported: String,
+}
+
+impl SettingsBundle {
+    /// Lists every setting that would change if `imported` replaced this bundle.
+    pub fn diff(&self, imported: &SettingsBundle) -> Vec<SettingsChange> {
+        let mut changes = Vec::new();
+
+        push_change(
+            &mut changes,
+            "Server URL",
+            format_server_url(self.api.server_url.as_deref()),
+            format_server_url(imported.api.server_url.as_deref()),
+        );
+        push_change(
+            &mut changes,
+            "Online game transport",
+            self.api.live_transport.to_string(),
+            imported.api.live_transport.to_string(),
+        );
+        push_change(
+            &mut changes,
+            "Saved servers",
+            format_server_profiles(&self.api.server_profiles),
+            format_server_profiles(&imported.api.server_profiles),
+        );
+        push_change(
+            &mut changes,
+            "Connect timeout",
+            format!("{} s", self.api.retry_policy.connect_timeout_secs),
+            format!("{} s", imported.api.retry_policy.connect_timeout_secs),
+        );
+        push_change(
+            &mut changes,
+            "Request timeout",
+            format!("{} s", self.api.retry_policy.request_timeout_secs),
+            format!("{} s", imported.api.retry_policy.request_timeout_secs),
+        );
+        push_change(
+            &mut changes,
+            "Request retries",
+            self.api.retry_policy.max_retries.to_string(),
+            imported.api.retry_policy.max_retries.to_string(),
+        );
+        push_network_changes(
+            &mut changes,
+            &self.api.network_settings,
+            &imported.api.network_settings,
+        );
+
+        let current_theme = &self.board_theme;
+        let imported_theme = &imported.board_theme;
+        push_color_change(
+            &mut changes,
+            "Light squares",
+            current_theme.light_color,
+            imported_theme.light_color,
+        );
+        push_color_change(
+            &mut changes,
+            "Light squares (highlight)",
+            current_theme.light_color_highlight,
+            imported_theme.light_color_highlight,
+        );
+        push_color_change(
+            &mut changes,
+            "Light squares (threat)",
+            current_theme.light_color_threat,
+            imported_theme.light_color_threat,
+        );
+        push_color_change(
+            &mut changes,
+            "Dark squares",
+            current_theme.dark_color,
+            imported_theme.dark_color,
+        );
+        push_color_change(
+            &mut changes,
+            "Dark squares (highlight)",
+            current_theme.dark_color_highlight,
+            imported_theme.dark_color_highlight,
+        );
+        push_color_change(
+            &mut changes,
+            "Dark squares (threat)",
+            current_theme.dark_color_threat,
+            imported_theme.dark_color_threat,
+        );
+
+        changes
+    }
+}
+
+fn push_network_changes(
+    changes: &mut Vec<SettingsChange>,
+    current: &NetworkSettings,
+    imported: &NetworkSettings,
+) {
+    push_change(
+        changes,
+        "Trusted certificates",
+        format_paths(current.root_certificates.iter()),
+        format_paths(imported.root_certificates.iter()),
+    );
+    push_change(
+        changes,
+        "Client certificate",
+        format_client_certificate(current.client_certificate.as_ref()),
+        format_client_certificate(imported.client_certificate.as_ref()),
+    );
+    push_change(
+        changes,
+        "Proxy",
+        format_proxy(current),
+        format_proxy(imported),
+    );
+    push_change(
+        changes,
+        "Allow insecure connections",
+        current.allow_insecure.to_string(),
+        imported.allow_insecure.to_string(),
+    );
+}
+
+fn format_paths<'a>(paths: impl Iterator<Item = &'a PathBuf>) -> String {
+    let paths: Vec<String> = paths.map(|path| path.display().to_string()).collect();
+    if paths.is_empty() {
+        return "None".to_string();
+    }
+    paths.join(", ")
+}
+
+fn format_client_certificate(certificate: Option<&ClientCertificate>) -> String {
+    format_paths(
+        certificate
+            .into_iter()
+            .flat_map(|certificate| [&certificate.certificate_path, &certificate.key_path]),
+    )
+}
+
+fn format_proxy(settings: &NetworkSettings) -> String {
+    match settings.proxy_mode {
+        ProxyMode::Custom => settings.proxy_url.clone(),
+        mode => mode.to_string(),
+    }
+}
+
+fn format_server_url(url: Option<&str>) -> String {
+    url.unwrap_or("None").to_string()
+}
+
+fn format_server_profiles(profiles: &[ServerProfile]) -> String {
+    if profiles.is_empty() {
+        return "None".to_string();
+    }
+    profiles
+        .iter()
+        .map(|profile| format!("{} ({})", profile.name, profile.url))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn push_change(
+    changes: &mut Vec<SettingsChange>,
+    setting: &'static str,
+    current: String,
+    imported: String,
+) {
+    if current != imported {
+        changes.push(SettingsChange {
+            setting,
+            current,
+            imported,
+        });
+    }
+}
+
+fn push_color_change(
+    changes: &mut Vec<SettingsChange>,
+    setting: &'static str,
+    current: Color32Persist,
+    imported: Color32Persist,
+) {
+    push_change(changes, setting, current.to_hex(), imported.to_hex());
+}
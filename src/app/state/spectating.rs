@@ -0,0 +1,10 @@
+use crate::api::spectating::LiveGameList;
+
+#[derive(Debug, Default, Clone)]
+pub enum LiveGamesState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(LiveGameList),
+    Error(String),
+}
@@ -0,0 +1,20 @@
+use crate::api::tournaments::{Tournament, TournamentList};
+
+#[derive(Debug, Default, Clone)]
+pub enum TournamentListState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(TournamentList),
+    Error(String),
+}
+
+#[derive(Debug, Default, Clone)]
+pub enum TournamentState {
+    #[default]
+    Idle,
+    Loading,
+    /// Boxed, since the standings and pairings make it much larger than the other variants.
+    Loaded(Box<Tournament>),
+    Error(String),
+}
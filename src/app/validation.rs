@@ -1,5 +1,7 @@
+use crate::persistence::profile::is_valid_profile_name;
 use giga_chess_api_types::validation::alphanumeric::is_alphanumeric;
 use giga_chess_api_types::validation::uuid::is_uuid;
+use std::path::Path;
 use url::Url;
 
 pub fn validate_username(username: &str) -> bool {
@@ -17,3 +19,31 @@ pub fn validate_invite_code(invite_code: &str) -> bool {
 pub fn validate_url(url: &str) -> bool {
     Url::parse(url).is_ok()
 }
+
+pub fn validate_proxy_url(url: &str) -> bool {
+    Url::parse(url.trim()).is_ok_and(|url| {
+        matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") && url.has_host()
+    })
+}
+
+/// File paths are optional, so an empty path is valid as well.
+pub fn validate_file_path(path: &str) -> bool {
+    path.trim().is_empty() || Path::new(path.trim()).is_file()
+}
+
+pub fn validate_profile_name(name: &str) -> bool {
+    is_valid_profile_name(name)
+}
+
+pub fn validate_server_name(name: &str) -> bool {
+    !name.trim().is_empty() && name.chars().count() <= 32
+}
+
+/// Room names are optional, so an empty name is valid as well.
+pub fn validate_room_name(name: &str) -> bool {
+    name.is_empty() || (is_alphanumeric(name).is_ok() && name.len() >= 3 && name.len() <= 50)
+}
+
+pub fn validate_game_id(game_id: &str) -> bool {
+    is_uuid(game_id).is_ok()
+}
@@ -1,19 +1,44 @@
+mod account;
+mod invite_codes;
+mod leaderboard;
 mod main_menu;
+mod online_game;
 mod online_home;
+mod player_profile;
 mod sandbox;
+mod tournaments;
 
+use crate::api::server_info::Capability;
 use crate::app::state::AppState;
+use crate::app::views::account::AccountView;
+use crate::app::views::invite_codes::InviteCodesView;
+use crate::app::views::leaderboard::LeaderboardView;
 use crate::app::views::main_menu::MainMenuView;
+use crate::app::views::online_game::OnlineGameView;
 use crate::app::views::online_home::OnlineHomeView;
+use crate::app::views::player_profile::PlayerProfileView;
 use crate::app::views::sandbox::SandboxView;
-use egui::Context;
+use crate::app::views::tournaments::TournamentsView;
+use crate::app::windows::log::LogWindow;
+use crate::app::windows::ToggleableWindow;
+use egui::{Context, Key};
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Joined tournaments are polled from every view, so the next round is joined wherever the user is.
+const JOINED_TOURNAMENTS_POLL_INTERVAL: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
 pub enum ViewID {
     #[default]
     MainMenu,
     OnlineHome,
+    OnlineGame,
+    PlayerProfile,
+    Leaderboard,
+    Tournaments,
+    InviteCodes,
+    Account,
     Sandbox,
 }
 
@@ -26,7 +51,41 @@ pub trait View {
 pub struct ViewManager {
     main_menu: MainMenuView,
     online_home: OnlineHomeView,
+    online_game: OnlineGameView,
+    player_profile: PlayerProfileView,
+    leaderboard: LeaderboardView,
+    tournaments: TournamentsView,
+    invite_codes: InviteCodesView,
+    account: AccountView,
     sandbox: SandboxView,
+    log_window: LogWindow,
+    last_joined_tournaments_poll: Option<Instant>,
+}
+
+impl ViewManager {
+    pub fn open_profile_picker(&mut self, state: &mut AppState) {
+        state.switch_view(ViewID::MainMenu);
+        self.main_menu.open_profiles_modal();
+    }
+
+    fn poll_joined_tournaments(&mut self, ctx: &Context, state: &mut AppState) {
+        if !state.is_logged_in() || !state.api.server_info().supports(Capability::Tournaments) {
+            self.last_joined_tournaments_poll = None;
+            return;
+        }
+
+        let is_due = self
+            .last_joined_tournaments_poll
+            .is_none_or(|last_poll| last_poll.elapsed() >= JOINED_TOURNAMENTS_POLL_INTERVAL);
+        if is_due {
+            state.poll_joined_tournaments();
+            self.last_joined_tournaments_poll = Some(Instant::now());
+        }
+        ctx.request_repaint_after(JOINED_TOURNAMENTS_POLL_INTERVAL);
+
+        let ctx = ctx.clone();
+        state.start_tournament_game(move || ctx.request_repaint());
+    }
 }
 
 impl View for ViewManager {
@@ -34,15 +93,35 @@ impl View for ViewManager {
         Self {
             main_menu: MainMenuView::new(),
             online_home: OnlineHomeView::new(),
+            online_game: OnlineGameView::new(),
+            player_profile: PlayerProfileView::new(),
+            leaderboard: LeaderboardView::new(),
+            tournaments: TournamentsView::new(),
+            invite_codes: InviteCodesView::new(),
+            account: AccountView::new(),
             sandbox: SandboxView::new(),
+            log_window: LogWindow::default(),
+            last_joined_tournaments_poll: None,
         }
     }
 
     fn render(&mut self, ctx: &Context, state: &mut AppState) {
+        self.poll_joined_tournaments(ctx, state);
         match state.current_view {
             ViewID::MainMenu => self.main_menu.render(ctx, state),
             ViewID::OnlineHome => self.online_home.render(ctx, state),
+            ViewID::OnlineGame => self.online_game.render(ctx, state),
+            ViewID::PlayerProfile => self.player_profile.render(ctx, state),
+            ViewID::Leaderboard => self.leaderboard.render(ctx, state),
+            ViewID::Tournaments => self.tournaments.render(ctx, state),
+            ViewID::InviteCodes => self.invite_codes.render(ctx, state),
+            ViewID::Account => self.account.render(ctx, state),
             ViewID::Sandbox => self.sandbox.render(ctx, state),
         }
+
+        if ctx.input(|input| input.key_pressed(Key::F12)) {
+            self.log_window.set_open(!self.log_window.is_open());
+        }
+        let _ = self.log_window.render(ctx, state);
     }
 }
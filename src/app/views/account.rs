@@ -0,0 +1,279 @@
+use crate::app::components::retry_status::render_loading;
+use crate::app::components::validated_field::{ValidatedField, ValidatedFieldResponse};
+use crate::app::state::account::{AccountState, AccountUpdateState};
+use crate::app::state::AppState;
+use crate::app::validation::{validate_password, validate_username};
+use crate::app::views::{View, ViewID};
+use crate::types::shared::Shared;
+use egui::{Button, CentralPanel, Context, RichText, ScrollArea, TopBottomPanel, Ui};
+
+const LABEL_WIDTH: f32 = 120.0;
+
+#[derive(Debug)]
+pub struct AccountView {
+    password: String,
+    new_password: String,
+    repeated_password: String,
+    new_username: String,
+    username_password: String,
+    deletion_password: String,
+    confirm_deletion: bool,
+}
+
+impl AccountView {
+    fn render_password_change(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.heading("Change Password");
+        let update = state.password_change.get_clone();
+        let field_errors = update.get_field_errors().cloned().unwrap_or_default();
+        // The form is emptied once, the next edit then hides the success message
+        if let AccountUpdateState::Success = update
+            && !self.password.is_empty()
+        {
+            self.password.clear();
+            self.new_password.clear();
+            self.repeated_password.clear();
+        }
+
+        let password_response = ValidatedField::new("Current password", &mut self.password)
+            .label_width(LABEL_WIDTH)
+            .validator(|password| !password.is_empty())
+            .error_message("Please enter your current password.")
+            .password()
+            .server_errors(field_errors.get("password"))
+            .show(ui);
+        let new_password_response = ValidatedField::new("New password", &mut self.new_password)
+            .label_width(LABEL_WIDTH)
+            .validator(validate_password)
+            .error_message("Password must be between 8 and 100 characters long.")
+            .password()
+            .server_errors(field_errors.get("new_password"))
+            .show(ui);
+        let new_password = self.new_password.clone();
+        let repeated_response = ValidatedField::new("Repeat password", &mut self.repeated_password)
+            .label_width(LABEL_WIDTH)
+            .validator(move |repeated| repeated == new_password)
+            .error_message("The passwords don't match.")
+            .password()
+            .show(ui);
+        clear_changed_fields(
+            &state.password_change,
+            [
+                ("password", &password_response),
+                ("new_password", &new_password_response),
+                ("repeated_password", &repeated_response),
+            ],
+        );
+
+        let is_valid = password_response.is_valid
+            && new_password_response.is_valid
+            && repeated_response.is_valid;
+        if ui
+            .add_enabled(
+                is_valid && !update.is_loading(),
+                Button::new("Change password"),
+            )
+            .clicked()
+        {
+            state.change_password(self.password.clone(), self.new_password.clone());
+        }
+        render_update_status(ui, state, &update, "Your password was changed.");
+    }
+
+    fn render_username_change(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.heading("Change Username");
+        let update = state.username_change.get_clone();
+        let field_errors = update.get_field_errors().cloned().unwrap_or_default();
+        if let AccountUpdateState::Success = update
+            && !self.username_password.is_empty()
+        {
+            self.new_username.clear();
+            self.username_password.clear();
+        }
+
+        let username_response = ValidatedField::new("New username", &mut self.new_username)
+            .label_width(LABEL_WIDTH)
+            .validator(validate_username)
+            .error_message(
+                "Username must be between 3 and 16 characters and only contain alphanumeric characters.",
+            )
+            .server_errors(field_errors.get("username"))
+            .show(ui);
+        let password_response = ValidatedField::new("Password", &mut self.username_password)
+            .label_width(LABEL_WIDTH)
+            .validator(|password| !password.is_empty())
+            .error_message("Please enter your password to confirm the change.")
+            .password()
+            .server_errors(field_errors.get("password"))
+            .show(ui);
+        clear_changed_fields(
+            &state.username_change,
+            [
+                ("username", &username_response),
+                ("password", &password_response),
+            ],
+        );
+
+        let is_unchanged = state.get_username().as_deref() == Some(self.new_username.as_str());
+        let is_valid = username_response.is_valid && password_response.is_valid && !is_unchanged;
+        if ui
+            .add_enabled(
+                is_valid && !update.is_loading(),
+                Button::new("Change username"),
+            )
+            .clicked()
+        {
+            state.change_username(self.new_username.clone(), self.username_password.clone());
+        }
+        render_update_status(ui, state, &update, "Your username was changed.");
+    }
+
+    fn render_deletion(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.heading("Delete Account");
+        ui.label("Your account can't be restored once it is deleted.");
+        let update = state.account_deletion.get_clone();
+        let field_errors = update.get_field_errors().cloned().unwrap_or_default();
+
+        let password_response = ValidatedField::new("Password", &mut self.deletion_password)
+            .label_width(LABEL_WIDTH)
+            .validator(|password| !password.is_empty())
+            .error_message("Please enter your password to confirm the deletion.")
+            .password()
+            .server_errors(field_errors.get("password"))
+            .show(ui);
+        clear_changed_fields(&state.account_deletion, [("password", &password_response)]);
+
+        if !self.confirm_deletion {
+            if ui
+                .add_enabled(password_response.is_valid, Button::new("Delete account"))
+                .clicked()
+            {
+                self.confirm_deletion = true;
+            }
+        } else {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                "Do you really want to delete your account? This can't be undone.",
+            );
+            ui.horizontal(|ui| {
+                let delete_button = Button::new(RichText::new("Delete permanently").strong());
+                if ui
+                    .add_enabled(
+                        password_response.is_valid && !update.is_loading(),
+                        delete_button,
+                    )
+                    .clicked()
+                {
+                    self.confirm_deletion = false;
+                    state.delete_account(self.deletion_password.clone());
+                }
+                if ui.button("Cancel").clicked() {
+                    self.confirm_deletion = false;
+                }
+            });
+        }
+        render_update_status(ui, state, &update, "Your account was deleted.");
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// Errors reported by the server no longer apply once the field was edited, neither does the
+/// message about the last successful change.
+fn clear_changed_fields<const N: usize>(
+    update: &Shared<AccountUpdateState>,
+    fields: [(&str, &ValidatedFieldResponse); N],
+) {
+    for (field, response) in fields {
+        if response.response.changed() {
+            update.with(|update| match update {
+                AccountUpdateState::Success => *update = AccountUpdateState::Idle,
+                _ => update.clear_field_error(field),
+            });
+        }
+    }
+}
+
+fn render_update_status(
+    ui: &mut Ui,
+    state: &AppState,
+    update: &AccountUpdateState,
+    success_message: &str,
+) {
+    match update {
+        AccountUpdateState::Idle => {}
+        AccountUpdateState::Loading => render_loading(ui, state.api.retry_status()),
+        AccountUpdateState::Success => {
+            ui.label(success_message);
+        }
+        AccountUpdateState::Error(_) | AccountUpdateState::Invalid(_) => {
+            if let Some(error) = update.get_error() {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        }
+    }
+}
+
+impl View for AccountView {
+    fn new() -> Self {
+        Self {
+            password: String::new(),
+            new_password: String::new(),
+            repeated_password: String::new(),
+            new_username: String::new(),
+            username_password: String::new(),
+            deletion_password: String::new(),
+            confirm_deletion: false,
+        }
+    }
+
+    fn render(&mut self, ctx: &Context, state: &mut AppState) {
+        // The session is already gone, the rest of the online state is cleared by logging out
+        if matches!(
+            state.account_deletion.get_clone(),
+            AccountUpdateState::Success
+        ) {
+            self.reset();
+            state.account_deletion.set(AccountUpdateState::Idle);
+            state.logout();
+            return;
+        }
+        if !state.is_logged_in() {
+            self.reset();
+            state.switch_view(ViewID::MainMenu);
+            return;
+        }
+
+        TopBottomPanel::top("account_top_panel").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                let home_response = ui.add(Button::new(RichText::new(" 🏠 ").size(20.0)));
+                if home_response.clicked() {
+                    self.reset();
+                    state.switch_view(ViewID::OnlineHome);
+                }
+
+                ui.label("Account");
+            });
+        });
+
+        CentralPanel::default().show(ctx, |ui| {
+            ScrollArea::vertical().show(ui, |ui| {
+                let username = state.get_username().unwrap_or_default();
+                ui.label(format!("Logged in as {username}"));
+                if state.account.lock().is_admin() {
+                    ui.label("You are an admin of this server.");
+                }
+                if matches!(state.account.get_clone(), AccountState::Unavailable) {
+                    ui.label("The role of your account couldn't be loaded.");
+                }
+                ui.separator();
+                self.render_password_change(ui, state);
+                ui.separator();
+                self.render_username_change(ui, state);
+                ui.separator();
+                self.render_deletion(ui, state);
+            });
+        });
+    }
+}
@@ -0,0 +1,185 @@
+use crate::api::invite_codes::{InviteCode, InviteCodeBody, InviteCodeStatus};
+use crate::app::components::retry_status::render_loading;
+use crate::app::state::invite_codes::InviteCodesState;
+use crate::app::state::AppState;
+use crate::app::views::{View, ViewID};
+use chrono::{DateTime, Local};
+use egui::{
+    Button, CentralPanel, Context, DragValue, Grid, RichText, ScrollArea, TopBottomPanel, Ui,
+};
+
+#[derive(Debug)]
+pub struct InviteCodesView {
+    limit_expiry: bool,
+    expires_in_days: u32,
+    limit_uses: bool,
+    max_uses: u32,
+}
+
+impl InviteCodesView {
+    fn render_create(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.heading("New Invite Code");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.limit_expiry, "Expires after");
+            ui.add_enabled(
+                self.limit_expiry,
+                DragValue::new(&mut self.expires_in_days)
+                    .range(1..=365)
+                    .suffix(" days"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.limit_uses, "Can be used");
+            ui.add_enabled(
+                self.limit_uses,
+                DragValue::new(&mut self.max_uses)
+                    .range(1..=1000)
+                    .suffix(" times"),
+            );
+        });
+
+        if ui.button("Generate").clicked() {
+            state.create_invite_code(InviteCodeBody {
+                expires_in_days: self.limit_expiry.then_some(self.expires_in_days),
+                max_uses: self.limit_uses.then_some(self.max_uses),
+            });
+        }
+        if let Some(error) = state.invite_code_error.get_clone() {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+
+    fn render_codes(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.heading("Invite Codes");
+        let list = match state.invite_codes.get_clone() {
+            InviteCodesState::Idle => return,
+            InviteCodesState::Loading => {
+                render_loading(ui, state.api.retry_status());
+                return;
+            }
+            InviteCodesState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                if ui.button("Retry").clicked() {
+                    state.load_invite_codes();
+                }
+                return;
+            }
+            InviteCodesState::Loaded(list) => list,
+        };
+        if list.codes.is_empty() {
+            ui.label("No invite codes were generated yet.");
+            return;
+        }
+
+        ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("invite_codes")
+                .num_columns(7)
+                .spacing([20.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Code");
+                    ui.strong("Created");
+                    ui.strong("Expires");
+                    ui.strong("Uses");
+                    ui.strong("Status");
+                    // The copy and revoke buttons have no header
+                    ui.label("");
+                    ui.label("");
+                    ui.end_row();
+
+                    for invite_code in &list.codes {
+                        render_code(ui, state, invite_code);
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
+
+fn render_code(ui: &mut Ui, state: &mut AppState, invite_code: &InviteCode) {
+    let status = invite_code.get_status();
+    let code = RichText::new(&invite_code.code).monospace();
+    match status {
+        InviteCodeStatus::Active => ui.label(code),
+        _ => ui.label(code.weak()),
+    }
+    .on_hover_text(format!("Created by {}", invite_code.created_by));
+    ui.label(format_date(invite_code.created_at));
+    ui.label(
+        invite_code
+            .expires_at
+            .map(format_date)
+            .unwrap_or_else(|| "Never".to_string()),
+    );
+    match invite_code.max_uses {
+        Some(max_uses) => ui.label(format!("{} / {max_uses}", invite_code.uses)),
+        None => ui.label(invite_code.uses.to_string()),
+    };
+    ui.label(match status {
+        InviteCodeStatus::Active => "Active",
+        InviteCodeStatus::Revoked => "Revoked",
+        InviteCodeStatus::Expired => "Expired",
+        InviteCodeStatus::UsedUp => "Used up",
+    });
+
+    let is_active = status == InviteCodeStatus::Active;
+    if ui.add_enabled(is_active, Button::new("Copy")).clicked() {
+        ui.ctx().copy_text(invite_code.code.clone());
+    }
+    if ui.add_enabled(is_active, Button::new("Revoke")).clicked() {
+        state.revoke_invite_code(invite_code.code.clone());
+    }
+}
+
+fn format_date(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|time| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+impl View for InviteCodesView {
+    fn new() -> Self {
+        Self {
+            limit_expiry: true,
+            expires_in_days: 7,
+            limit_uses: true,
+            max_uses: 1,
+        }
+    }
+
+    fn render(&mut self, ctx: &Context, state: &mut AppState) {
+        if !state.is_logged_in() {
+            state.switch_view(ViewID::MainMenu);
+            return;
+        }
+        // Only reachable for admins, the server rejects everyone else anyway
+        if !state.account.lock().is_admin() {
+            state.switch_view(ViewID::OnlineHome);
+            return;
+        }
+        if matches!(state.invite_codes.get_clone(), InviteCodesState::Idle) {
+            state.load_invite_codes();
+        }
+
+        TopBottomPanel::top("invite_codes_top_panel").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                let home_response = ui.add(Button::new(RichText::new(" 🏠 ").size(20.0)));
+                if home_response.clicked() {
+                    state.switch_view(ViewID::OnlineHome);
+                }
+
+                ui.label("Invite Codes");
+            });
+        });
+
+        CentralPanel::default().show(ctx, |ui| {
+            self.render_create(ui, state);
+            ui.separator();
+            self.render_codes(ui, state);
+        });
+    }
+}
@@ -0,0 +1,206 @@
+use crate::api::leaderboard::{Leaderboard, LeaderboardEntry};
+use crate::api::player_profile::RatingCategory;
+use crate::api::server_info::Capability;
+use crate::app::components::retry_status::render_loading;
+use crate::app::state::leaderboard::LeaderboardState;
+use crate::app::state::AppState;
+use crate::app::views::{View, ViewID};
+use egui::{Align, Button, CentralPanel, Context, Grid, Layout, RichText, TopBottomPanel, Ui};
+
+const ENTRIES_PER_PAGE: i64 = 25;
+
+#[derive(Debug)]
+pub struct LeaderboardView {
+    category: RatingCategory,
+    page: i64,
+}
+
+impl LeaderboardView {
+    fn load_page(&mut self, state: &mut AppState, page: i64) {
+        self.page = page;
+        state.load_leaderboard(self.category, page, ENTRIES_PER_PAGE);
+    }
+
+    fn render_categories(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.horizontal(|ui| {
+            for category in RatingCategory::ALL {
+                if ui
+                    .selectable_label(self.category == category, category.to_string())
+                    .clicked()
+                    && self.category != category
+                {
+                    self.category = category;
+                    self.load_page(state, 1);
+                }
+            }
+        });
+    }
+
+    fn render_own_entry(&mut self, ui: &mut Ui, state: &mut AppState, leaderboard: &Leaderboard) {
+        let Some(own_entry) = &leaderboard.own_entry else {
+            ui.label(format!(
+                "You are not ranked in {} yet, play a rated game to get on the board.",
+                leaderboard.category
+            ));
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(format!(
+                    "Your rank: #{} with a rating of {}",
+                    own_entry.rank, own_entry.rating
+                ))
+                .strong(),
+            );
+            let own_page = (own_entry.rank - 1) / ENTRIES_PER_PAGE + 1;
+            if own_page != self.page && ui.button("Show").clicked() {
+                self.load_page(state, own_page);
+            }
+        });
+    }
+
+    fn render_entries(&mut self, ui: &mut Ui, state: &mut AppState, leaderboard: &Leaderboard) {
+        if leaderboard.entries.is_empty() {
+            ui.label("Nobody has played a rated game in this category yet.");
+            return;
+        }
+
+        let own_name = leaderboard
+            .own_entry
+            .as_ref()
+            .map(|entry| entry.name.as_str());
+        let supports_profiles = state.api.server_info().supports(Capability::Profiles);
+        Grid::new("leaderboard_entries")
+            .num_columns(4)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Rank");
+                ui.strong("Player");
+                ui.strong("Rating");
+                ui.strong("Games");
+                ui.end_row();
+
+                for entry in &leaderboard.entries {
+                    let is_own = Some(entry.name.as_str()) == own_name;
+                    render_entry(ui, state, entry, is_own, supports_profiles);
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn render_pages(&mut self, ui: &mut Ui, state: &mut AppState, leaderboard: &Leaderboard) {
+        let page_count = (leaderboard.pagination.total + ENTRIES_PER_PAGE - 1) / ENTRIES_PER_PAGE;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.page > 1, Button::new("Previous"))
+                .clicked()
+            {
+                self.load_page(state, self.page - 1);
+            }
+            ui.label(format!("Page {} of {}", self.page, page_count.max(1)));
+            if ui
+                .add_enabled(self.page < page_count, Button::new("Next"))
+                .clicked()
+            {
+                self.load_page(state, self.page + 1);
+            }
+        });
+    }
+}
+
+fn render_entry(
+    ui: &mut Ui,
+    state: &mut AppState,
+    entry: &LeaderboardEntry,
+    is_own: bool,
+    supports_profiles: bool,
+) {
+    let highlight_color = ui.visuals().selection.stroke.color;
+    let highlight = |text: String| {
+        let text = RichText::new(text);
+        if is_own {
+            text.strong().color(highlight_color)
+        } else {
+            text
+        }
+    };
+
+    ui.label(highlight(format!("#{}", entry.rank)));
+    if supports_profiles {
+        if ui.link(highlight(entry.name.clone())).clicked() {
+            state.open_player_profile(Some(entry.name.clone()));
+        }
+    } else {
+        ui.label(highlight(entry.name.clone()));
+    }
+    ui.label(highlight(entry.rating.to_string()));
+    ui.label(highlight(entry.games.to_string()));
+}
+
+impl View for LeaderboardView {
+    fn new() -> Self {
+        Self {
+            category: RatingCategory::Blitz,
+            page: 1,
+        }
+    }
+
+    fn render(&mut self, ctx: &Context, state: &mut AppState) {
+        if !state.is_logged_in() {
+            state.switch_view(ViewID::MainMenu);
+            return;
+        }
+        // Opened from the lobby, or the view was restored from a previous run
+        if matches!(state.leaderboard.get_clone(), LeaderboardState::Idle) {
+            self.load_page(state, self.page);
+        }
+
+        TopBottomPanel::top("leaderboard_top_panel").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                let home_response = ui.add(Button::new(RichText::new(" 🏠 ").size(20.0)));
+                if home_response.clicked() {
+                    state.switch_view(ViewID::OnlineHome);
+                }
+
+                ui.label("Leaderboard");
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if state.api.server_info().supports(Capability::Profiles)
+                        && ui.button("My Profile").clicked()
+                    {
+                        state.open_player_profile(None);
+                    }
+                });
+            });
+        });
+
+        CentralPanel::default().show(ctx, |ui| {
+            self.render_categories(ui, state);
+            ui.separator();
+
+            let leaderboard = match state.leaderboard.get_clone() {
+                LeaderboardState::Idle => return,
+                LeaderboardState::Loading => {
+                    render_loading(ui, state.api.retry_status());
+                    return;
+                }
+                LeaderboardState::Error(error) => {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                    if ui.button("Retry").clicked() {
+                        self.load_page(state, self.page);
+                    }
+                    return;
+                }
+                LeaderboardState::Loaded(leaderboard) => leaderboard,
+            };
+
+            self.render_own_entry(ui, state, &leaderboard);
+            ui.separator();
+            self.render_entries(ui, state, &leaderboard);
+            ui.separator();
+            self.render_pages(ui, state, &leaderboard);
+        });
+    }
+}
@@ -1,22 +1,31 @@
+use crate::api::login::LoginStateStatus;
+use crate::api::server_info::ServerInfoState;
 use crate::app::modals::login::LoginModal;
 use crate::app::modals::login_or_register::LoginOrRegisterModal;
+use crate::app::modals::profiles::ProfilesModal;
 use crate::app::modals::register::RegisterModal;
 use crate::app::modals::server_settings::ServerSettingsModal;
+use crate::app::modals::settings_bundle::SettingsBundleModal;
 use crate::app::modals::{Modal, ModalEvent};
-use crate::app::state::login::LoginStateStatus;
 use crate::app::state::AppState;
 use crate::app::views::{View, ViewID};
-use egui::{Button, CentralPanel, Context, RichText, Vec2};
+use egui::{Button, CentralPanel, ComboBox, Context, RichText, Ui, Vec2};
 
 #[derive(Debug, Default)]
 pub struct MainMenuView {
     login_modal: LoginModal,
     login_or_register_modal: LoginOrRegisterModal,
+    profiles_modal: ProfilesModal,
     register_modal: RegisterModal,
     server_settings_modal: ServerSettingsModal,
+    settings_bundle_modal: SettingsBundleModal,
 }
 
 impl MainMenuView {
+    pub fn open_profiles_modal(&mut self) {
+        self.profiles_modal.set_open(true);
+    }
+
     fn render_login_modal(&mut self, ctx: &Context, state: &mut AppState) {
         let event = self.login_modal.render(ctx, state);
         if event == ModalEvent::LoginSuccess {
@@ -36,6 +45,13 @@ impl MainMenuView {
         }
     }
 
+    fn render_profiles_modal(&mut self, ctx: &Context, state: &mut AppState) {
+        let event = self.profiles_modal.render(ctx, state);
+        if event == ModalEvent::SelectProfile {
+            self.profiles_modal.set_open(false);
+        }
+    }
+
     fn render_register_modal(&mut self, ctx: &Context, state: &mut AppState) {
         let event = self.register_modal.render(ctx, state);
         if event == ModalEvent::RegisterSuccess {
@@ -52,14 +68,91 @@ impl MainMenuView {
         }
     }
 
+    /// Quick switching between saved servers, each server keeps its own login.
+    fn render_server_switch(&mut self, ui: &mut Ui, state: &mut AppState) {
+        let profiles = state.api.get_server_profiles().to_vec();
+        if profiles.is_empty() {
+            return;
+        }
+
+        let current = match state.api.get_active_server_profile() {
+            Some(profile) => profile.name.clone(),
+            None => state.api.get_server_url().unwrap_or("None").to_string(),
+        };
+        let mut selected = None;
+        ui.add_space(10.0);
+        ComboBox::from_id_salt("main_menu_server_switch")
+            .width(400.0)
+            .selected_text(RichText::new(format!("Server: {current}")).size(20.0))
+            .show_ui(ui, |ui| {
+                for profile in profiles {
+                    let is_current = state.api.get_server_url() == Some(profile.url.as_str());
+                    if ui
+                        .selectable_label(is_current, &profile.name)
+                        .on_hover_text(&profile.url)
+                        .clicked()
+                        && !is_current
+                    {
+                        selected = Some(profile.url);
+                    }
+                }
+            });
+
+        if let Some(url) = selected {
+            state.set_multiplayer_url(url);
+        }
+    }
+
+    /// Explains why the online mode is unavailable, the handshake is retried if it failed.
+    fn render_server_status(&mut self, ui: &mut Ui, state: &mut AppState) {
+        match state.api.server_info() {
+            ServerInfoState::Incompatible(reason) => {
+                ui.add_space(5.0);
+                ui.label(RichText::new(reason).color(ui.visuals().error_fg_color));
+            }
+            ServerInfoState::Error(error) => {
+                ui.add_space(5.0);
+                ui.label(
+                    RichText::new("Could not reach the server.").color(ui.visuals().warn_fg_color),
+                )
+                .on_hover_text(error);
+                if ui.small_button("Retry").clicked() {
+                    state.connect();
+                }
+            }
+            ServerInfoState::Unknown
+            | ServerInfoState::Checking
+            | ServerInfoState::Compatible(_) => {}
+        }
+    }
+
+    /// Reminds the user of correspondence games which were refreshed on startup.
+    fn render_correspondence_notice(&mut self, ui: &mut Ui, state: &mut AppState) {
+        let Some(username) = state.get_username() else {
+            return;
+        };
+        let own_turns = state.correspondence_games.lock().count_own_turns(&username);
+        match own_turns {
+            0 => {}
+            1 => {
+                ui.add_space(5.0);
+                ui.label("It's your move in 1 correspondence game.");
+            }
+            count => {
+                ui.add_space(5.0);
+                ui.label(format!("It's your move in {count} correspondence games."));
+            }
+        }
+    }
+
     fn on_sandbox_clicked(&mut self, _ctx: &Context, state: &mut AppState) {
         state.switch_view(ViewID::Sandbox)
     }
 
     fn on_online_clicked(&mut self, _ctx: &Context, state: &mut AppState) {
-        if (!state.api.is_ready()) {
+        if !state.api.is_ready() {
             self.server_settings_modal.set_open(true);
-        } else if (state.login_state.lock().get_status() != LoginStateStatus::Success) {
+        } else if !state.is_logged_in() {
             self.login_or_register_modal.set_open(true);
         } else {
             state.switch_view(ViewID::OnlineHome);
@@ -73,10 +166,18 @@ impl View for MainMenuView {
     }
 
     fn render(&mut self, ctx: &Context, state: &mut AppState) {
+        if state.login_state().lock().get_status() == LoginStateStatus::Expired
+            && !self.login_modal.is_open()
+        {
+            self.login_modal.set_open(true);
+        }
+
         self.render_login_modal(ctx, state);
         self.render_login_or_register_modal(ctx, state);
+        self.render_profiles_modal(ctx, state);
         self.render_register_modal(ctx, state);
         self.render_server_settings_modal(ctx, state);
+        let _ = self.settings_bundle_modal.render(ctx, state);
 
         CentralPanel::default().show(ctx, |ui| {
             let vertical_space = ui.available_size_before_wrap().y;
@@ -91,10 +192,27 @@ impl View for MainMenuView {
                 );
 
                 ui.add_space(20.0);
-                let online_response = ui.add(
+                let online_response = ui.add_enabled(
+                    !state.api.server_info().is_incompatible(),
                     Button::new(RichText::new("Online").size(50.0))
                         .min_size(Vec2::new(400.0, 100.0)),
                 );
+                self.render_server_status(ui, state);
+                self.render_correspondence_notice(ui, state);
+
+                ui.add_space(20.0);
+                let profile_response = ui.add(
+                    Button::new(RichText::new(format!("Profile: {}", state.profile)).size(20.0))
+                        .min_size(Vec2::new(400.0, 40.0)),
+                );
+
+                self.render_server_switch(ui, state);
+
+                ui.add_space(10.0);
+                let settings_response = ui.add(
+                    Button::new(RichText::new("Import / Export Settings").size(20.0))
+                        .min_size(Vec2::new(400.0, 40.0)),
+                );
 
                 if sandbox_response.clicked() {
                     self.on_sandbox_clicked(ctx, state);
@@ -102,6 +220,12 @@ impl View for MainMenuView {
                 if online_response.clicked() {
                     self.on_online_clicked(ctx, state);
                 }
+                if profile_response.clicked() {
+                    self.open_profiles_modal();
+                }
+                if settings_response.clicked() {
+                    self.settings_bundle_modal.set_open(true);
+                }
             });
         });
     }
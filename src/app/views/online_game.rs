@@ -0,0 +1,324 @@
+use crate::api::live::MAX_CHAT_MESSAGE_LENGTH;
+use crate::app::components::chess_board::ChessBoardComponent;
+use crate::app::state::online_game::{ConnectionStatus, OnlineGame};
+use crate::app::state::AppState;
+use crate::app::views::{View, ViewID};
+use chrono::{DateTime, Local};
+use egui::{
+    Button, CentralPanel, Context, Grid, Key, RichText, ScrollArea, SidePanel, TextEdit,
+    TopBottomPanel, Ui,
+};
+use giga_chess::prelude::Color;
+use giga_chess_api_types::response::user_info::PublicUserInfo;
+use std::time::{Duration, Instant};
+
+const QUICK_MESSAGES: [&str; 4] = ["Hello!", "Good luck", "Good game", "Thanks!"];
+
+#[derive(Debug, Default)]
+pub struct OnlineGameView {
+    chess_board: ChessBoardComponent,
+    room_uuid: Option<String>,
+    chat_input: String,
+}
+
+impl OnlineGameView {
+    /// Running games stay connected in the lobby and can be returned to, correspondence games are
+    /// continued later.
+    fn on_leave_clicked(&mut self, online_game: OnlineGame, state: &mut AppState) {
+        if online_game.is_correspondence() {
+            state.online_game = Some(online_game);
+            state.close_correspondence_game();
+            return;
+        }
+        if online_game.is_running() && !online_game.is_spectating() {
+            state.park_online_game(online_game);
+            return;
+        }
+        state.cancel_matchmaking();
+        if state.is_tournament_game(&online_game.room.uuid) {
+            state.switch_view(ViewID::Tournaments);
+        } else {
+            state.switch_view(ViewID::OnlineHome);
+        }
+    }
+
+    fn render_info(&mut self, ui: &mut Ui, online_game: &mut OnlineGame) -> bool {
+        let mut leave_clicked = false;
+
+        ui.heading(online_game.room.name.as_deref().unwrap_or("Online Game"));
+        render_connection_status(ui, &online_game.connection_status);
+        if let Some(days_per_move) = online_game.days_per_move {
+            ui.label(format!("Correspondence, {days_per_move} days per move"));
+        }
+        if online_game.spectators > 0 {
+            ui.label(format!("👁 {} watching", online_game.spectators));
+        }
+        ui.separator();
+
+        Grid::new("online_game_players")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for (color, player) in [
+                    (Color::Black, &online_game.room.black),
+                    (Color::White, &online_game.room.white),
+                ] {
+                    let name = get_player_name(player, color);
+                    let is_to_move = online_game.is_running()
+                        && online_game.app_game.game.side_to_move() == color;
+                    if is_to_move {
+                        ui.label(RichText::new(name).strong());
+                    } else {
+                        ui.label(name);
+                    }
+
+                    match online_game.get_time_left(color) {
+                        Some(time_left) => ui.monospace(format_clock(time_left)),
+                        None => ui.label("-"),
+                    };
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+        if let Some(game_end) = &online_game.game_end {
+            ui.label(RichText::new(&game_end.result).strong().size(20.0));
+            ui.label(&game_end.reason);
+        } else if online_game.is_spectating() {
+            ui.label("You are watching this game.");
+        } else if online_game
+            .app_game
+            .can_color_move(online_game.app_game.game.side_to_move())
+        {
+            ui.label("Your move.");
+        } else if online_game.is_correspondence() {
+            ui.label("Waiting for your opponent, you can come back later.");
+        } else {
+            ui.label("Waiting for your opponent...");
+        }
+
+        if let Some(error) = &online_game.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            let can_resign = online_game.is_running() && !online_game.is_spectating();
+            if ui.add_enabled(can_resign, Button::new("Resign")).clicked() {
+                online_game.resign();
+            }
+            if ui.button("Back to Lobby").clicked() {
+                leave_clicked = true;
+            }
+        });
+
+        ui.separator();
+        ui.label(RichText::new("Moves").strong());
+        ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+            Grid::new("online_game_moves")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    let history = online_game.app_game.game.algebraic_history();
+                    for (index, moves) in history.chunks(2).enumerate() {
+                        ui.label(format!("{}.", index + 1));
+                        for notation in moves {
+                            ui.label(notation);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        leave_clicked
+    }
+
+    fn render_chat(&mut self, ui: &mut Ui, online_game: &mut OnlineGame) {
+        ui.horizontal(|ui| {
+            ui.heading("Chat");
+            if let Some(opponent) = online_game.get_opponent_name() {
+                let label = format!("Mute {opponent}");
+                ui.checkbox(&mut online_game.opponent_muted, label);
+            }
+        });
+        ui.separator();
+
+        if online_game.is_spectating() {
+            ui.label("Only the players can chat.");
+        }
+        let can_chat = online_game.can_chat();
+        let mut quick_message = None;
+        ui.horizontal_wrapped(|ui| {
+            for message in QUICK_MESSAGES {
+                if ui.add_enabled(can_chat, Button::new(message)).clicked() {
+                    quick_message = Some(message);
+                }
+            }
+        });
+        if let Some(message) = quick_message {
+            online_game.send_chat(message);
+        }
+
+        let input_response = ui.add_enabled(
+            can_chat,
+            TextEdit::singleline(&mut self.chat_input)
+                .hint_text("Message")
+                .char_limit(MAX_CHAT_MESSAGE_LENGTH)
+                .desired_width(f32::INFINITY),
+        );
+        let submitted =
+            input_response.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter));
+        if submitted && online_game.send_chat(&self.chat_input) {
+            self.chat_input.clear();
+            input_response.request_focus();
+        }
+
+        if let Some(cooldown) = online_game.get_chat_cooldown() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "You are sending messages too fast, wait {}s.",
+                    cooldown.as_secs() + 1
+                ),
+            );
+            ui.ctx().request_repaint_after(Duration::from_millis(200));
+        } else if let Some(error) = &online_game.chat_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        ui.separator();
+
+        ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                for message in online_game.get_visible_chat() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.weak(format_time(message.sent_at));
+                        ui.strong(&message.from);
+                        ui.label(&message.text);
+                    });
+                }
+            });
+    }
+}
+
+fn render_connection_status(ui: &mut Ui, status: &ConnectionStatus) {
+    match status {
+        ConnectionStatus::Connecting => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Connecting...");
+            });
+        }
+        ConnectionStatus::Connected => {}
+        ConnectionStatus::Reconnecting { retry_at } => {
+            let retry_in = retry_at.saturating_duration_since(Instant::now());
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!(
+                    "Connection lost, reconnecting in {}s...",
+                    retry_in.as_secs() + 1
+                ));
+            });
+        }
+        ConnectionStatus::Closed(reason) => {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!("Disconnected: {reason}"),
+            );
+        }
+    }
+}
+
+fn get_player_name(player: &Option<PublicUserInfo>, color: Color) -> String {
+    let name = player
+        .as_ref()
+        .map(|player| player.name.as_str())
+        .unwrap_or("?");
+    format!("{name} ({color:?})")
+}
+
+fn format_time(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|time| time.with_timezone(&Local).format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// Correspondence clocks run for days, those are shown without seconds.
+fn format_clock(time_left: Duration) -> String {
+    let secs = time_left.as_secs();
+    match secs {
+        86400.. => format!("{}d {:02}h", secs / 86400, secs % 86400 / 3600),
+        3600.. => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{:02}:{:02}", secs / 60, secs % 60),
+    }
+}
+
+impl View for OnlineGameView {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn render(&mut self, ctx: &Context, state: &mut AppState) {
+        // The board borrows the state, so the game is taken out for the duration of the frame
+        let Some(mut online_game) = state.online_game.take() else {
+            state.switch_view(ViewID::OnlineHome);
+            return;
+        };
+
+        if self.room_uuid.as_ref() != Some(&online_game.room.uuid) {
+            self.room_uuid = Some(online_game.room.uuid.clone());
+            self.chess_board.clear();
+        }
+        if online_game.process_events(&state.engine) {
+            self.chess_board.clear();
+        }
+        if online_game.is_running() {
+            // Keeps the clocks ticking
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+
+        let mut leave_clicked = false;
+        TopBottomPanel::top("online_game_top_panel").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                let title = if online_game.is_spectating() {
+                    "Spectating"
+                } else {
+                    "Online Game"
+                };
+                ui.label(RichText::new(title).size(20.0));
+            });
+        });
+
+        SidePanel::right("online_game_info_panel")
+            .resizable(false)
+            .min_width(220.0)
+            .show(ctx, |ui| {
+                leave_clicked = self.render_info(ui, &mut online_game);
+            });
+
+        SidePanel::left("online_game_chat_panel")
+            .default_width(240.0)
+            .show(ctx, |ui| {
+                self.render_chat(ui, &mut online_game);
+            });
+
+        let move_count = online_game.app_game.game.move_history().len();
+        CentralPanel::default().show(ctx, |ui| {
+            self.chess_board
+                .render(ui, state, &mut online_game.app_game);
+        });
+        if online_game.app_game.game.move_history().len() > move_count
+            && let Some(chess_move) = online_game.app_game.game.latest_move()
+        {
+            online_game.send_move(chess_move);
+        }
+
+        if leave_clicked {
+            self.on_leave_clicked(online_game, state);
+        } else {
+            state.online_game = Some(online_game);
+        }
+    }
+}
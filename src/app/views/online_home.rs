@@ -1,24 +1,756 @@
+use crate::api::correspondence::{CorrespondenceBody, CorrespondenceGame, DAYS_PER_MOVE_PRESETS};
+use crate::api::friends::{Friend, Presence};
+use crate::api::matchmaking::format_time_control;
+use crate::api::matchmaking::ColorPreference;
+use crate::api::server_info::Capability;
+use crate::app::components::game_options::GameOptionsComponent;
+use crate::app::components::retry_status::render_loading;
+use crate::app::components::validated_field::ValidatedField;
+use crate::app::modals::challenge::ChallengeModal;
+use crate::app::modals::create_game::CreateGameModal;
+use crate::app::modals::{Modal, ModalEvent};
+use crate::app::state::account::AccountState;
+use crate::app::state::correspondence::CorrespondenceState;
+use crate::app::state::friends::FriendsState;
+use crate::app::state::matchmaking::{MatchmakingState, OpenGamesState};
+use crate::app::state::spectating::LiveGamesState;
 use crate::app::state::AppState;
+use crate::app::validation::{validate_game_id, validate_username};
 use crate::app::views::{View, ViewID};
-use egui::{Button, CentralPanel, Context, RichText, TopBottomPanel};
+use chrono::Utc;
+use egui::{
+    Align, Align2, Button, CentralPanel, Color32, ComboBox, Context, Grid, Layout, RichText,
+    SidePanel, TopBottomPanel, Ui, Window,
+};
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use giga_chess_api_types::response::user_info::PublicUserInfo;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Default)]
-pub struct OnlineHomeView;
+const OPEN_GAMES_PER_PAGE: i64 = 20;
+const LIVE_GAMES_PER_PAGE: i64 = 10;
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Presence and incoming challenges don't need to be as fresh as matchmaking.
+const FRIENDS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Correspondence opponents take their time, the dashboard is refreshed rarely.
+const CORRESPONDENCE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub struct OnlineHomeView {
+    create_game_modal: CreateGameModal,
+    challenge_modal: ChallengeModal,
+    quick_match_options: GameOptionsComponent,
+    join_game_id: String,
+    open_games_page: i64,
+    open_games_loaded: bool,
+    live_games_page: i64,
+    live_games_loaded: bool,
+    last_poll: Option<Instant>,
+    friend_name: String,
+    last_friends_poll: Option<Instant>,
+    correspondence_days: u32,
+    correspondence_color: ColorPreference,
+    correspondence_public: bool,
+    last_correspondence_poll: Option<Instant>,
+}
 
 impl OnlineHomeView {
     fn on_home_clicked(&mut self, _ctx: &Context, state: &mut AppState) {
         state.switch_view(ViewID::MainMenu);
     }
+
+    fn on_logout_clicked(&mut self, _ctx: &Context, state: &mut AppState) {
+        self.open_games_loaded = false;
+        self.live_games_loaded = false;
+        state.logout();
+    }
+
+    fn refresh_open_games(&mut self, state: &mut AppState) {
+        state.refresh_open_games(self.open_games_page, OPEN_GAMES_PER_PAGE);
+        self.open_games_loaded = true;
+    }
+
+    fn refresh_live_games(&mut self, state: &mut AppState) {
+        state.refresh_live_games(self.live_games_page, LIVE_GAMES_PER_PAGE);
+        self.live_games_loaded = true;
+    }
+
+    fn poll_matchmaking(&mut self, ctx: &Context, state: &mut AppState) {
+        if !state.matchmaking.lock().is_searching() {
+            self.last_poll = None;
+            return;
+        }
+
+        let is_due = self
+            .last_poll
+            .is_none_or(|last_poll| last_poll.elapsed() >= POLL_INTERVAL);
+        if is_due {
+            state.poll_matchmaking();
+            self.last_poll = Some(Instant::now());
+        }
+        ctx.request_repaint_after(POLL_INTERVAL);
+    }
+
+    fn poll_friends(&mut self, ctx: &Context, state: &mut AppState) {
+        let is_due = state.friends.lock().needs_refresh()
+            || self
+                .last_friends_poll
+                .is_none_or(|last_poll| last_poll.elapsed() >= FRIENDS_POLL_INTERVAL);
+        if is_due {
+            state.refresh_friends();
+            self.last_friends_poll = Some(Instant::now());
+        }
+        ctx.request_repaint_after(FRIENDS_POLL_INTERVAL);
+    }
+
+    fn poll_correspondence_games(&mut self, ctx: &Context, state: &mut AppState) {
+        let is_due = matches!(
+            state.correspondence_games.get_clone(),
+            CorrespondenceState::Idle
+        ) || self
+            .last_correspondence_poll
+            .is_none_or(|last_poll| last_poll.elapsed() >= CORRESPONDENCE_POLL_INTERVAL);
+        if is_due {
+            state.refresh_correspondence_games();
+            self.last_correspondence_poll = Some(Instant::now());
+        }
+        ctx.request_repaint_after(CORRESPONDENCE_POLL_INTERVAL);
+    }
+
+    fn start_matched_game(&mut self, ctx: &Context, state: &mut AppState) {
+        if state.online_game.is_some() {
+            return;
+        }
+
+        let MatchmakingState::Matched(room) = state.matchmaking.get_clone() else {
+            return;
+        };
+        let ctx = ctx.clone();
+        state.start_online_game(room, move || ctx.request_repaint());
+    }
+
+    fn render_create_game_modal(&mut self, ctx: &Context, state: &mut AppState) {
+        let event = self.create_game_modal.render(ctx, state);
+        if event == ModalEvent::CreateGame {
+            self.create_game_modal.set_open(false);
+        }
+    }
+
+    fn render_challenge_modal(&mut self, ctx: &Context, state: &mut AppState) {
+        let event = self.challenge_modal.render(ctx, state);
+        if event == ModalEvent::SendChallenge {
+            self.challenge_modal.set_open(false);
+        }
+    }
+
+    /// Shows the oldest incoming challenge as a notification in the top right corner.
+    fn render_incoming_challenge(&mut self, ctx: &Context, state: &mut AppState) {
+        let Some(challenge) = state.incoming_challenges.lock().first().cloned() else {
+            return;
+        };
+        let is_idle = state.matchmaking.lock().is_idle();
+
+        Window::new("Challenge")
+            .id(egui::Id::new("incoming_challenge"))
+            .anchor(Align2::RIGHT_TOP, [-10.0, 40.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} challenges you to a game ({}, your colour: {}).",
+                    challenge.challenger.name,
+                    format_time_control(challenge.time_control()),
+                    challenge.color.opposite()
+                ));
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(is_idle, Button::new("Accept")).clicked() {
+                        state.accept_challenge(&challenge.uuid);
+                    }
+                    if ui.button("Decline").clicked() {
+                        state.decline_challenge(&challenge.uuid);
+                    }
+                });
+            });
+    }
+
+    fn render_friends(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.heading("Friends");
+
+        ui.horizontal(|ui| {
+            let name_response = ValidatedField::new("Name", &mut self.friend_name)
+                .label_width(40.0)
+                .validator(validate_username)
+                .error_message("Must be a valid username.")
+                .show(ui);
+            if ui
+                .add_enabled(name_response.is_valid, Button::new("Add"))
+                .clicked()
+            {
+                state.add_friend(self.friend_name.trim());
+                self.friend_name.clear();
+            }
+        });
+        if let Some(error) = state.friends_error.get_clone() {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        ui.separator();
+
+        let list = match state.friends.get_clone() {
+            FriendsState::Idle => return,
+            FriendsState::Loading => {
+                render_loading(ui, state.api.retry_status());
+                return;
+            }
+            FriendsState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return;
+            }
+            FriendsState::Loaded(list) | FriendsState::Outdated(list) => list,
+        };
+
+        if !list.incoming_requests.is_empty() {
+            ui.label(RichText::new("Friend Requests").strong());
+            for user in &list.incoming_requests {
+                ui.horizontal(|ui| {
+                    ui.label(&user.name);
+                    if ui.button("Accept").clicked() {
+                        state.add_friend(&user.name);
+                    }
+                    if ui.button("Decline").clicked() {
+                        state.remove_friend(&user.name);
+                    }
+                });
+            }
+            ui.separator();
+        }
+
+        if !list.outgoing_requests.is_empty() {
+            ui.label(RichText::new("Sent Requests").strong());
+            for user in &list.outgoing_requests {
+                ui.horizontal(|ui| {
+                    ui.label(&user.name);
+                    if ui.button("Cancel").clicked() {
+                        state.remove_friend(&user.name);
+                    }
+                });
+            }
+            ui.separator();
+        }
+
+        if list.friends.is_empty() {
+            ui.label("No friends yet, add someone by their name.");
+            return;
+        }
+
+        let can_challenge = state.matchmaking.lock().is_idle();
+        let supports_profiles = state.api.server_info().supports(Capability::Profiles);
+        for friend in &list.friends {
+            ui.horizontal(|ui| {
+                ui.colored_label(get_presence_color(friend), "●")
+                    .on_hover_text(friend.presence.to_string());
+                if supports_profiles {
+                    if ui.link(&friend.name).clicked() {
+                        state.open_player_profile(Some(friend.name.clone()));
+                    }
+                } else {
+                    ui.label(&friend.name);
+                }
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.small_button("Remove").clicked() {
+                        state.remove_friend(&friend.name);
+                    }
+                    let is_available = friend.presence == Presence::Online;
+                    if ui
+                        .add_enabled(
+                            can_challenge && is_available,
+                            Button::new("Challenge").small(),
+                        )
+                        .clicked()
+                    {
+                        self.challenge_modal.open_for(&friend.name);
+                    }
+                });
+            });
+        }
+    }
+
+    fn render_play(&mut self, ui: &mut Ui, state: &mut AppState) {
+        let is_idle = state.matchmaking.lock().is_idle();
+
+        ui.heading("Play");
+        if ui
+            .add_enabled(is_idle, Button::new("Create Game"))
+            .clicked()
+        {
+            self.create_game_modal.set_open(true);
+        }
+
+        ui.add_space(10.0);
+        ui.label(RichText::new("Quick Match").strong());
+        if state.api.server_info().supports(Capability::Queue) {
+            self.quick_match_options.render(ui, "quick_match_options");
+            if ui
+                .add_enabled(is_idle, Button::new("Find Opponent"))
+                .clicked()
+            {
+                state.quick_match(
+                    self.quick_match_options.time_control,
+                    self.quick_match_options.color,
+                );
+            }
+        } else {
+            ui.label("Not supported by this server.");
+        }
+
+        ui.add_space(10.0);
+        ui.label(RichText::new("Join by ID").strong());
+        let game_id_response = ValidatedField::new("ID", &mut self.join_game_id)
+            .label_width(20.0)
+            .validator(validate_game_id)
+            .error_message("Must be a valid game ID.")
+            .show(ui);
+        if ui
+            .add_enabled(is_idle && game_id_response.is_valid, Button::new("Join"))
+            .clicked()
+        {
+            state.join_game(self.join_game_id.trim());
+        }
+
+        ui.separator();
+        self.render_matchmaking_status(ui, state);
+        self.render_correspondence(ui, state);
+    }
+
+    fn render_matchmaking_status(&mut self, ui: &mut Ui, state: &mut AppState) {
+        let matchmaking = state.matchmaking.get_clone();
+        match &matchmaking {
+            MatchmakingState::Idle => {}
+            MatchmakingState::Loading => {
+                render_loading(ui, state.api.retry_status());
+            }
+            MatchmakingState::WaitingForOpponent(room) => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!(
+                        "Waiting for an opponent to join {}...",
+                        get_room_name(room)
+                    ));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Game ID:");
+                    let mut uuid = room.uuid.clone();
+                    ui.text_edit_singleline(&mut uuid);
+                });
+            }
+            MatchmakingState::Queued(time_control) => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!(
+                        "Searching for an opponent ({})...",
+                        format_time_control(*time_control)
+                    ));
+                });
+            }
+            MatchmakingState::Challenging(challenge) => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!(
+                        "Waiting for {} to accept your challenge ({})...",
+                        challenge.challenged.name,
+                        format_time_control(challenge.time_control())
+                    ));
+                });
+            }
+            MatchmakingState::Matched(room) => {
+                ui.label(RichText::new("Opponent found!").strong());
+                ui.label(format!(
+                    "{} vs {}",
+                    get_player_name(&room.white),
+                    get_player_name(&room.black)
+                ));
+                if state.online_game.is_some() && ui.button("Return to Game").clicked() {
+                    state.switch_view(ViewID::OnlineGame);
+                }
+            }
+            MatchmakingState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        }
+
+        let cancel_label = match matchmaking {
+            MatchmakingState::WaitingForOpponent(_)
+            | MatchmakingState::Queued(_)
+            | MatchmakingState::Challenging(_) => Some("Cancel"),
+            MatchmakingState::Matched(_)
+                if state
+                    .online_game
+                    .as_ref()
+                    .is_some_and(|game| game.is_running() && !game.is_spectating()) =>
+            {
+                Some("Resign and Leave")
+            }
+            MatchmakingState::Matched(_) => Some("Leave"),
+            _ => None,
+        };
+        if let Some(label) = cancel_label
+            && ui.button(label).clicked()
+        {
+            state.cancel_matchmaking();
+        }
+    }
+
+    fn render_correspondence(&mut self, ui: &mut Ui, state: &mut AppState) {
+        if !state.api.server_info().supports(Capability::Correspondence) {
+            return;
+        }
+        ui.separator();
+        ui.heading("Correspondence");
+
+        ui.horizontal(|ui| {
+            ComboBox::from_id_salt("correspondence_days")
+                .selected_text(format_days(self.correspondence_days))
+                .show_ui(ui, |ui| {
+                    for days in DAYS_PER_MOVE_PRESETS {
+                        ui.selectable_value(&mut self.correspondence_days, days, format_days(days));
+                    }
+                });
+            ComboBox::from_id_salt("correspondence_color")
+                .selected_text(self.correspondence_color.to_string())
+                .show_ui(ui, |ui| {
+                    for color in ColorPreference::ALL {
+                        ui.selectable_value(
+                            &mut self.correspondence_color,
+                            color,
+                            color.to_string(),
+                        );
+                    }
+                });
+            ui.checkbox(&mut self.correspondence_public, "Public")
+                .on_hover_text("Private games can only be joined by sharing their ID.");
+            if ui.button("Create").clicked() {
+                state.create_correspondence_game(CorrespondenceBody {
+                    name: None,
+                    public: self.correspondence_public,
+                    days_per_move: self.correspondence_days,
+                    color: self.correspondence_color,
+                });
+            }
+        });
+        if let Some(error) = state.correspondence_error.get_clone() {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        let list = match state.correspondence_games.get_clone() {
+            CorrespondenceState::Idle => return,
+            CorrespondenceState::Loading => {
+                render_loading(ui, state.api.retry_status());
+                return;
+            }
+            CorrespondenceState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return;
+            }
+            CorrespondenceState::Loaded(list) => list,
+        };
+        if list.games.is_empty() {
+            ui.label("No running correspondence games.");
+            return;
+        }
+
+        let username = state.get_username().unwrap_or_default();
+        let mut games: Vec<&CorrespondenceGame> = list.games.iter().collect();
+        games.sort_by_key(|game| !game.is_turn_of(&username));
+        let can_open = state.online_game.is_none() && state.matchmaking.lock().is_idle();
+        Grid::new("correspondence_games")
+            .num_columns(4)
+            .spacing([20.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for game in games {
+                    let opponent = game
+                        .get_opponent(&username)
+                        .map(|opponent| opponent.name.as_str());
+                    ui.label(opponent.unwrap_or("-"));
+
+                    if !game.is_started() {
+                        ui.label("Waiting for an opponent");
+                    } else if game.is_turn_of(&username) {
+                        ui.label(RichText::new("Your move").strong());
+                    } else {
+                        ui.label("Their move");
+                    }
+                    ui.label(game.deadline.map(format_deadline).unwrap_or_default());
+
+                    let can_open_game = can_open && game.is_started();
+                    if ui.add_enabled(can_open_game, Button::new("Open")).clicked() {
+                        let ctx = ui.ctx().clone();
+                        state.open_correspondence_game(game, move || ctx.request_repaint());
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn render_open_games(&mut self, ui: &mut Ui, state: &mut AppState) {
+        if !state.api.server_info().supports(Capability::OpenGames) {
+            ui.heading("Open Games");
+            ui.label("Not supported by this server.");
+            return;
+        }
+
+        let is_idle = state.matchmaking.lock().is_idle();
+        let open_games = state.open_games.get_clone();
+
+        ui.horizontal(|ui| {
+            ui.heading("Open Games");
+            let is_loading = matches!(open_games, OpenGamesState::Loading);
+            if ui
+                .add_enabled(!is_loading, Button::new("Refresh"))
+                .clicked()
+            {
+                self.refresh_open_games(state);
+            }
+        });
+
+        let rooms = match open_games {
+            OpenGamesState::Idle => return,
+            OpenGamesState::Loading => {
+                render_loading(ui, state.api.retry_status());
+                return;
+            }
+            OpenGamesState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return;
+            }
+            OpenGamesState::Loaded(rooms) => rooms,
+        };
+
+        if rooms.rooms.is_empty() {
+            ui.label("There are no open games right now, why not create one?");
+        } else {
+            Grid::new("open_games")
+                .num_columns(4)
+                .spacing([20.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Name");
+                    ui.strong("White");
+                    ui.strong("Black");
+                    ui.end_row();
+
+                    for room in &rooms.rooms {
+                        ui.label(room.name.as_deref().unwrap_or("Unnamed"));
+                        ui.label(get_player_name(&room.white));
+                        ui.label(get_player_name(&room.black));
+                        if ui.add_enabled(is_idle, Button::new("Join")).clicked() {
+                            state.join_game(&room.uuid);
+                        }
+                        ui.end_row();
+                    }
+                });
+        }
+
+        let page_count = (rooms.pagination.total + OPEN_GAMES_PER_PAGE - 1) / OPEN_GAMES_PER_PAGE;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.open_games_page > 1, Button::new("Previous"))
+                .clicked()
+            {
+                self.open_games_page -= 1;
+                self.refresh_open_games(state);
+            }
+            ui.label(format!(
+                "Page {} of {}",
+                self.open_games_page,
+                page_count.max(1)
+            ));
+            if ui
+                .add_enabled(self.open_games_page < page_count, Button::new("Next"))
+                .clicked()
+            {
+                self.open_games_page += 1;
+                self.refresh_open_games(state);
+            }
+        });
+    }
+
+    fn render_live_games(&mut self, ui: &mut Ui, state: &mut AppState) {
+        if !state.api.server_info().supports(Capability::Spectating) {
+            return;
+        }
+        ui.separator();
+
+        let is_idle = state.matchmaking.lock().is_idle();
+        let live_games = state.live_games.get_clone();
+
+        ui.horizontal(|ui| {
+            ui.heading("Live Games");
+            let is_loading = matches!(live_games, LiveGamesState::Loading);
+            if ui
+                .add_enabled(!is_loading, Button::new("Refresh"))
+                .clicked()
+            {
+                self.refresh_live_games(state);
+            }
+        });
+
+        let games = match live_games {
+            LiveGamesState::Idle => return,
+            LiveGamesState::Loading => {
+                render_loading(ui, state.api.retry_status());
+                return;
+            }
+            LiveGamesState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return;
+            }
+            LiveGamesState::Loaded(games) => games,
+        };
+
+        if games.games.is_empty() {
+            ui.label("Nobody is playing right now.");
+        } else {
+            Grid::new("live_games")
+                .num_columns(5)
+                .spacing([20.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("White");
+                    ui.strong("Black");
+                    ui.strong("Time");
+                    ui.strong("Watching");
+                    ui.end_row();
+
+                    for game in &games.games {
+                        ui.label(&game.white.name);
+                        ui.label(&game.black.name);
+                        ui.label(format_time_control(game.time_control()));
+                        ui.label(game.spectators.to_string());
+                        if ui.add_enabled(is_idle, Button::new("Watch")).clicked() {
+                            let ctx = ui.ctx().clone();
+                            state.spectate_game(game, move || ctx.request_repaint());
+                        }
+                        ui.end_row();
+                    }
+                });
+        }
+
+        let page_count = (games.pagination.total + LIVE_GAMES_PER_PAGE - 1) / LIVE_GAMES_PER_PAGE;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.live_games_page > 1, Button::new("Previous"))
+                .clicked()
+            {
+                self.live_games_page -= 1;
+                self.refresh_live_games(state);
+            }
+            ui.label(format!(
+                "Page {} of {}",
+                self.live_games_page,
+                page_count.max(1)
+            ));
+            if ui
+                .add_enabled(self.live_games_page < page_count, Button::new("Next"))
+                .clicked()
+            {
+                self.live_games_page += 1;
+                self.refresh_live_games(state);
+            }
+        });
+    }
+}
+
+fn get_room_name(room: &PrivateRoomInfo) -> &str {
+    room.name.as_deref().unwrap_or("your game")
+}
+
+fn format_days(days: u32) -> String {
+    match days {
+        1 => "1 day per move".to_string(),
+        days => format!("{days} days per move"),
+    }
+}
+
+fn format_deadline(deadline: i64) -> String {
+    let secs = (deadline - Utc::now().timestamp()).max(0);
+    match secs {
+        86400.. => format!("{}d {}h left", secs / 86400, secs % 86400 / 3600),
+        3600.. => format!("{}h {}m left", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}m left", secs / 60),
+    }
+}
+
+fn get_presence_color(friend: &Friend) -> Color32 {
+    match friend.presence {
+        Presence::Offline => Color32::GRAY,
+        Presence::Online => Color32::from_rgb(80, 200, 120),
+        Presence::Playing => Color32::from_rgb(230, 170, 60),
+    }
+}
+
+fn get_player_name(player: &Option<PublicUserInfo>) -> &str {
+    player
+        .as_ref()
+        .map(|player| player.name.as_str())
+        .unwrap_or("-")
 }
 
 impl View for OnlineHomeView {
     fn new() -> Self {
-        Self
+        Self {
+            create_game_modal: CreateGameModal::default(),
+            challenge_modal: ChallengeModal::default(),
+            quick_match_options: GameOptionsComponent::default(),
+            join_game_id: String::new(),
+            open_games_page: 1,
+            open_games_loaded: false,
+            live_games_page: 1,
+            live_games_loaded: false,
+            last_poll: None,
+            friend_name: String::new(),
+            last_friends_poll: None,
+            correspondence_days: DAYS_PER_MOVE_PRESETS[2],
+            correspondence_color: ColorPreference::default(),
+            correspondence_public: true,
+            last_correspondence_poll: None,
+        }
     }
 
     fn render(&mut self, ctx: &Context, state: &mut AppState) {
-        // Just for the background color
-        CentralPanel::default().show(ctx, |_ui| {});
+        // The session expired or was ended elsewhere, the main menu takes care of logging in again.
+        // Incompatible servers are explained there as well.
+        if !state.is_logged_in() || state.api.server_info().is_incompatible() {
+            self.open_games_loaded = false;
+            self.live_games_loaded = false;
+            state.switch_view(ViewID::MainMenu);
+            return;
+        }
+
+        if !self.open_games_loaded && state.api.server_info().supports(Capability::OpenGames) {
+            self.refresh_open_games(state);
+        }
+        if !self.live_games_loaded && state.api.server_info().supports(Capability::Spectating) {
+            self.refresh_live_games(state);
+        }
+        let supports_friends = state.api.server_info().supports(Capability::Friends);
+        if supports_friends {
+            self.poll_friends(ctx, state);
+        }
+        if state.api.server_info().supports(Capability::Correspondence) {
+            self.poll_correspondence_games(ctx, state);
+        }
+        if state.api.server_info().supports(Capability::InviteCodes)
+            && matches!(state.account.get_clone(), AccountState::Idle)
+        {
+            state.load_account();
+        }
+        self.poll_matchmaking(ctx, state);
+        self.start_matched_game(ctx, state);
+        self.render_create_game_modal(ctx, state);
+        self.render_challenge_modal(ctx, state);
+        if supports_friends {
+            self.render_incoming_challenge(ctx, state);
+        }
 
         TopBottomPanel::top("online_home_top_panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
@@ -28,6 +760,57 @@ impl View for OnlineHomeView {
                 }
 
                 ui.label("Online Mode");
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.button("Logout").clicked() {
+                        self.on_logout_clicked(ctx, state);
+                    }
+                    if state
+                        .api
+                        .server_info()
+                        .supports(Capability::AccountManagement)
+                        && ui.button("Account").clicked()
+                    {
+                        state.open_account();
+                    }
+                    if state.api.server_info().supports(Capability::Profiles)
+                        && ui.button("Profile").clicked()
+                    {
+                        state.open_player_profile(None);
+                    }
+                    if state.api.server_info().supports(Capability::Leaderboards)
+                        && ui.button("Leaderboard").clicked()
+                    {
+                        state.open_leaderboard();
+                    }
+                    if state.api.server_info().supports(Capability::Tournaments)
+                        && ui.button("Tournaments").clicked()
+                    {
+                        state.open_tournaments();
+                    }
+                    if state.api.server_info().supports(Capability::InviteCodes)
+                        && state.account.lock().is_admin()
+                        && ui.button("Invite Codes").clicked()
+                    {
+                        state.open_invite_codes();
+                    }
+                });
+            });
+        });
+
+        if supports_friends {
+            SidePanel::right("online_home_friends_panel")
+                .default_width(240.0)
+                .show(ctx, |ui| {
+                    self.render_friends(ui, state);
+                });
+        }
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.columns(2, |columns| {
+                self.render_play(&mut columns[0], state);
+                self.render_open_games(&mut columns[1], state);
+                self.render_live_games(&mut columns[1], state);
             });
         });
     }
@@ -0,0 +1,378 @@
+use crate::api::matchmaking::format_time_control;
+use crate::api::player_profile::{GameHistory, GameOutcome, GameSummary, UserProfile};
+use crate::api::server_info::Capability;
+use crate::app::components::chess_board::ChessBoardComponent;
+use crate::app::components::retry_status::render_loading;
+use crate::app::state::player_profile::{
+    GameHistoryState, GameReplay, GameReplayState, PlayerProfileState,
+};
+use crate::app::state::AppState;
+use crate::app::views::{View, ViewID};
+use chrono::{DateTime, Local};
+use egui::{
+    Align, Button, CentralPanel, Color32, Context, Grid, Key, Layout, RichText, ScrollArea,
+    SidePanel, TopBottomPanel, Ui,
+};
+use giga_chess::prelude::Color;
+
+const GAMES_PER_PAGE: i64 = 15;
+
+#[derive(Debug, Default)]
+pub struct PlayerProfileView {
+    chess_board: ChessBoardComponent,
+    history_page: i64,
+    replay: Option<GameReplay>,
+}
+
+impl PlayerProfileView {
+    fn load_profile(&mut self, state: &mut AppState) {
+        self.history_page = 1;
+        self.replay = None;
+        state.load_player_profile(GAMES_PER_PAGE);
+    }
+
+    fn load_history_page(&mut self, state: &mut AppState, page: i64) {
+        self.history_page = page;
+        state.load_game_history(page, GAMES_PER_PAGE);
+    }
+
+    /// Picks up a loaded game record and shows it from the viewed player's side.
+    fn start_loaded_replay(&mut self, state: &mut AppState, username: Option<&str>) {
+        let GameReplayState::Loaded(record) = state.game_replay.get_clone() else {
+            return;
+        };
+        state.game_replay.set(GameReplayState::Idle);
+
+        let perspective = username
+            .and_then(|username| record.summary.get_color(username))
+            .unwrap_or(Color::White);
+        self.replay = Some(GameReplay::new(&state.engine, record, perspective));
+        self.chess_board.clear();
+    }
+
+    fn render_profile(&mut self, ui: &mut Ui, state: &AppState) -> Option<UserProfile> {
+        let profile = match state.player_profile.get_clone() {
+            PlayerProfileState::Idle => return None,
+            PlayerProfileState::Loading => {
+                render_loading(ui, state.api.retry_status());
+                return None;
+            }
+            PlayerProfileState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return None;
+            }
+            PlayerProfileState::Loaded(profile) => profile,
+        };
+
+        ui.heading(RichText::new(&profile.name).size(30.0));
+        ui.add_space(10.0);
+
+        ui.label(RichText::new("Ratings").strong());
+        if profile.ratings.is_empty() {
+            ui.label("No rated games yet.");
+        } else {
+            Grid::new("player_profile_ratings")
+                .num_columns(3)
+                .spacing([20.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for rating in &profile.ratings {
+                        ui.label(rating.category.to_string());
+                        ui.monospace(rating.rating.to_string());
+                        ui.label(format!("{} games", rating.games));
+                        ui.end_row();
+                    }
+                });
+        }
+
+        ui.add_space(10.0);
+        ui.label(RichText::new("Results").strong());
+        let stats = profile.stats;
+        Grid::new("player_profile_stats")
+            .num_columns(2)
+            .spacing([20.0, 4.0])
+            .show(ui, |ui| {
+                for (label, count) in [
+                    ("Wins", stats.wins),
+                    ("Draws", stats.draws),
+                    ("Losses", stats.losses),
+                    ("Total", stats.total()),
+                ] {
+                    ui.label(label);
+                    ui.monospace(count.to_string());
+                    ui.end_row();
+                }
+            });
+
+        Some(profile)
+    }
+
+    fn render_history(&mut self, ui: &mut Ui, state: &mut AppState, username: &str) {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Game History").strong());
+            let is_loading = matches!(state.game_history.get_clone(), GameHistoryState::Loading);
+            if ui
+                .add_enabled(!is_loading, Button::new("Refresh"))
+                .clicked()
+            {
+                self.load_history_page(state, self.history_page);
+            }
+        });
+
+        let history = match state.game_history.get_clone() {
+            GameHistoryState::Idle => return,
+            GameHistoryState::Loading => {
+                render_loading(ui, state.api.retry_status());
+                return;
+            }
+            GameHistoryState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return;
+            }
+            GameHistoryState::Loaded(history) => history,
+        };
+
+        if history.games.is_empty() {
+            ui.label("No finished games yet.");
+        } else {
+            self.render_history_games(ui, state, &history, username);
+        }
+
+        let page_count = (history.pagination.total + GAMES_PER_PAGE - 1) / GAMES_PER_PAGE;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.history_page > 1, Button::new("Previous"))
+                .clicked()
+            {
+                self.load_history_page(state, self.history_page - 1);
+            }
+            ui.label(format!(
+                "Page {} of {}",
+                self.history_page,
+                page_count.max(1)
+            ));
+            if ui
+                .add_enabled(self.history_page < page_count, Button::new("Next"))
+                .clicked()
+            {
+                self.load_history_page(state, self.history_page + 1);
+            }
+        });
+    }
+
+    fn render_history_games(
+        &mut self,
+        ui: &mut Ui,
+        state: &mut AppState,
+        history: &GameHistory,
+        username: &str,
+    ) {
+        let is_replay_loading = matches!(state.game_replay.get_clone(), GameReplayState::Loading);
+        let mut opened_player = None;
+        ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("player_profile_history")
+                .num_columns(5)
+                .spacing([12.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Date");
+                    ui.strong("Opponent");
+                    ui.strong("Time");
+                    ui.strong("Result");
+                    ui.end_row();
+
+                    for game in &history.games {
+                        ui.label(format_date(game.ended_at));
+                        match game.get_opponent(username) {
+                            Some(opponent) => {
+                                let color = game
+                                    .get_color(username)
+                                    .map_or(Color::White, Color::opposite);
+                                if ui
+                                    .link(format!("{} ({color:?})", opponent.name))
+                                    .on_hover_text("Show profile")
+                                    .clicked()
+                                {
+                                    opened_player = Some(opponent.name.clone());
+                                }
+                            }
+                            None => {
+                                ui.label("-");
+                            }
+                        }
+                        ui.label(format_time_control(game.time_control()));
+                        render_outcome(ui, game, username);
+
+                        let is_replayed = self
+                            .replay
+                            .as_ref()
+                            .is_some_and(|replay| replay.record.summary.uuid == game.uuid);
+                        if ui
+                            .add_enabled(!is_replay_loading && !is_replayed, Button::new("Replay"))
+                            .clicked()
+                        {
+                            state.load_game_replay(&game.uuid);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if let Some(username) = opened_player {
+            state.open_player_profile(Some(username));
+        }
+    }
+
+    fn render_replay(&mut self, ui: &mut Ui, state: &mut AppState) {
+        match state.game_replay.get_clone() {
+            GameReplayState::Loading => {
+                render_loading(ui, state.api.retry_status());
+                return;
+            }
+            GameReplayState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return;
+            }
+            GameReplayState::Idle | GameReplayState::Loaded(_) => {}
+        }
+
+        let Some(replay) = &mut self.replay else {
+            ui.centered_and_justified(|ui| ui.label("Select a game to replay it."));
+            return;
+        };
+
+        let summary = &replay.record.summary;
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(format!("{} vs {}", summary.white.name, summary.black.name)).strong(),
+            );
+            ui.label(format!("{}, {}", summary.result, summary.reason));
+        });
+
+        let mut target = None;
+        ui.horizontal(|ui| {
+            let ply = replay.ply();
+            let move_count = replay.move_count();
+            if ui.add_enabled(ply > 0, Button::new("⏮")).clicked() {
+                target = Some(0);
+            }
+            if ui.add_enabled(ply > 0, Button::new("◀")).clicked() {
+                target = Some(ply - 1);
+            }
+            if ui.add_enabled(ply < move_count, Button::new("▶")).clicked() {
+                target = Some(ply + 1);
+            }
+            if ui.add_enabled(ply < move_count, Button::new("⏭")).clicked() {
+                target = Some(move_count);
+            }
+            ui.label(format!("Move {ply} of {move_count}"));
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui.button("Flip board").clicked() {
+                    replay.app_game.perspective = replay.app_game.perspective.opposite();
+                }
+            });
+
+            ui.input(|input| {
+                if input.key_pressed(Key::ArrowLeft) {
+                    target = Some(ply.saturating_sub(1));
+                } else if input.key_pressed(Key::ArrowRight) {
+                    target = Some(ply + 1);
+                }
+            });
+        });
+
+        if let Some(target) = target {
+            replay.go_to(&state.engine, target);
+            self.chess_board.clear();
+        }
+        ui.separator();
+        self.chess_board.render(ui, state, &mut replay.app_game);
+    }
+}
+
+impl View for PlayerProfileView {
+    fn new() -> Self {
+        Self {
+            history_page: 1,
+            ..Default::default()
+        }
+    }
+
+    fn render(&mut self, ctx: &Context, state: &mut AppState) {
+        if !state.is_logged_in() {
+            state.switch_view(ViewID::MainMenu);
+            return;
+        }
+        // A different player was opened, or the view was restored from a previous run
+        if matches!(state.player_profile.get_clone(), PlayerProfileState::Idle) {
+            self.load_profile(state);
+        }
+
+        let username = match state.player_profile.get_clone() {
+            PlayerProfileState::Loaded(profile) => Some(profile.name),
+            _ => None,
+        };
+        self.start_loaded_replay(state, username.as_deref());
+
+        TopBottomPanel::top("player_profile_top_panel").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                let home_response = ui.add(Button::new(RichText::new(" 🏠 ").size(20.0)));
+                if home_response.clicked() {
+                    state.switch_view(ViewID::OnlineHome);
+                }
+
+                ui.label("Player Profile");
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if state.viewed_player.is_some() && ui.button("My Profile").clicked() {
+                        state.open_player_profile(None);
+                    }
+                    if state.api.server_info().supports(Capability::Leaderboards)
+                        && ui.button("Leaderboard").clicked()
+                    {
+                        state.open_leaderboard();
+                    }
+                });
+            });
+        });
+
+        SidePanel::left("player_profile_panel")
+            .resizable(true)
+            .min_width(420.0)
+            .show(ctx, |ui| {
+                if let Some(profile) = self.render_profile(ui, state) {
+                    ui.separator();
+                    self.render_history(ui, state, &profile.name);
+                }
+            });
+
+        CentralPanel::default().show(ctx, |ui| {
+            self.render_replay(ui, state);
+        });
+    }
+}
+
+fn render_outcome(ui: &mut Ui, game: &GameSummary, username: &str) {
+    let Some(outcome) = game.get_outcome(username) else {
+        ui.label(&game.result);
+        return;
+    };
+    let color = match outcome {
+        GameOutcome::Win => Color32::from_rgb(80, 180, 80),
+        GameOutcome::Draw => ui.visuals().text_color(),
+        GameOutcome::Loss => ui.visuals().error_fg_color,
+    };
+    ui.colored_label(color, outcome.to_string())
+        .on_hover_text(&game.reason);
+}
+
+fn format_date(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|date| {
+            date.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
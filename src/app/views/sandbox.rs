@@ -14,9 +14,18 @@ pub struct SandboxView {
     game: Option<AppGame>,
     game_creation_window: SandboxGameCreationWindow,
     game_control_window: GameControlWindow,
+    open_error: Option<String>,
 }
 
 impl SandboxView {
+    fn open_game(&mut self, game: AppGame) {
+        self.open_error = None;
+        self.game = Some(game);
+        self.chess_board.clear();
+        self.game_creation_window.set_open(false);
+        self.game_control_window.set_open(true);
+    }
+
     fn on_home_clicked(&mut self, _ctx: &Context, state: &mut AppState) {
         state.switch_view(ViewID::MainMenu);
     }
@@ -28,12 +37,16 @@ impl View for SandboxView {
     }
 
     fn render(&mut self, ctx: &Context, state: &mut AppState) {
+        if let Some(game) = state.opened_game.take() {
+            self.open_game(game);
+        }
+        if let Some(error) = state.open_error.take() {
+            self.open_error = Some(error);
+        }
+
         let window_event = self.game_creation_window.render(ctx, state);
         if window_event == WindowEvent::CreateSandboxGame {
-            self.game = Some(AppGame::new(&state.engine, PGNMetadata::now()));
-            self.chess_board.clear();
-            self.game_creation_window.set_open(false);
-            self.game_control_window.set_open(true);
+            self.open_game(AppGame::new(&state.engine, PGNMetadata::now()));
         }
 
         // Just for the background color
@@ -56,6 +69,17 @@ impl View for SandboxView {
                 ui.checkbox(&mut game_control_open, "Game Control");
                 self.game_control_window.set_open(game_control_open);
             });
+
+            if let Some(error) = &self.open_error {
+                let mut dismissed = false;
+                ui.horizontal(|ui| {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                    dismissed = ui.button("Dismiss").clicked();
+                });
+                if dismissed {
+                    self.open_error = None;
+                }
+            }
         });
 
         if let Some(game) = &mut self.game {
@@ -0,0 +1,360 @@
+use crate::api::matchmaking::format_time_control;
+use crate::api::server_info::Capability;
+use crate::api::tournaments::{Pairing, Tournament, TournamentStatus, TournamentSummary};
+use crate::app::components::retry_status::render_loading;
+use crate::app::state::tournaments::{TournamentListState, TournamentState};
+use crate::app::state::AppState;
+use crate::app::views::{View, ViewID};
+use chrono::Utc;
+use egui::{
+    Align, Button, CentralPanel, CollapsingHeader, Context, Grid, Layout, RichText, ScrollArea,
+    SidePanel, TopBottomPanel, Ui,
+};
+use std::time::{Duration, Instant};
+
+const TOURNAMENTS_PER_PAGE: i64 = 20;
+const LIST_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// The viewed tournament is polled more often, so new rounds are joined quickly.
+const TOURNAMENT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub struct TournamentsView {
+    page: i64,
+    last_list_poll: Option<Instant>,
+    last_tournament_poll: Option<Instant>,
+}
+
+impl TournamentsView {
+    fn load_page(&mut self, state: &mut AppState, page: i64) {
+        self.page = page;
+        self.last_list_poll = Some(Instant::now());
+        state.load_tournaments(page, TOURNAMENTS_PER_PAGE);
+    }
+
+    fn poll(&mut self, ctx: &Context, state: &mut AppState) {
+        let is_list_due = matches!(state.tournaments.get_clone(), TournamentListState::Idle)
+            || self
+                .last_list_poll
+                .is_none_or(|last_poll| last_poll.elapsed() >= LIST_POLL_INTERVAL);
+        if is_list_due {
+            self.load_page(state, self.page);
+        }
+
+        if state.viewed_tournament.is_none() {
+            return;
+        }
+        let tournament = state.tournament.get_clone();
+        let is_finished = matches!(
+            &tournament,
+            TournamentState::Loaded(tournament)
+                if tournament.summary.status == TournamentStatus::Finished
+        );
+        let is_tournament_due = matches!(tournament, TournamentState::Idle)
+            || !is_finished
+                && self
+                    .last_tournament_poll
+                    .is_none_or(|last_poll| last_poll.elapsed() >= TOURNAMENT_POLL_INTERVAL);
+        if is_tournament_due {
+            self.last_tournament_poll = Some(Instant::now());
+            state.load_tournament();
+        }
+        ctx.request_repaint_after(TOURNAMENT_POLL_INTERVAL);
+    }
+
+    fn render_list(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.heading("Tournaments");
+        ui.separator();
+
+        let list = match state.tournaments.get_clone() {
+            TournamentListState::Idle => return,
+            TournamentListState::Loading => {
+                render_loading(ui, state.api.retry_status());
+                return;
+            }
+            TournamentListState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                if ui.button("Retry").clicked() {
+                    self.load_page(state, self.page);
+                }
+                return;
+            }
+            TournamentListState::Loaded(list) => list,
+        };
+        if list.tournaments.is_empty() {
+            ui.label("No tournaments are scheduled.");
+        }
+
+        ScrollArea::vertical()
+            .auto_shrink([false, true])
+            .max_height(ui.available_height() - 30.0)
+            .show(ui, |ui| {
+                for summary in &list.tournaments {
+                    let is_selected = state.viewed_tournament.as_ref() == Some(&summary.uuid);
+                    let title = match summary.joined {
+                        true => format!("✔ {}", summary.name),
+                        false => summary.name.clone(),
+                    };
+                    if ui
+                        .selectable_label(is_selected, RichText::new(title).strong())
+                        .clicked()
+                        && !is_selected
+                    {
+                        self.last_tournament_poll = None;
+                        state.select_tournament(summary.uuid.clone());
+                    }
+                    ui.label(
+                        RichText::new(format!(
+                            "{}, {}, {}",
+                            summary.format,
+                            format_time_control(summary.time_control()),
+                            summary.status
+                        ))
+                        .small(),
+                    );
+                    ui.add_space(4.0);
+                }
+            });
+
+        let page_count = (list.pagination.total + TOURNAMENTS_PER_PAGE - 1) / TOURNAMENTS_PER_PAGE;
+        if page_count > 1 {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(self.page > 1, Button::new("Previous"))
+                    .clicked()
+                {
+                    self.load_page(state, self.page - 1);
+                }
+                ui.label(format!("{} / {}", self.page, page_count));
+                if ui
+                    .add_enabled(self.page < page_count, Button::new("Next"))
+                    .clicked()
+                {
+                    self.load_page(state, self.page + 1);
+                }
+            });
+        }
+    }
+
+    fn render_header(&mut self, ui: &mut Ui, state: &mut AppState, summary: &TournamentSummary) {
+        ui.heading(&summary.name);
+        ui.label(format!(
+            "{}, {}, {} players",
+            summary.format,
+            format_time_control(summary.time_control()),
+            summary.player_count
+        ));
+
+        let progress = match (summary.status, summary.rounds) {
+            (TournamentStatus::Upcoming, _) => summary
+                .starts_at
+                .map(|starts_at| format!("Starts in {}", format_remaining(starts_at)))
+                .unwrap_or_else(|| "Upcoming".to_string()),
+            (TournamentStatus::Running, Some(rounds)) => {
+                format!("Round {} of {rounds}", summary.current_round)
+            }
+            (TournamentStatus::Running, None) => summary
+                .ends_at
+                .map(|ends_at| format!("Ends in {}", format_remaining(ends_at)))
+                .unwrap_or_else(|| "Running".to_string()),
+            (TournamentStatus::Finished, _) => "Finished".to_string(),
+        };
+        ui.label(progress);
+
+        ui.horizontal(|ui| {
+            if summary.joined {
+                if summary.status != TournamentStatus::Finished && ui.button("Withdraw").clicked() {
+                    state.withdraw_from_tournament(summary.uuid.clone());
+                }
+            } else if summary.can_join() && ui.button("Join").clicked() {
+                state.join_tournament(summary.uuid.clone());
+            }
+        });
+        if let Some(error) = state.tournament_error.get_clone() {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        if summary.joined && summary.status != TournamentStatus::Finished {
+            ui.label("Keep this page open, your board opens as soon as your next game is paired.");
+        }
+    }
+
+    fn render_standings(&mut self, ui: &mut Ui, state: &mut AppState, tournament: &Tournament) {
+        ui.heading("Standings");
+        if tournament.standings.is_empty() {
+            ui.label("Nobody has joined yet.");
+            return;
+        }
+
+        let username = state.get_username().unwrap_or_default();
+        let highlight_color = ui.visuals().selection.stroke.color;
+        Grid::new("tournament_standings")
+            .num_columns(4)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Rank");
+                ui.strong("Player");
+                ui.strong("Points");
+                ui.strong("Games");
+                ui.end_row();
+
+                for standing in &tournament.standings {
+                    let highlight = |text: String| {
+                        let text = RichText::new(text);
+                        match standing.name == username {
+                            true => text.strong().color(highlight_color),
+                            false => text,
+                        }
+                    };
+                    ui.label(highlight(format!("#{}", standing.rank)));
+                    match standing.withdrawn {
+                        true => ui.label(highlight(format!("{} (withdrawn)", standing.name))),
+                        false => ui.label(highlight(standing.name.clone())),
+                    };
+                    ui.label(highlight(standing.points.to_string()));
+                    ui.label(highlight(standing.games.to_string()));
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn render_pairings(&mut self, ui: &mut Ui, state: &mut AppState, tournament: &Tournament) {
+        ui.heading("Pairings");
+        if tournament.pairings.is_empty() {
+            ui.label("The first round has not been paired yet.");
+            return;
+        }
+
+        let username = state.get_username().unwrap_or_default();
+        let can_watch = state.online_game.is_none() && state.matchmaking.lock().is_idle();
+        for round in (1..=tournament.summary.current_round).rev() {
+            CollapsingHeader::new(format!("Round {round}"))
+                .default_open(round == tournament.summary.current_round)
+                .show(ui, |ui| {
+                    Grid::new(("tournament_pairings", round))
+                        .num_columns(4)
+                        .spacing([20.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for pairing in tournament.get_round(round) {
+                                render_pairing(ui, state, pairing, &username, can_watch);
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+    }
+
+    fn render_tournament(&mut self, ui: &mut Ui, state: &mut AppState) {
+        let tournament = match state.tournament.get_clone() {
+            TournamentState::Idle => return,
+            TournamentState::Loading => {
+                render_loading(ui, state.api.retry_status());
+                return;
+            }
+            TournamentState::Error(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                if ui.button("Retry").clicked() {
+                    state.load_tournament();
+                }
+                return;
+            }
+            TournamentState::Loaded(tournament) => tournament,
+        };
+
+        self.render_header(ui, state, &tournament.summary);
+        ui.separator();
+        ScrollArea::vertical().show(ui, |ui| {
+            self.render_standings(ui, state, &tournament);
+            ui.separator();
+            self.render_pairings(ui, state, &tournament);
+        });
+    }
+}
+
+fn render_pairing(
+    ui: &mut Ui,
+    state: &mut AppState,
+    pairing: &Pairing,
+    username: &str,
+    can_watch: bool,
+) {
+    ui.label(&pairing.white.name);
+    let Some(black) = &pairing.black else {
+        ui.label("Bye");
+        ui.label("");
+        return;
+    };
+    ui.label(&black.name);
+    ui.label(pairing.result.as_deref().unwrap_or("Playing"));
+
+    if pairing.is_running()
+        && !pairing.includes(username)
+        && let Some(room) = pairing.to_room_info()
+        && ui.add_enabled(can_watch, Button::new("Watch")).clicked()
+    {
+        let ctx = ui.ctx().clone();
+        state.start_online_game(room, move || ctx.request_repaint());
+    }
+}
+
+/// The time until the given unix timestamp in seconds.
+fn format_remaining(timestamp: i64) -> String {
+    let secs = (timestamp - Utc::now().timestamp()).max(0);
+    match secs {
+        86400.. => format!("{}d {}h", secs / 86400, secs % 86400 / 3600),
+        3600.. => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+        60.. => format!("{}m", secs / 60),
+        _ => "less than a minute".to_string(),
+    }
+}
+
+impl View for TournamentsView {
+    fn new() -> Self {
+        Self {
+            page: 1,
+            last_list_poll: None,
+            last_tournament_poll: None,
+        }
+    }
+
+    fn render(&mut self, ctx: &Context, state: &mut AppState) {
+        if !state.is_logged_in() {
+            state.switch_view(ViewID::MainMenu);
+            return;
+        }
+        self.poll(ctx, state);
+
+        TopBottomPanel::top("tournaments_top_panel").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                let home_response = ui.add(Button::new(RichText::new(" 🏠 ").size(20.0)));
+                if home_response.clicked() {
+                    state.switch_view(ViewID::OnlineHome);
+                }
+
+                ui.label("Tournaments");
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if state.api.server_info().supports(Capability::Leaderboards)
+                        && ui.button("Leaderboard").clicked()
+                    {
+                        state.open_leaderboard();
+                    }
+                });
+            });
+        });
+
+        SidePanel::left("tournaments_list_panel")
+            .default_width(260.0)
+            .show(ctx, |ui| {
+                self.render_list(ui, state);
+            });
+
+        CentralPanel::default().show(ctx, |ui| {
+            if state.viewed_tournament.is_none() {
+                ui.label("Select a tournament to see its standings and pairings.");
+                return;
+            }
+            self.render_tournament(ui, state);
+        });
+    }
+}
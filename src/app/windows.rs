@@ -2,6 +2,7 @@ use crate::app::state::AppState;
 use egui::{Context, Id, Ui, WidgetText};
 
 pub mod game_control;
+pub mod log;
 pub mod sandbox_game_creation;
 
 pub trait ToggleableWindow {
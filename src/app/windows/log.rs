@@ -0,0 +1,132 @@
+use crate::app::state::AppState;
+use crate::app::windows::{ToggleableWindow, WindowEvent};
+use crate::logging;
+use crate::logging::LogRecord;
+use egui::{Color32, ComboBox, Grid, Id, ScrollArea, Ui, WidgetText};
+use log::{Level, LevelFilter};
+
+const LEVEL_FILTERS: [LevelFilter; 6] = [
+    LevelFilter::Off,
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+    LevelFilter::Trace,
+];
+
+#[derive(Debug)]
+pub struct LogWindow {
+    open: bool,
+    display_level: LevelFilter,
+}
+
+impl Default for LogWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            display_level: LevelFilter::Trace,
+        }
+    }
+}
+
+impl LogWindow {
+    fn render_controls(&mut self, ui: &mut Ui, state: &mut AppState) {
+        ui.horizontal(|ui| {
+            ui.label("Show:");
+            ComboBox::from_id_salt("log_display_level_combo")
+                .selected_text(self.display_level.to_string())
+                .show_ui(ui, |ui| {
+                    for level in LEVEL_FILTERS.into_iter().skip(1) {
+                        ui.selectable_value(&mut self.display_level, level, level.to_string());
+                    }
+                });
+
+            ui.label("Record:");
+            let mut record_level = logging::get_level();
+            ComboBox::from_id_salt("log_record_level_combo")
+                .selected_text(record_level.to_string())
+                .show_ui(ui, |ui| {
+                    for level in LEVEL_FILTERS {
+                        ui.selectable_value(&mut record_level, level, level.to_string());
+                    }
+                });
+            if record_level != logging::get_level() {
+                logging::set_level(record_level);
+                state.log_level = Some(record_level);
+                state.requested_save = true;
+            }
+
+            if ui.button("Clear").clicked() {
+                logging::clear_recent_records();
+            }
+        });
+
+        ui.label(format!(
+            "Log files: {}",
+            logging::get_log_dir().to_string_lossy()
+        ));
+    }
+
+    fn render_records(&self, ui: &mut Ui) {
+        let records: Vec<LogRecord> = logging::get_recent_records()
+            .into_iter()
+            .filter(|record| record.level <= self.display_level)
+            .collect();
+
+        ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                Grid::new("log_records")
+                    .num_columns(4)
+                    .spacing([10.0, 2.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for record in records {
+                            ui.label(&record.time);
+                            ui.colored_label(
+                                get_level_color(ui, record.level),
+                                record.level.as_str(),
+                            );
+                            ui.label(&record.target);
+                            ui.label(&record.message);
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
+
+fn get_level_color(ui: &Ui, level: Level) -> Color32 {
+    match level {
+        Level::Error => ui.visuals().error_fg_color,
+        Level::Warn => ui.visuals().warn_fg_color,
+        Level::Info => ui.visuals().text_color(),
+        Level::Debug | Level::Trace => ui.visuals().weak_text_color(),
+    }
+}
+
+impl ToggleableWindow for LogWindow {
+    fn id(&self) -> Id {
+        Id::new("log_window")
+    }
+
+    fn title(&self) -> impl Into<WidgetText> {
+        "Logs"
+    }
+
+    fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn render_content(&mut self, ui: &mut Ui, state: &mut AppState) -> WindowEvent {
+        self.render_controls(ui, state);
+        ui.separator();
+        self.render_records(ui);
+        WindowEvent::None
+    }
+}
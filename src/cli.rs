@@ -0,0 +1,48 @@
+use crate::persistence::profile::is_valid_profile_name;
+use clap::Parser;
+use log::LevelFilter;
+use std::path::PathBuf;
+use url::Url;
+
+/// A desktop chess client for giga-chess multiplayer servers.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct CliArgs {
+    /// Multiplayer server to use, replacing the one saved in the profile.
+    #[arg(long, value_name = "URL", value_parser = parse_server_url)]
+    pub server_url: Option<String>,
+    /// Profile to load, it is created if it does not exist yet.
+    #[arg(long, value_name = "NAME", value_parser = parse_profile_name)]
+    pub profile: Option<String>,
+    /// Directory to store profiles in instead of the platform's data directory.
+    #[arg(long, value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+    /// PGN or FEN file to open in a new sandbox game.
+    #[arg(long, value_name = "FILE")]
+    pub open: Option<PathBuf>,
+    /// Start with a fresh state instead of restoring the profile's save.
+    #[arg(long)]
+    pub reset_state: bool,
+    /// Start an in-memory development server and connect to it instead of a real one.
+    #[cfg(feature = "mock-server")]
+    #[arg(long, conflicts_with = "server_url")]
+    pub mock_server: bool,
+    /// Verbosity of the log (off, error, warn, info, debug or trace), replacing the one chosen in
+    /// the log window. Defaults to info.
+    #[arg(long, value_name = "LEVEL")]
+    pub log_level: Option<LevelFilter>,
+}
+
+fn parse_server_url(url: &str) -> Result<String, String> {
+    Url::parse(url)
+        .map(|_| url.to_string())
+        .map_err(|err| err.to_string())
+}
+
+fn parse_profile_name(name: &str) -> Result<String, String> {
+    if is_valid_profile_name(name) {
+        Ok(name.to_string())
+    } else {
+        Err("profile names may only contain alphanumeric characters, spaces, dashes and underscores (max. 32 characters)".to_string())
+    }
+}
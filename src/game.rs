@@ -1,27 +1,44 @@
+use crate::game::import::{game_from_file, GameImportError};
 use giga_chess::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
 
+pub mod import;
+pub mod uci;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AppGame {
     pub game: Game,
     pub promotion_piece: Piece,
     pub perspective: Color,
     pub played_color: Option<Color>,
+    /// Neither side can move locally, set for replays of finished games and spectated live games.
+    #[serde(default)]
+    pub read_only: bool,
     auto_adjust_perspective: bool,
 }
 
 impl AppGame {
     pub fn new(engine: &Arc<Engine>, pgn: PGNMetadata) -> Self {
+        Self::from_game(Game::new(engine, pgn))
+    }
+
+    pub fn from_game(game: Game) -> Self {
         Self {
-            game: Game::new(engine, pgn),
+            game,
             promotion_piece: Piece::Queen,
             perspective: Color::White,
             played_color: None,
+            read_only: false,
             auto_adjust_perspective: false,
         }
     }
 
+    pub fn from_file(engine: &Arc<Engine>, path: &Path) -> Result<Self, GameImportError> {
+        Ok(Self::from_game(game_from_file(engine, path)?))
+    }
+
     pub fn try_play_move(&mut self, engine: &Arc<Engine>, from: Square, to: Square) -> bool {
         let (moving_piece, moving_color) =
             self.game.board().get_piece_at(from.get_value()).unwrap();
@@ -45,7 +62,7 @@ impl AppGame {
     }
 
     pub fn can_color_move(&self, color: Color) -> bool {
-        if self.game.status() != GameStatus::Running {
+        if self.read_only || self.game.status() != GameStatus::Running {
             return false;
         }
         Some(color) == self.played_color || self.played_color.is_none()
@@ -0,0 +1,155 @@
+use giga_chess::prelude::*;
+use std::path::Path;
+use std::sync::Arc;
+use thiserror::Error;
+
+const GAME_RESULTS: [&str; 5] = ["1-0", "0-1", "1/2-1/2", "½–½", "*"];
+
+#[derive(Debug, Error)]
+pub enum GameImportError {
+    #[error("Invalid FEN: {0}")]
+    InvalidFen(String),
+    #[error("Illegal or unknown move: {0}")]
+    IllegalMove(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Loads a game from a `.pgn` or `.fen` file, guessing the format from the content for other extensions.
+pub fn game_from_file(engine: &Arc<Engine>, path: &Path) -> Result<Game, GameImportError> {
+    let content = std::fs::read_to_string(path)?;
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+
+    let is_fen = match extension.as_deref() {
+        Some("fen") => true,
+        Some("pgn") => false,
+        _ => content.trim().lines().count() == 1 && content.matches('/').count() == 7,
+    };
+
+    if is_fen {
+        game_from_fen(engine, &content)
+    } else {
+        game_from_pgn(engine, &content)
+    }
+}
+
+pub fn game_from_fen(engine: &Arc<Engine>, fen: &str) -> Result<Game, GameImportError> {
+    Game::from_fen_string(engine, fen.trim())
+        .map_err(|err| GameImportError::InvalidFen(err.to_string()))
+}
+
+/// Parses a single PGN game, ignoring comments, variations and annotations.
+pub fn game_from_pgn(engine: &Arc<Engine>, pgn: &str) -> Result<Game, GameImportError> {
+    let mut metadata = PGNMetadata::now();
+    let mut fen = None;
+    let mut movetext = String::new();
+
+    for line in pgn.lines() {
+        let line = line.trim();
+        if let Some((name, value)) = parse_tag(line) {
+            metadata = match name {
+                "Event" => metadata.event(value),
+                "Site" => metadata.site(value),
+                "Date" => metadata.date(value),
+                "Round" => metadata.round(value),
+                "White" => metadata.white(value),
+                "Black" => metadata.black(value),
+                _ => metadata,
+            };
+            if name == "FEN" {
+                fen = Some(value.to_string());
+            }
+        } else if !line.starts_with('[') {
+            movetext.push_str(line);
+            movetext.push('\n');
+        }
+    }
+
+    let mut game = match fen {
+        Some(fen) => game_from_fen(engine, &fen)?,
+        None => Game::new(engine, PGNMetadata::default()),
+    };
+    game.set_pgn_meta_data(metadata);
+
+    for token in strip_movetext(&movetext).split_whitespace() {
+        let Some(san) = normalize_san(token) else {
+            continue;
+        };
+
+        let chess_move = game
+            .legal_moves_algebraic(engine)
+            .into_iter()
+            .find(|(notation, _)| normalize_san(notation).as_deref() == Some(san.as_str()))
+            .map(|(_, chess_move)| chess_move)
+            .ok_or_else(|| GameImportError::IllegalMove(token.to_string()))?;
+
+        game.play_move(engine, chess_move);
+    }
+
+    Ok(game)
+}
+
+fn parse_tag(line: &str) -> Option<(&str, &str)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(' ')?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((name, value))
+}
+
+/// Removes comments, variations and numeric annotation glyphs from the movetext.
+fn strip_movetext(movetext: &str) -> String {
+    let mut result = String::with_capacity(movetext.len());
+    let mut variation_depth = 0;
+    let mut in_brace_comment = false;
+    let mut in_line_comment = false;
+
+    for c in movetext.chars() {
+        if in_line_comment {
+            in_line_comment = c != '\n';
+            continue;
+        }
+        if in_brace_comment {
+            in_brace_comment = c != '}';
+            continue;
+        }
+
+        match c {
+            '{' => in_brace_comment = true,
+            ';' => in_line_comment = true,
+            '(' => variation_depth += 1,
+            ')' => variation_depth -= 1,
+            _ if variation_depth > 0 => {}
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Reduces a SAN token to a comparable form, returns None for move numbers, results and annotations.
+fn normalize_san(token: &str) -> Option<String> {
+    if GAME_RESULTS.contains(&token) || token.starts_with('$') {
+        return None;
+    }
+
+    let without_number = match token.split_once('.') {
+        Some((number, rest)) if number.chars().all(|c| c.is_ascii_digit()) => {
+            rest.trim_start_matches('.')
+        }
+        _ => token,
+    };
+    // Castling is sometimes written with zeros, only whole tokens are mapped
+    let san = match without_number.trim_end_matches(['+', '#', '!', '?']) {
+        "0-0" => "O-O",
+        "0-0-0" => "O-O-O",
+        san => san,
+    };
+
+    if san.is_empty() {
+        None
+    } else {
+        Some(san.to_string())
+    }
+}
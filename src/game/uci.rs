@@ -0,0 +1,42 @@
+use giga_chess::prelude::*;
+
+/// Formats a move in UCI long algebraic notation, e.g. `e2e4` or `e7e8q`.
+pub fn move_to_uci(chess_move: ChessMove) -> String {
+    let from = Square::new(chess_move.get_from())
+        .to_string()
+        .to_lowercase();
+    let to = Square::new(chess_move.get_to()).to_string().to_lowercase();
+    match chess_move.get_type().promotion_piece() {
+        Some(piece) => format!("{from}{to}{}", piece.get_char().to_ascii_lowercase()),
+        None => format!("{from}{to}"),
+    }
+}
+
+/// Finds the legal move matching the given UCI notation in the current position.
+pub fn find_uci_move(game: &Game, uci: &str) -> Option<ChessMove> {
+    let uci = uci.trim();
+    if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+        return None;
+    }
+
+    let from = parse_square(&uci[0..2])?;
+    let to = parse_square(&uci[2..4])?;
+    let promotion = match uci[4..].chars().next() {
+        Some(c) => Some(Piece::try_from(c.to_ascii_uppercase()).ok()?),
+        None => None,
+    };
+    game.find_legal_move(from, to, promotion)
+}
+
+fn parse_square(notation: &str) -> Option<Square> {
+    let mut chars = notation.chars();
+    let file = chars.next()?.to_ascii_lowercase();
+    let rank = chars.next()?;
+    if !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+        return None;
+    }
+    Some(Square::from_file_rank(
+        file as u8 - b'a' + 1,
+        rank as u8 - b'0',
+    ))
+}
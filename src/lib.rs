@@ -0,0 +1,9 @@
+pub mod api;
+pub mod app;
+pub mod cli;
+pub mod game;
+pub mod logging;
+#[cfg(feature = "mock-server")]
+pub mod mock_server;
+pub mod persistence;
+pub mod types;
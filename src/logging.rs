@@ -0,0 +1,187 @@
+use crate::persistence;
+use chrono::Local;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+const LOG_FILE_NAME: &str = "giga-chess";
+const MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
+const MAX_LOG_FILES: usize = 5;
+const MAX_RECENT_RECORDS: usize = 1000;
+
+static LOGGER: OnceLock<AppLogger> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub time: String,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+#[derive(Debug)]
+struct LogFile {
+    file: File,
+    size: u64,
+}
+
+/// Writes records to stderr, a rotating log file and an in-memory buffer for the log window.
+#[derive(Debug)]
+struct AppLogger {
+    directory: PathBuf,
+    file: Mutex<Option<LogFile>>,
+    recent_records: Mutex<VecDeque<LogRecord>>,
+}
+
+impl AppLogger {
+    fn new(directory: PathBuf) -> Self {
+        let file = open_log_file(&directory);
+        Self {
+            directory,
+            file: Mutex::new(file),
+            recent_records: Mutex::new(VecDeque::with_capacity(MAX_RECENT_RECORDS)),
+        }
+    }
+
+    fn write_to_file(&self, line: &str) {
+        let mut file = self.file.lock().unwrap();
+        let needs_rotation = file
+            .as_ref()
+            .is_some_and(|log_file| log_file.size >= MAX_LOG_FILE_SIZE);
+        if needs_rotation {
+            *file = None;
+            rotate_log_files(&self.directory);
+            *file = open_log_file(&self.directory);
+        }
+
+        if let Some(log_file) = file.as_mut()
+            && writeln!(log_file.file, "{line}").is_ok()
+        {
+            log_file.size += line.len() as u64 + 1;
+        }
+    }
+
+    fn push_recent(&self, record: LogRecord) {
+        let mut records = self.recent_records.lock().unwrap();
+        if records.len() >= MAX_RECENT_RECORDS {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Dependencies are very chatty below warn, only our own crate honours the verbosity setting
+        let max_level = if metadata.target().starts_with(env!("CARGO_CRATE_NAME")) {
+            log::max_level()
+        } else {
+            log::max_level().min(LevelFilter::Warn)
+        };
+        metadata.level() <= max_level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let record = LogRecord {
+            time: Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+
+        let line = format!(
+            "{} {:<5} [{}] {}",
+            record.time, record.level, record.target, record.message
+        );
+        eprintln!("{line}");
+        self.write_to_file(&line);
+        self.push_recent(record);
+    }
+
+    fn flush(&self) {
+        if let Some(log_file) = self.file.lock().unwrap().as_mut() {
+            let _ = log_file.file.flush();
+        }
+    }
+}
+
+pub fn get_log_dir() -> PathBuf {
+    persistence::get_data_dir().join("logs")
+}
+
+fn get_log_file_path(directory: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        directory.join(format!("{LOG_FILE_NAME}.log"))
+    } else {
+        directory.join(format!("{LOG_FILE_NAME}.{index}.log"))
+    }
+}
+
+fn open_log_file(directory: &Path) -> Option<LogFile> {
+    std::fs::create_dir_all(directory).ok()?;
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_log_file_path(directory, 0))
+        .ok()?;
+    let size = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+    Some(LogFile { file, size })
+}
+
+/// Shifts every log file up by one index, dropping the oldest one.
+fn rotate_log_files(directory: &Path) {
+    let _ = std::fs::remove_file(get_log_file_path(directory, MAX_LOG_FILES - 1));
+    for index in (0..MAX_LOG_FILES - 1).rev() {
+        let _ = std::fs::rename(
+            get_log_file_path(directory, index),
+            get_log_file_path(directory, index + 1),
+        );
+    }
+}
+
+/// Installs the logger, has to be called after the data directory override was applied.
+pub fn init(level: LevelFilter) {
+    let directory = get_log_dir();
+    rotate_log_files(&directory);
+
+    let logger = LOGGER.get_or_init(|| AppLogger::new(directory));
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+pub fn get_level() -> LevelFilter {
+    log::max_level()
+}
+
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
+pub fn get_recent_records() -> Vec<LogRecord> {
+    LOGGER
+        .get()
+        .map(|logger| {
+            logger
+                .recent_records
+                .lock()
+                .unwrap()
+                .iter()
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn clear_recent_records() {
+    if let Some(logger) = LOGGER.get() {
+        logger.recent_records.lock().unwrap().clear();
+    }
+}
@@ -1,19 +1,23 @@
-use crate::app::GigaChessApp;
-
-mod api;
-mod app;
-mod game;
-mod persistence;
-mod types;
+use clap::Parser;
+use giga_chess_desktop::app::GigaChessApp;
+use giga_chess_desktop::cli::CliArgs;
+use giga_chess_desktop::{logging, persistence};
+use log::LevelFilter;
 
 fn main() {
+    let args = CliArgs::parse();
+    if let Some(data_dir) = &args.data_dir {
+        persistence::set_data_dir_override(data_dir.clone());
+    }
+    logging::init(args.log_level.unwrap_or(LevelFilter::Info));
+
     let native_options = eframe::NativeOptions::default();
     eframe::run_native(
         "Giga Chess",
         native_options,
         Box::new(|cc| {
             egui_extras::install_image_loaders(&cc.egui_ctx);
-            Ok(Box::new(GigaChessApp::new(cc)))
+            Ok(Box::new(GigaChessApp::new(cc, args)))
         }),
     )
     .expect("Failed to run egui application.");
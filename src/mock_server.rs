@@ -0,0 +1,54 @@
+use crate::mock_server::state::MockState;
+use log::{error, info};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Runtime;
+
+pub mod routes;
+pub mod state;
+
+/// An in-memory multiplayer server for development and tests, listening on a random local port.
+/// It implements just enough of the real server to play games and shuts down once dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    url: String,
+    state: Arc<Mutex<MockState>>,
+    _runtime: Runtime,
+}
+
+impl MockServer {
+    pub fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let url = format!("http://{}", listener.local_addr()?);
+
+        let state = Arc::new(Mutex::new(MockState::default()));
+        let router = routes::router(state.clone());
+        let runtime = Runtime::new()?;
+        runtime.spawn(async move {
+            let result = match tokio::net::TcpListener::from_std(listener) {
+                Ok(listener) => axum::serve(listener, router).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                error!("Mock server stopped: {err}");
+            }
+        });
+
+        info!("Started mock server at {url}");
+        Ok(Self {
+            url,
+            state,
+            _runtime: runtime,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Gives tests direct access to the server state, e.g. to revoke tokens.
+    pub fn state(&self) -> &Arc<Mutex<MockState>> {
+        &self.state
+    }
+}
@@ -0,0 +1,910 @@
+use crate::api::account::{AccountInfo, ChangePasswordBody, ChangeUsernameBody, DeleteAccountBody};
+use crate::api::correspondence::{CorrespondenceBody, CorrespondenceGameList};
+use crate::api::friends::{Challenge, ChallengeBody, ChallengeList, FriendList, UsernameBody};
+use crate::api::invite_codes::{InviteCode, InviteCodeBody, InviteCodeList};
+use crate::api::leaderboard::{Leaderboard, LeaderboardQuery};
+use crate::api::live::{ClientMessage, PollResponse, ServerMessage};
+use crate::api::matchmaking::{CreateGameBody, QueueBody, QueueStatusResponse};
+use crate::api::player_profile::{GameHistory, GameRecord, UserProfile};
+use crate::api::server_info::{Capability, ServerInfo, API_VERSION};
+use crate::api::server_profile::HealthResponse;
+use crate::api::session::{LogoutBody, RefreshBody, RememberedLoginResponse};
+use crate::api::spectating::{LiveGame, LiveGameList};
+use crate::api::tournaments::{Tournament, TournamentList};
+use crate::mock_server::state::{
+    AccountError, JoinError, MockRoom, MockState, RoomError, SocialError, TournamentError,
+};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use giga_chess_api_types::body::login::LoginBody;
+use giga_chess_api_types::body::register::RegisterBody;
+use giga_chess_api_types::body::uuid::UuidBody;
+use giga_chess_api_types::general::pagination::Pagination;
+use giga_chess_api_types::response::login::LoginResponse;
+use giga_chess_api_types::response::message::MessageResponse;
+use giga_chess_api_types::response::room_info::PrivateRoomInfo;
+use giga_chess_api_types::response::room_list::PublicRoomList;
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+pub const TOKEN_EXPIRY_SECS: u64 = 3600;
+pub const MOCK_SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+const POLL_TIMEOUT: Duration = Duration::from_secs(25);
+const CAPABILITIES: [Capability; 13] = [
+    Capability::OpenGames,
+    Capability::Queue,
+    Capability::LiveWebsocket,
+    Capability::LivePolling,
+    Capability::Profiles,
+    Capability::Friends,
+    Capability::Spectating,
+    Capability::Leaderboards,
+    Capability::Correspondence,
+    Capability::Tournaments,
+    Capability::InviteCodes,
+    Capability::AccountManagement,
+    Capability::RefreshTokens,
+];
+
+type SharedState = Arc<Mutex<MockState>>;
+type MockResult<T> = Result<T, MockError>;
+
+#[derive(Debug)]
+pub enum MockError {
+    BadRequest(String),
+    Validation(ValidationErrors),
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict(String),
+    RateLimited(Duration),
+}
+
+impl IntoResponse for MockError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Validation(errors) => {
+                return (StatusCode::BAD_REQUEST, Json(errors)).into_response()
+            }
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, "Invalid token".to_string()),
+            Self::Forbidden => (StatusCode::FORBIDDEN, "Admins only".to_string()),
+            Self::NotFound => (StatusCode::NOT_FOUND, "Not found".to_string()),
+            Self::Conflict(message) => (StatusCode::CONFLICT, message),
+            Self::RateLimited(retry_after) => {
+                let retry_after = (retry_after.as_secs() + 1).to_string();
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(RETRY_AFTER, retry_after)],
+                    "Too many messages, slow down".to_string(),
+                )
+                    .into_response();
+            }
+        }
+        .into_response()
+    }
+}
+
+pub fn router(state: SharedState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/version", get(version))
+        .route("/register", post(register))
+        .route("/login", post(login))
+        .route("/login/remember", post(login_remembered))
+        .route("/refresh", post(refresh))
+        .route("/logout", post(logout))
+        .route("/ping", post(ping))
+        .route("/account", get(get_account).delete(delete_account))
+        .route("/account/password", post(change_password))
+        .route("/account/username", post(change_username))
+        .route(
+            "/invite-codes",
+            get(list_invite_codes).post(create_invite_code),
+        )
+        .route("/invite-codes/{code}", delete(revoke_invite_code))
+        .route("/room", post(create_room))
+        .route("/room/public", get(list_public_rooms))
+        .route("/room/live", get(list_live_games))
+        .route("/room/join", post(join_room))
+        .route("/room/leave", post(leave_room))
+        .route("/room/{uuid}", get(get_room))
+        .route("/room/{uuid}/live", get(live_socket).post(live_send))
+        .route("/room/{uuid}/live/poll", get(live_poll))
+        .route("/user/{name}", get(get_user_profile))
+        .route("/user/{name}/games", get(get_game_history))
+        .route("/game/{uuid}", get(get_game_record))
+        .route("/leaderboard", get(get_leaderboard))
+        .route(
+            "/correspondence",
+            get(list_correspondence_games).post(create_correspondence_game),
+        )
+        .route("/tournaments", get(list_tournaments))
+        .route("/tournaments/{uuid}", get(get_tournament))
+        .route("/tournaments/{uuid}/join", post(join_tournament))
+        .route(
+            "/tournaments/{uuid}/withdraw",
+            post(withdraw_from_tournament),
+        )
+        .route("/friends", get(get_friends).post(add_friend))
+        .route("/friends/{name}", delete(remove_friend))
+        .route("/challenges", get(get_challenges).post(send_challenge))
+        .route("/challenges/{uuid}/accept", post(accept_challenge))
+        .route("/challenges/{uuid}/decline", post(decline_challenge))
+        .route(
+            "/queue",
+            get(get_queue).post(join_queue).delete(leave_queue),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            inject_failures,
+        ))
+        .with_state(state)
+}
+
+/// Applies the failures and delays configured by tests before handling a request.
+async fn inject_failures(
+    State(state): State<SharedState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (failure, delay) = {
+        let mut state = state.lock().unwrap();
+        (state.injected_failures.pop_front(), state.response_delay)
+    };
+    if let Some(delay) = delay {
+        tokio::time::sleep(delay).await;
+    }
+
+    match failure {
+        Some(status) => (status, [(RETRY_AFTER, "0")], "Injected failure").into_response(),
+        None => next.run(request).await,
+    }
+}
+
+fn get_bearer_token(headers: &HeaderMap) -> MockResult<&str> {
+    headers
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(MockError::Unauthorized)
+}
+
+fn authenticate(state: &SharedState, headers: &HeaderMap) -> MockResult<String> {
+    let token = get_bearer_token(headers)?;
+    state
+        .lock()
+        .unwrap()
+        .authenticate(token)
+        .ok_or(MockError::Unauthorized)
+}
+
+fn login_response(token: &str) -> LoginResponse {
+    LoginResponse::new(token, "Bearer", TOKEN_EXPIRY_SECS)
+}
+
+fn message_response(message: &str) -> MessageResponse {
+    MessageResponse::new(message)
+}
+
+async fn health() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        version: Some(MOCK_SERVER_VERSION.to_string()),
+    })
+}
+
+async fn version() -> Json<ServerInfo> {
+    Json(ServerInfo {
+        api_version: API_VERSION.to_string(),
+        version: Some(MOCK_SERVER_VERSION.to_string()),
+        capabilities: CAPABILITIES.to_vec(),
+    })
+}
+
+async fn register(
+    State(state): State<SharedState>,
+    Json(body): Json<RegisterBody>,
+) -> MockResult<LoginResponse> {
+    body.validate().map_err(MockError::Validation)?;
+    if body.invite_code.is_empty() {
+        return Err(MockError::BadRequest("Invalid invite code".to_string()));
+    }
+
+    let mut state = state.lock().unwrap();
+    state
+        .check_invite_code(&body.invite_code)
+        .map_err(MockError::BadRequest)?;
+    if !state.register(&body.username, &body.password) {
+        return Err(MockError::Conflict("Username already taken".to_string()));
+    }
+    state.redeem_invite_code(&body.invite_code);
+    let token = state
+        .login(&body.username, &body.password)
+        .ok_or(MockError::Unauthorized)?;
+    Ok(login_response(&token))
+}
+
+async fn login(
+    State(state): State<SharedState>,
+    Json(body): Json<LoginBody>,
+) -> MockResult<LoginResponse> {
+    let token = state
+        .lock()
+        .unwrap()
+        .login(&body.username, &body.password)
+        .ok_or(MockError::Unauthorized)?;
+    Ok(login_response(&token))
+}
+
+async fn login_remembered(
+    State(state): State<SharedState>,
+    Json(body): Json<LoginBody>,
+) -> MockResult<Json<RememberedLoginResponse>> {
+    let mut state = state.lock().unwrap();
+    let token = state
+        .login(&body.username, &body.password)
+        .ok_or(MockError::Unauthorized)?;
+    let refresh_token = state.issue_refresh_token(&body.username);
+    Ok(Json(RememberedLoginResponse {
+        login: login_response(&token),
+        refresh_token,
+    }))
+}
+
+async fn refresh(
+    State(state): State<SharedState>,
+    Json(body): Json<RefreshBody>,
+) -> MockResult<LoginResponse> {
+    let token = state
+        .lock()
+        .unwrap()
+        .refresh(&body.refresh_token)
+        .ok_or(MockError::Unauthorized)?;
+    Ok(login_response(&token))
+}
+
+async fn logout(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<LogoutBody>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    let token = get_bearer_token(&headers)?;
+    state
+        .lock()
+        .unwrap()
+        .logout(&username, token, body.refresh_token.as_deref());
+    Ok(message_response("Logged out"))
+}
+
+async fn ping(State(state): State<SharedState>, headers: HeaderMap) -> MockResult<MessageResponse> {
+    authenticate(&state, &headers)?;
+    Ok(message_response("Pong"))
+}
+
+async fn get_account(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> MockResult<Json<AccountInfo>> {
+    let username = authenticate(&state, &headers)?;
+    let account = state.lock().unwrap().get_account(&username);
+    Ok(Json(account))
+}
+
+/// A wrong password is reported like a validation error, so it is shown next to its field.
+impl From<AccountError> for MockError {
+    fn from(err: AccountError) -> Self {
+        match err {
+            AccountError::WrongPassword => {
+                let mut errors = ValidationErrors::new();
+                errors.add(
+                    "password",
+                    ValidationError::new("wrong_password").with_message("Wrong password".into()),
+                );
+                Self::Validation(errors)
+            }
+            AccountError::UsernameTaken => Self::Conflict("Username already taken".to_string()),
+        }
+    }
+}
+
+/// The rules of the real server, the same ones it applies on registration.
+#[derive(Validate)]
+struct NewPassword<'a> {
+    #[validate(length(min = 8, max = 100))]
+    new_password: &'a str,
+}
+
+#[derive(Validate)]
+struct NewUsername<'a> {
+    #[validate(
+        length(min = 3, max = 50),
+        custom(function = "giga_chess_api_types::validation::alphanumeric::is_alphanumeric")
+    )]
+    username: &'a str,
+}
+
+async fn change_password(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<ChangePasswordBody>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    NewPassword {
+        new_password: &body.new_password,
+    }
+    .validate()
+    .map_err(MockError::Validation)?;
+    state
+        .lock()
+        .unwrap()
+        .change_password(&username, &body.password, &body.new_password)?;
+    Ok(message_response("Password changed"))
+}
+
+async fn change_username(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<ChangeUsernameBody>,
+) -> MockResult<Json<AccountInfo>> {
+    let username = authenticate(&state, &headers)?;
+    NewUsername {
+        username: &body.username,
+    }
+    .validate()
+    .map_err(MockError::Validation)?;
+    let account =
+        state
+            .lock()
+            .unwrap()
+            .change_username(&username, &body.password, &body.username)?;
+    Ok(Json(account))
+}
+
+async fn delete_account(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<DeleteAccountBody>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    state
+        .lock()
+        .unwrap()
+        .delete_account(&username, &body.password)?;
+    Ok(message_response("Account deleted"))
+}
+
+/// Returns the username if the user is an admin.
+fn authenticate_admin(state: &SharedState, headers: &HeaderMap) -> MockResult<String> {
+    let username = authenticate(state, headers)?;
+    if !state.lock().unwrap().is_admin(&username) {
+        return Err(MockError::Forbidden);
+    }
+    Ok(username)
+}
+
+async fn list_invite_codes(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> MockResult<Json<InviteCodeList>> {
+    authenticate_admin(&state, &headers)?;
+    let codes = state.lock().unwrap().list_invite_codes();
+    Ok(Json(InviteCodeList { codes }))
+}
+
+async fn create_invite_code(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<InviteCodeBody>,
+) -> MockResult<Json<InviteCode>> {
+    let username = authenticate_admin(&state, &headers)?;
+    if body.expires_in_days == Some(0) || body.max_uses == Some(0) {
+        return Err(MockError::BadRequest(
+            "Limits have to be at least one".to_string(),
+        ));
+    }
+    let invite_code = state.lock().unwrap().create_invite_code(&username, body);
+    Ok(Json(invite_code))
+}
+
+async fn revoke_invite_code(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(code): Path<String>,
+) -> MockResult<MessageResponse> {
+    authenticate_admin(&state, &headers)?;
+    if !state.lock().unwrap().revoke_invite_code(&code) {
+        return Err(MockError::NotFound);
+    }
+    Ok(message_response("Invite code revoked"))
+}
+
+async fn create_room(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<CreateGameBody>,
+) -> MockResult<PrivateRoomInfo> {
+    let username = authenticate(&state, &headers)?;
+    Ok(state.lock().unwrap().create_room(&username, body))
+}
+
+#[derive(Debug, Deserialize)]
+struct PageQuery {
+    page: Option<i64>,
+    limit: Option<i64>,
+}
+
+fn paginate<T: Clone>(items: &[T], query: &PageQuery) -> (Vec<T>, Pagination) {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(10).clamp(1, 100);
+    let results: Vec<T> = items
+        .iter()
+        .skip(((page - 1) * limit) as usize)
+        .take(limit as usize)
+        .cloned()
+        .collect();
+
+    let pagination = Pagination {
+        results: results.len() as i64,
+        total: items.len() as i64,
+        page,
+        limit,
+    };
+    (results, pagination)
+}
+
+async fn list_public_rooms(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Query(query): Query<PageQuery>,
+) -> MockResult<Json<PublicRoomList>> {
+    authenticate(&state, &headers)?;
+    let all_rooms = state.lock().unwrap().list_public_rooms();
+    let (rooms, pagination) = paginate(&all_rooms, &query);
+    Ok(Json(PublicRoomList { rooms, pagination }))
+}
+
+async fn create_correspondence_game(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<CorrespondenceBody>,
+) -> MockResult<PrivateRoomInfo> {
+    let username = authenticate(&state, &headers)?;
+    if body.days_per_move == 0 {
+        return Err(MockError::BadRequest(
+            "Players need at least one day per move".to_string(),
+        ));
+    }
+    Ok(state
+        .lock()
+        .unwrap()
+        .create_correspondence_room(&username, body))
+}
+
+async fn list_correspondence_games(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> MockResult<Json<CorrespondenceGameList>> {
+    let username = authenticate(&state, &headers)?;
+    let games = state.lock().unwrap().list_correspondence_games(&username);
+    Ok(Json(CorrespondenceGameList { games }))
+}
+
+async fn list_live_games(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Query(query): Query<PageQuery>,
+) -> MockResult<Json<LiveGameList>> {
+    authenticate(&state, &headers)?;
+    let all_games: Vec<LiveGame> = state.lock().unwrap().list_live_games();
+    let (games, pagination) = paginate(&all_games, &query);
+    Ok(Json(LiveGameList { games, pagination }))
+}
+
+async fn get_room(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+) -> MockResult<PrivateRoomInfo> {
+    authenticate(&state, &headers)?;
+    state
+        .lock()
+        .unwrap()
+        .rooms
+        .get(&uuid)
+        .map(|room| room.info.clone())
+        .ok_or(MockError::NotFound)
+}
+
+async fn join_room(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<UuidBody>,
+) -> MockResult<PrivateRoomInfo> {
+    let username = authenticate(&state, &headers)?;
+    state
+        .lock()
+        .unwrap()
+        .join_room(&username, &body.uuid)
+        .map_err(|err| match err {
+            JoinError::NotFound => MockError::NotFound,
+            JoinError::Full => MockError::Conflict("The room is already full".to_string()),
+        })
+}
+
+async fn leave_room(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<UuidBody>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    state.lock().unwrap().leave_room(&username, &body.uuid);
+    Ok(message_response("Left the room"))
+}
+
+async fn join_queue(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<QueueBody>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    state.lock().unwrap().join_queue(&username, body);
+    Ok(message_response("Joined the queue"))
+}
+
+async fn get_queue(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> MockResult<Json<QueueStatusResponse>> {
+    let username = authenticate(&state, &headers)?;
+    let room = state.lock().unwrap().get_queue_match(&username);
+    Ok(Json(QueueStatusResponse { room }))
+}
+
+async fn leave_queue(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    state.lock().unwrap().leave_queue(&username);
+    Ok(message_response("Left the queue"))
+}
+
+/// Resolves `me` to the authenticated user.
+fn get_username(state: &SharedState, headers: &HeaderMap, name: String) -> MockResult<String> {
+    let username = authenticate(state, headers)?;
+    Ok(if name == "me" { username } else { name })
+}
+
+async fn get_user_profile(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> MockResult<Json<UserProfile>> {
+    let username = get_username(&state, &headers, name)?;
+    let profile = state.lock().unwrap().get_user_profile(&username);
+    profile.map(Json).ok_or(MockError::NotFound)
+}
+
+async fn get_game_history(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(query): Query<PageQuery>,
+) -> MockResult<Json<GameHistory>> {
+    let username = get_username(&state, &headers, name)?;
+    let all_games = state.lock().unwrap().get_game_history(&username);
+    let (games, pagination) = paginate(&all_games, &query);
+    Ok(Json(GameHistory { games, pagination }))
+}
+
+async fn get_game_record(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+) -> MockResult<Json<GameRecord>> {
+    authenticate(&state, &headers)?;
+    let record = state.lock().unwrap().get_game_record(&uuid);
+    record.map(Json).ok_or(MockError::NotFound)
+}
+
+async fn get_leaderboard(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Query(query): Query<LeaderboardQuery>,
+) -> MockResult<Json<Leaderboard>> {
+    let username = authenticate(&state, &headers)?;
+    let all_entries = state.lock().unwrap().get_leaderboard(query.category);
+    let page_query = PageQuery {
+        page: Some(query.page),
+        limit: Some(query.limit),
+    };
+    let (entries, pagination) = paginate(&all_entries, &page_query);
+    let own_entry = all_entries.into_iter().find(|entry| entry.name == username);
+    Ok(Json(Leaderboard {
+        category: query.category,
+        entries,
+        pagination,
+        own_entry,
+    }))
+}
+
+impl From<SocialError> for MockError {
+    fn from(err: SocialError) -> Self {
+        match err {
+            SocialError::NotFound => Self::NotFound,
+            SocialError::Invalid(message) => Self::BadRequest(message),
+        }
+    }
+}
+
+async fn get_friends(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> MockResult<Json<FriendList>> {
+    let username = authenticate(&state, &headers)?;
+    let friends = state.lock().unwrap().get_friends(&username);
+    Ok(Json(friends))
+}
+
+async fn add_friend(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<UsernameBody>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    state
+        .lock()
+        .unwrap()
+        .add_friend(&username, &body.username)?;
+    Ok(message_response("Friend request sent"))
+}
+
+async fn remove_friend(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    state.lock().unwrap().remove_friend(&username, &name);
+    Ok(message_response("Friend removed"))
+}
+
+async fn get_challenges(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> MockResult<Json<ChallengeList>> {
+    let username = authenticate(&state, &headers)?;
+    let challenges = state.lock().unwrap().get_challenges(&username);
+    Ok(Json(challenges))
+}
+
+async fn send_challenge(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(body): Json<ChallengeBody>,
+) -> MockResult<Json<Challenge>> {
+    let username = authenticate(&state, &headers)?;
+    let challenge = state.lock().unwrap().send_challenge(&username, body)?;
+    Ok(Json(challenge))
+}
+
+async fn accept_challenge(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+) -> MockResult<PrivateRoomInfo> {
+    let username = authenticate(&state, &headers)?;
+    let room = state.lock().unwrap().accept_challenge(&username, &uuid)?;
+    Ok(room)
+}
+
+async fn decline_challenge(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    state.lock().unwrap().decline_challenge(&username, &uuid)?;
+    Ok(message_response("Challenge declined"))
+}
+
+impl From<TournamentError> for MockError {
+    fn from(err: TournamentError) -> Self {
+        match err {
+            TournamentError::NotFound => Self::NotFound,
+            TournamentError::Rejected(message) => Self::BadRequest(message),
+        }
+    }
+}
+
+async fn list_tournaments(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Query(query): Query<PageQuery>,
+) -> MockResult<Json<TournamentList>> {
+    let username = authenticate(&state, &headers)?;
+    let all_tournaments = state.lock().unwrap().list_tournaments(&username);
+    let (tournaments, pagination) = paginate(&all_tournaments, &query);
+    Ok(Json(TournamentList {
+        tournaments,
+        pagination,
+    }))
+}
+
+async fn get_tournament(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+) -> MockResult<Json<Tournament>> {
+    let username = authenticate(&state, &headers)?;
+    let tournament = state.lock().unwrap().get_tournament(&username, &uuid);
+    tournament.map(Json).ok_or(MockError::NotFound)
+}
+
+async fn join_tournament(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    state.lock().unwrap().join_tournament(&username, &uuid)?;
+    Ok(message_response("Joined the tournament"))
+}
+
+async fn withdraw_from_tournament(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    state
+        .lock()
+        .unwrap()
+        .withdraw_from_tournament(&username, &uuid)?;
+    Ok(message_response("Withdrew from the tournament"))
+}
+
+/// Applies a message of a player to the room, the resulting messages are broadcast via the log.
+fn handle_client_message(
+    state: &SharedState,
+    uuid: &str,
+    username: &str,
+    message: ClientMessage,
+) -> MockResult<()> {
+    let mut state = state.lock().unwrap();
+    let engine = state.engine.clone();
+    let room = state.rooms.get_mut(uuid).ok_or(MockError::NotFound)?;
+    room.handle_message(&engine, username, message)
+        .map_err(|err| match err {
+            RoomError::Rejected(message) => MockError::BadRequest(message),
+            RoomError::RateLimited(retry_after) => MockError::RateLimited(retry_after),
+        })
+}
+
+/// Returns the messages after the cursor, or a full sync if there is none, and the new cursor.
+fn read_log(
+    state: &SharedState,
+    uuid: &str,
+    cursor: Option<usize>,
+) -> MockResult<(Vec<ServerMessage>, usize, watch::Receiver<usize>)> {
+    let state = state.lock().unwrap();
+    let room = state.rooms.get(uuid).ok_or(MockError::NotFound)?;
+    let messages = match cursor {
+        Some(cursor) => room.log.get(cursor..).unwrap_or_default().to_vec(),
+        None => room.sync_messages(),
+    };
+    Ok((messages, room.log.len(), room.log_length.subscribe()))
+}
+
+async fn live_send(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+    Json(message): Json<ClientMessage>,
+) -> MockResult<MessageResponse> {
+    let username = authenticate(&state, &headers)?;
+    handle_client_message(&state, &uuid, &username, message)?;
+    Ok(message_response("Ok"))
+}
+
+#[derive(Debug, Deserialize)]
+struct CursorQuery {
+    cursor: Option<usize>,
+}
+
+async fn live_poll(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+    Query(query): Query<CursorQuery>,
+) -> MockResult<Json<PollResponse>> {
+    let username = authenticate(&state, &headers)?;
+    update_room(&state, &uuid, |room| room.poll_spectator(&username));
+
+    let (mut messages, mut cursor, mut log_length) = read_log(&state, &uuid, query.cursor)?;
+    if messages.is_empty() {
+        let _ = tokio::time::timeout(POLL_TIMEOUT, log_length.changed()).await;
+        (messages, cursor, _) = read_log(&state, &uuid, query.cursor)?;
+    }
+
+    Ok(Json(PollResponse {
+        cursor: cursor as u64,
+        messages,
+    }))
+}
+
+async fn live_socket(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+    upgrade: WebSocketUpgrade,
+) -> MockResult<Response> {
+    let username = authenticate(&state, &headers)?;
+    read_log(&state, &uuid, None)?;
+    Ok(upgrade.on_upgrade(move |socket| async move {
+        update_room(&state, &uuid, |room| room.connect_spectator(&username));
+        run_socket(socket, state.clone(), uuid.clone(), username.clone()).await;
+        update_room(&state, &uuid, |room| room.disconnect_spectator(&username));
+    }))
+}
+
+fn update_room<F: FnOnce(&mut MockRoom)>(state: &SharedState, uuid: &str, update: F) {
+    if let Some(room) = state.lock().unwrap().rooms.get_mut(uuid) {
+        update(room);
+    }
+}
+
+async fn run_socket(mut socket: WebSocket, state: SharedState, uuid: String, username: String) {
+    let mut cursor = None;
+    loop {
+        let Ok((messages, new_cursor, mut log_length)) = read_log(&state, &uuid, cursor) else {
+            return;
+        };
+        cursor = Some(new_cursor);
+        for message in messages {
+            if send_json(&mut socket, &message).await.is_err() {
+                return;
+            }
+        }
+
+        tokio::select! {
+            _ = log_length.changed() => {}
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => continue,
+                };
+                let result = serde_json::from_str::<ClientMessage>(&text)
+                    .map_err(|err| MockError::BadRequest(err.to_string()))
+                    .and_then(|message| handle_client_message(&state, &uuid, &username, message));
+                let error = match result {
+                    Err(MockError::BadRequest(error)) => ServerMessage::Error { message: error },
+                    Err(MockError::RateLimited(retry_after)) => ServerMessage::RateLimited {
+                        message: "Too many messages, slow down".to_string(),
+                        retry_after_secs: Some(retry_after.as_secs() + 1),
+                    },
+                    _ => continue,
+                };
+                if send_json(&mut socket, &error).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+async fn send_json(socket: &mut WebSocket, message: &ServerMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).unwrap_or_default();
+    socket.send(Message::text(text)).await
+}
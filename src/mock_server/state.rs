@@ -0,0 +1,1751 @@
+use crate::api::account::{AccountInfo, Role};
+use crate::api::correspondence::{CorrespondenceBody, CorrespondenceGame, MICROS_PER_DAY};
+use crate::api::friends::{
+    Challenge, ChallengeBody, ChallengeList, ChallengeStatus, Friend, FriendList, Presence,
+};
+use crate::api::invite_codes::{InviteCode, InviteCodeBody};
+use crate::api::leaderboard::LeaderboardEntry;
+use crate::api::live::{
+    ChatMessage, ClientMessage, ClockUpdate, ServerMessage, MAX_CHAT_MESSAGE_LENGTH,
+};
+use crate::api::matchmaking::{ColorPreference, CreateGameBody, QueueBody, TimeControl};
+use crate::api::player_profile::{
+    GameOutcome, GameRecord, GameStats, GameSummary, Rating, RatingCategory, UserProfile,
+};
+use crate::api::spectating::LiveGame;
+use crate::api::tournaments::{
+    Pairing, Standing, Tournament, TournamentFormat, TournamentStatus, TournamentSummary,
+};
+use crate::game::uci::{find_uci_move, move_to_uci};
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use giga_chess::prelude::*;
+use giga_chess_api_types::response::room_info::{PrivateRoomInfo, PublicRoomInfo};
+use giga_chess_api_types::response::user_info::PublicUserInfo;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+const INITIAL_RATING: i32 = 1500;
+const RATING_K_FACTOR: f64 = 32.0;
+/// Users count as online if they made a request within this time.
+const PRESENCE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Players may send this many chat messages within the window.
+const CHAT_RATE_LIMIT: usize = 5;
+const CHAT_RATE_WINDOW: Duration = Duration::from_secs(10);
+/// Spectators using long polling are counted until they stop polling for this long.
+const SPECTATOR_POLL_TIMEOUT: Duration = Duration::from_secs(60);
+const SWISS_ROUNDS: u32 = 3;
+const ARENA_DURATION: Duration = Duration::from_secs(30 * 60);
+/// Arenas reward wins more, a draw is worth half a win there as well.
+const ARENA_WIN_POINTS: f32 = 2.0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoomError {
+    Rejected(String),
+    RateLimited(Duration),
+}
+
+impl From<String> for RoomError {
+    fn from(message: String) -> Self {
+        Self::Rejected(message)
+    }
+}
+
+#[derive(Debug, Default)]
+struct SpectatorConnection {
+    open_sockets: usize,
+    last_poll: Option<Instant>,
+}
+
+impl SpectatorConnection {
+    fn is_connected(&self) -> bool {
+        self.open_sockets > 0
+            || self
+                .last_poll
+                .is_some_and(|last_poll| last_poll.elapsed() < SPECTATOR_POLL_TIMEOUT)
+    }
+}
+
+#[derive(Debug)]
+pub struct MockRoom {
+    pub info: PrivateRoomInfo,
+    time_micros: Option<i64>,
+    increment_micros: Option<i64>,
+    /// Set for correspondence games, the clock of a player is reset to the full time after a move.
+    days_per_move: Option<u32>,
+    game: Game,
+    clock: ClockUpdate,
+    last_move_at: Instant,
+    game_end: Option<ServerMessage>,
+    ended_at: Option<DateTime<Utc>>,
+    chat: Vec<ChatMessage>,
+    /// When the recent chat messages of each player were sent, for rate limiting.
+    chat_sent: HashMap<String, VecDeque<Instant>>,
+    spectators: HashMap<String, SpectatorConnection>,
+    /// The last broadcast spectator count.
+    spectator_count: u32,
+    /// Every message broadcast in this room, live connections keep a cursor into it.
+    pub log: Vec<ServerMessage>,
+    pub log_length: watch::Sender<usize>,
+}
+
+impl MockRoom {
+    fn new(
+        engine: &Arc<Engine>,
+        info: PrivateRoomInfo,
+        time_micros: Option<i64>,
+        increment_micros: Option<i64>,
+    ) -> Self {
+        Self {
+            info,
+            time_micros,
+            increment_micros,
+            days_per_move: None,
+            game: Game::new(engine, PGNMetadata::now()),
+            clock: ClockUpdate {
+                white_time_left_micros: time_micros,
+                black_time_left_micros: time_micros,
+            },
+            last_move_at: Instant::now(),
+            game_end: None,
+            ended_at: None,
+            chat: Vec::new(),
+            chat_sent: HashMap::new(),
+            spectators: HashMap::new(),
+            spectator_count: 0,
+            log: Vec::new(),
+            log_length: watch::channel(0).0,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.info.white.is_some() && self.info.black.is_some()
+    }
+
+    pub fn get_color(&self, username: &str) -> Option<Color> {
+        let is_player =
+            |player: &Option<PublicUserInfo>| player.as_ref().is_some_and(|p| p.name == username);
+        if is_player(&self.info.white) {
+            Some(Color::White)
+        } else if is_player(&self.info.black) {
+            Some(Color::Black)
+        } else {
+            None
+        }
+    }
+
+    pub fn sync_message(&self) -> ServerMessage {
+        ServerMessage::Sync {
+            moves: self
+                .game
+                .move_history()
+                .iter()
+                .map(|chess_move| move_to_uci(*chess_move))
+                .collect(),
+            clock: self.clock,
+            chat: self.chat.clone(),
+            days_per_move: self.days_per_move,
+        }
+    }
+
+    fn broadcast(&mut self, message: ServerMessage) {
+        self.log.push(message);
+        self.log_length.send_replace(self.log.len());
+    }
+
+    /// Everything a freshly connected client needs to catch up, including the result.
+    pub fn sync_messages(&self) -> Vec<ServerMessage> {
+        let mut messages = vec![
+            self.sync_message(),
+            ServerMessage::Spectators {
+                count: self.spectator_count,
+            },
+        ];
+        messages.extend(self.game_end.clone());
+        messages
+    }
+
+    /// Registers an opened WebSocket, players don't count as spectators.
+    pub fn connect_spectator(&mut self, username: &str) {
+        if self.get_color(username).is_none() {
+            self.spectators
+                .entry(username.to_string())
+                .or_default()
+                .open_sockets += 1;
+            self.update_spectators();
+        }
+    }
+
+    pub fn disconnect_spectator(&mut self, username: &str) {
+        if let Some(spectator) = self.spectators.get_mut(username) {
+            spectator.open_sockets = spectator.open_sockets.saturating_sub(1);
+            self.update_spectators();
+        }
+    }
+
+    pub fn poll_spectator(&mut self, username: &str) {
+        if self.get_color(username).is_none() {
+            self.spectators
+                .entry(username.to_string())
+                .or_default()
+                .last_poll = Some(Instant::now());
+        }
+        self.update_spectators();
+    }
+
+    /// Drops spectators which left and broadcasts the count if it changed.
+    fn update_spectators(&mut self) {
+        self.spectators
+            .retain(|_, spectator| spectator.is_connected());
+        let count = self.spectators.len() as u32;
+        if count != self.spectator_count {
+            self.spectator_count = count;
+            self.broadcast(ServerMessage::Spectators { count });
+        }
+    }
+
+    fn get_live_game(&self) -> Option<LiveGame> {
+        if !self.info.public || self.game_end.is_some() {
+            return None;
+        }
+        Some(LiveGame {
+            uuid: self.info.uuid.clone(),
+            name: self.info.name.clone(),
+            white: self.info.white.clone()?,
+            black: self.info.black.clone()?,
+            time_micros: self.time_micros,
+            increment_micros: self.increment_micros,
+            move_count: self.game.move_history().len() as u32,
+            spectators: self
+                .spectators
+                .values()
+                .filter(|spectator| spectator.is_connected())
+                .count() as u32,
+        })
+    }
+
+    fn end(&mut self, result: &str, reason: impl Into<String>) {
+        let game_end = ServerMessage::GameEnd {
+            result: result.to_string(),
+            reason: reason.into(),
+        };
+        self.game_end = Some(game_end.clone());
+        self.ended_at = Some(Utc::now());
+        self.broadcast(game_end);
+    }
+
+    /// The record of the game, None while it is still running.
+    pub fn get_record(&self) -> Option<GameRecord> {
+        let Some(ServerMessage::GameEnd { result, reason }) = &self.game_end else {
+            return None;
+        };
+        Some(GameRecord {
+            summary: GameSummary {
+                uuid: self.info.uuid.clone(),
+                white: self.info.white.clone()?,
+                black: self.info.black.clone()?,
+                time_micros: self.time_micros,
+                increment_micros: self.increment_micros,
+                result: result.clone(),
+                reason: reason.clone(),
+                ended_at: self.ended_at?.timestamp(),
+            },
+            moves: self
+                .game
+                .move_history()
+                .iter()
+                .map(|chess_move| move_to_uci(*chess_move))
+                .collect(),
+        })
+    }
+
+    /// Applies a message of one of the players, errors are only reported back to the sender.
+    /// Chatting is still possible once the game is over.
+    pub fn handle_message(
+        &mut self,
+        engine: &Arc<Engine>,
+        username: &str,
+        message: ClientMessage,
+    ) -> Result<(), RoomError> {
+        let color = self
+            .get_color(username)
+            .ok_or_else(|| "You are not playing in this game".to_string())?;
+
+        match message {
+            ClientMessage::Chat { text } => self.chat(username, text),
+            ClientMessage::Move { uci } => {
+                self.check_running()?;
+                Ok(self.play_move(engine, color, &uci)?)
+            }
+            ClientMessage::Resign => {
+                self.check_running()?;
+                let result = match color {
+                    Color::White => "0-1",
+                    Color::Black => "1-0",
+                };
+                self.end(result, format!("{username} resigned"));
+                Ok(())
+            }
+        }
+    }
+
+    /// The remaining time of the side to move, None for untimed or not running games.
+    fn get_time_to_move(&self) -> Option<i64> {
+        if !self.is_full() || self.game_end.is_some() {
+            return None;
+        }
+        let time_left = match self.game.side_to_move() {
+            Color::White => self.clock.white_time_left_micros,
+            Color::Black => self.clock.black_time_left_micros,
+        }?;
+        Some(time_left - self.last_move_at.elapsed().as_micros() as i64)
+    }
+
+    /// Correspondence games end when a deadline passes, even if nobody is connected.
+    fn expire_deadline(&mut self) {
+        if self.days_per_move.is_none() || self.get_time_to_move().is_none_or(|time| time > 0) {
+            return;
+        }
+        let (result, time_left) = match self.game.side_to_move() {
+            Color::White => ("0-1", &mut self.clock.white_time_left_micros),
+            Color::Black => ("1-0", &mut self.clock.black_time_left_micros),
+        };
+        *time_left = Some(0);
+        self.end(result, "Time ran out");
+    }
+
+    fn get_correspondence_game(&self) -> Option<CorrespondenceGame> {
+        let days_per_move = self.days_per_move?;
+        if self.game_end.is_some() {
+            return None;
+        }
+        let player_to_move = match self.game.side_to_move() {
+            Color::White => &self.info.white,
+            Color::Black => &self.info.black,
+        };
+        Some(CorrespondenceGame {
+            uuid: self.info.uuid.clone(),
+            name: self.info.name.clone(),
+            public: self.info.public,
+            white: self.info.white.clone(),
+            black: self.info.black.clone(),
+            days_per_move,
+            move_count: self.game.move_history().len() as u32,
+            player_to_move: player_to_move
+                .as_ref()
+                .filter(|_| self.is_full())
+                .map(|player| player.name.clone()),
+            deadline: self
+                .get_time_to_move()
+                .map(|time_left| Utc::now().timestamp() + time_left / 1_000_000),
+        })
+    }
+
+    fn check_running(&self) -> Result<(), String> {
+        if self.game_end.is_some() {
+            return Err("The game is already over".to_string());
+        }
+        if !self.is_full() {
+            return Err("The game has not started yet".to_string());
+        }
+        Ok(())
+    }
+
+    fn chat(&mut self, username: &str, text: String) -> Result<(), RoomError> {
+        let text = text.trim();
+        if text.is_empty() || text.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+            return Err(format!(
+                "Chat messages must be between 1 and {MAX_CHAT_MESSAGE_LENGTH} characters"
+            )
+            .into());
+        }
+
+        let sent = self.chat_sent.entry(username.to_string()).or_default();
+        while sent
+            .front()
+            .is_some_and(|sent_at| sent_at.elapsed() >= CHAT_RATE_WINDOW)
+        {
+            sent.pop_front();
+        }
+        if sent.len() >= CHAT_RATE_LIMIT
+            && let Some(oldest) = sent.front()
+        {
+            return Err(RoomError::RateLimited(
+                CHAT_RATE_WINDOW.saturating_sub(oldest.elapsed()),
+            ));
+        }
+        sent.push_back(Instant::now());
+
+        let message = ChatMessage {
+            from: username.to_string(),
+            text: text.to_string(),
+            sent_at: Utc::now().timestamp(),
+        };
+        self.chat.push(message.clone());
+        self.broadcast(ServerMessage::Chat(message));
+        Ok(())
+    }
+
+    fn play_move(&mut self, engine: &Arc<Engine>, color: Color, uci: &str) -> Result<(), String> {
+        if self.game.side_to_move() != color {
+            return Err("It is not your turn".to_string());
+        }
+        let chess_move =
+            find_uci_move(&self.game, uci).ok_or_else(|| format!("Illegal move '{uci}'"))?;
+
+        if let Some(full_time) = self.time_micros {
+            let elapsed = self.last_move_at.elapsed().as_micros() as i64;
+            let increment = self.increment_micros.unwrap_or(0);
+            let is_correspondence = self.days_per_move.is_some();
+            let time_left = match color {
+                Color::White => &mut self.clock.white_time_left_micros,
+                Color::Black => &mut self.clock.black_time_left_micros,
+            };
+            if let Some(time_left) = time_left {
+                *time_left -= elapsed;
+                if *time_left <= 0 {
+                    *time_left = 0;
+                    let result = match color {
+                        Color::White => "0-1",
+                        Color::Black => "1-0",
+                    };
+                    self.end(result, "Time ran out");
+                    return Ok(());
+                }
+                if is_correspondence {
+                    *time_left = full_time;
+                } else {
+                    *time_left += increment;
+                }
+            }
+        }
+        self.last_move_at = Instant::now();
+
+        self.game.play_move(engine, chess_move);
+        self.broadcast(ServerMessage::Move {
+            uci: move_to_uci(chess_move),
+            clock: self.clock,
+        });
+
+        match self.game.status() {
+            GameStatus::Running => {}
+            GameStatus::Checkmate => {
+                let result = match self.game.winner() {
+                    Some(Color::White) => "1-0",
+                    _ => "0-1",
+                };
+                self.end(result, "Checkmate");
+            }
+            GameStatus::Stalemate => self.end("1/2-1/2", "Stalemate"),
+            GameStatus::DrawFiftyMove => self.end("1/2-1/2", "Fifty move rule"),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MockPairing {
+    round: u32,
+    white: String,
+    /// None for a bye, which counts as a win.
+    black: Option<String>,
+    room: Option<String>,
+}
+
+impl MockPairing {
+    fn includes(&self, username: &str) -> bool {
+        self.white == username || self.black.as_deref() == Some(username)
+    }
+
+    fn get_opponent(&self, username: &str) -> Option<&str> {
+        match &self.black {
+            Some(black) if self.white == username => Some(black),
+            Some(black) if black == username => Some(&self.white),
+            _ => None,
+        }
+    }
+}
+
+/// A tournament which starts on its own once it is due and at least two players joined.
+#[derive(Debug)]
+pub struct MockTournament {
+    uuid: String,
+    name: String,
+    format: TournamentFormat,
+    time_control: Option<TimeControl>,
+    rounds: Option<u32>,
+    status: TournamentStatus,
+    current_round: u32,
+    starts_at: DateTime<Utc>,
+    ends_at: Option<DateTime<Utc>>,
+    /// In the order they joined, withdrawn players stay in the standings.
+    players: Vec<String>,
+    withdrawn: HashSet<String>,
+    pairings: Vec<MockPairing>,
+}
+
+impl MockTournament {
+    fn is_active_player(&self, username: &str) -> bool {
+        self.players.iter().any(|player| player == username) && !self.withdrawn.contains(username)
+    }
+
+    fn active_players(&self) -> Vec<String> {
+        self.players
+            .iter()
+            .filter(|player| !self.withdrawn.contains(*player))
+            .cloned()
+            .collect()
+    }
+
+    fn have_played(&self, username: &str, other: &str) -> bool {
+        self.pairings
+            .iter()
+            .any(|pairing| pairing.get_opponent(username) == Some(other))
+    }
+
+    fn count_white_games(&self, username: &str) -> usize {
+        self.pairings
+            .iter()
+            .filter(|pairing| pairing.white == username && pairing.black.is_some())
+            .count()
+    }
+
+    /// The player who had white less often gets it now.
+    fn get_colors(&self, first: String, second: String) -> (String, Option<String>) {
+        if self.count_white_games(&first) <= self.count_white_games(&second) {
+            (first, Some(second))
+        } else {
+            (second, Some(first))
+        }
+    }
+
+    /// Pairs the next round, players are ordered by points and don't meet twice if avoidable.
+    /// The lowest ranked player without a bye so far sits out if the count is odd.
+    fn pair_swiss(&self, ranking: Vec<String>) -> Vec<(String, Option<String>)> {
+        let mut unpaired = ranking;
+        let mut pairs = Vec::new();
+        if unpaired.len() % 2 == 1 {
+            let had_bye = |player: &String| {
+                self.pairings
+                    .iter()
+                    .any(|pairing| pairing.black.is_none() && &pairing.white == player)
+            };
+            let bye_index = unpaired
+                .iter()
+                .rposition(|player| !had_bye(player))
+                .unwrap_or(unpaired.len() - 1);
+            pairs.push((unpaired.remove(bye_index), None));
+        }
+
+        while !unpaired.is_empty() {
+            let player = unpaired.remove(0);
+            let opponent_index = unpaired
+                .iter()
+                .position(|opponent| !self.have_played(&player, opponent))
+                .unwrap_or(0);
+            let opponent = unpaired.remove(opponent_index);
+            pairs.push(self.get_colors(player, opponent));
+        }
+        pairs
+    }
+
+    /// Pairs the given round with the circle method, the players are fixed once it started.
+    fn pair_round_robin(&self, round: u32) -> Vec<(String, Option<String>)> {
+        let mut seats: Vec<Option<String>> = self.players.iter().cloned().map(Some).collect();
+        if seats.len() % 2 == 1 {
+            seats.push(None);
+        }
+        let rotation = (round as usize - 1) % (seats.len() - 1);
+        seats[1..].rotate_right(rotation);
+
+        let half = seats.len() / 2;
+        let mut pairs = Vec::new();
+        for index in 0..half {
+            let mut pair = (seats[index].clone(), seats[seats.len() - 1 - index].clone());
+            if (index + round as usize).is_multiple_of(2) {
+                pair = (pair.1, pair.0);
+            }
+            let is_active = |player: &Option<String>| {
+                player
+                    .as_ref()
+                    .is_some_and(|player| !self.withdrawn.contains(player))
+            };
+            match (is_active(&pair.0), is_active(&pair.1)) {
+                (true, true) => pairs.push((pair.0.unwrap_or_default(), pair.1)),
+                (true, false) => pairs.push((pair.0.unwrap_or_default(), None)),
+                (false, true) => pairs.push((pair.1.unwrap_or_default(), None)),
+                (false, false) => {}
+            }
+        }
+        pairs
+    }
+}
+
+#[derive(Debug)]
+struct QueueEntry {
+    username: String,
+    body: QueueBody,
+}
+
+/// In-memory state of the mock server, everything is lost once it shuts down.
+#[derive(Debug)]
+pub struct MockState {
+    pub engine: Arc<Engine>,
+    users: HashMap<String, String>,
+    admins: HashSet<String>,
+    invite_codes: HashMap<String, InviteCode>,
+    tokens: HashMap<String, String>,
+    /// Long-lived tokens of remembered logins, mapped to their user.
+    refresh_tokens: HashMap<String, String>,
+    pub rooms: HashMap<String, MockRoom>,
+    queue: Vec<QueueEntry>,
+    queue_matches: HashMap<String, PrivateRoomInfo>,
+    last_seen: HashMap<String, Instant>,
+    /// Pairs of friends, ordered by name.
+    friendships: HashSet<(String, String)>,
+    /// Pending friend requests from the first to the second user.
+    friend_requests: HashSet<(String, String)>,
+    challenges: HashMap<String, Challenge>,
+    tournaments: HashMap<String, MockTournament>,
+    /// Statuses returned instead of handling the next requests, used to test retries.
+    pub injected_failures: VecDeque<StatusCode>,
+    /// Delay before every response, used to test requests which are still running.
+    pub response_delay: Option<Duration>,
+}
+
+impl Default for MockState {
+    fn default() -> Self {
+        let mut state = Self {
+            engine: Engine::initialize(),
+            users: HashMap::new(),
+            admins: HashSet::new(),
+            invite_codes: HashMap::new(),
+            tokens: HashMap::new(),
+            refresh_tokens: HashMap::new(),
+            rooms: HashMap::new(),
+            queue: Vec::new(),
+            queue_matches: HashMap::new(),
+            last_seen: HashMap::new(),
+            friendships: HashSet::new(),
+            friend_requests: HashSet::new(),
+            challenges: HashMap::new(),
+            tournaments: HashMap::new(),
+            injected_failures: VecDeque::new(),
+            response_delay: None,
+        };
+        state.schedule_sample_tournaments();
+        state
+    }
+}
+
+impl MockState {
+    /// Returns false if the username is already taken. The first user becomes an admin, so
+    /// invite codes can be managed on a fresh server.
+    pub fn register(&mut self, username: &str, password: &str) -> bool {
+        if self.users.contains_key(username) {
+            return false;
+        }
+        if self.users.is_empty() {
+            self.admins.insert(username.to_string());
+        }
+        self.users
+            .insert(username.to_string(), password.to_string());
+        true
+    }
+
+    fn check_password(&self, username: &str, password: &str) -> Result<(), AccountError> {
+        match self.users.get(username) {
+            Some(stored) if stored == password => Ok(()),
+            _ => Err(AccountError::WrongPassword),
+        }
+    }
+
+    pub fn change_password(
+        &mut self,
+        username: &str,
+        password: &str,
+        new_password: &str,
+    ) -> Result<(), AccountError> {
+        self.check_password(username, password)?;
+        self.users
+            .insert(username.to_string(), new_password.to_string());
+        Ok(())
+    }
+
+    /// Moves the account, its tokens and friends to the new name. Rooms, games and tournaments
+    /// keep showing the old name.
+    pub fn change_username(
+        &mut self,
+        username: &str,
+        password: &str,
+        new_username: &str,
+    ) -> Result<AccountInfo, AccountError> {
+        self.check_password(username, password)?;
+        if self.users.contains_key(new_username) {
+            return Err(AccountError::UsernameTaken);
+        }
+
+        let rename = |name: &mut String| {
+            if name == username {
+                *name = new_username.to_string();
+            }
+        };
+        if let Some(password) = self.users.remove(username) {
+            self.users.insert(new_username.to_string(), password);
+        }
+        if self.admins.remove(username) {
+            self.admins.insert(new_username.to_string());
+        }
+        self.tokens.values_mut().for_each(rename);
+        self.refresh_tokens.values_mut().for_each(rename);
+        if let Some(last_seen) = self.last_seen.remove(username) {
+            self.last_seen.insert(new_username.to_string(), last_seen);
+        }
+        self.friendships = std::mem::take(&mut self.friendships)
+            .into_iter()
+            .map(|(mut a, mut b)| {
+                rename(&mut a);
+                rename(&mut b);
+                friend_pair(&a, &b)
+            })
+            .collect();
+        self.friend_requests = std::mem::take(&mut self.friend_requests)
+            .into_iter()
+            .map(|(mut from, mut to)| {
+                rename(&mut from);
+                rename(&mut to);
+                (from, to)
+            })
+            .collect();
+        Ok(self.get_account(new_username))
+    }
+
+    /// Removes the account with its tokens, friends and queue entry.
+    pub fn delete_account(&mut self, username: &str, password: &str) -> Result<(), AccountError> {
+        self.check_password(username, password)?;
+        self.leave_queue(username);
+        self.users.remove(username);
+        self.admins.remove(username);
+        self.tokens.retain(|_, name| name != username);
+        self.refresh_tokens.retain(|_, name| name != username);
+        self.last_seen.remove(username);
+        self.friendships
+            .retain(|(a, b)| a != username && b != username);
+        self.friend_requests
+            .retain(|(from, to)| from != username && to != username);
+        Ok(())
+    }
+
+    pub fn grant_admin(&mut self, username: &str) {
+        self.admins.insert(username.to_string());
+    }
+
+    pub fn get_account(&self, username: &str) -> AccountInfo {
+        AccountInfo {
+            name: username.to_string(),
+            role: match self.admins.contains(username) {
+                true => Role::Admin,
+                false => Role::User,
+            },
+        }
+    }
+
+    pub fn is_admin(&self, username: &str) -> bool {
+        self.admins.contains(username)
+    }
+
+    /// Codes which weren't issued by this server are accepted, so it can be used without an admin.
+    pub fn check_invite_code(&self, code: &str) -> Result<(), String> {
+        match self.invite_codes.get(code) {
+            Some(invite_code) if !invite_code.is_active() => {
+                Err("The invite code is no longer valid".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn redeem_invite_code(&mut self, code: &str) {
+        if let Some(invite_code) = self.invite_codes.get_mut(code) {
+            invite_code.uses += 1;
+        }
+    }
+
+    pub fn create_invite_code(&mut self, username: &str, body: InviteCodeBody) -> InviteCode {
+        let created_at = Utc::now().timestamp();
+        let invite_code = InviteCode {
+            code: Uuid::new_v4().to_string(),
+            created_by: username.to_string(),
+            created_at,
+            expires_at: body
+                .expires_in_days
+                .map(|days| created_at + i64::from(days) * 24 * 60 * 60),
+            max_uses: body.max_uses,
+            uses: 0,
+            revoked: false,
+        };
+        self.invite_codes
+            .insert(invite_code.code.clone(), invite_code.clone());
+        invite_code
+    }
+
+    /// The newest codes first.
+    pub fn list_invite_codes(&self) -> Vec<InviteCode> {
+        let mut codes: Vec<InviteCode> = self.invite_codes.values().cloned().collect();
+        codes.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.code.cmp(&b.code))
+        });
+        codes
+    }
+
+    /// Returns false if there is no such code.
+    pub fn revoke_invite_code(&mut self, code: &str) -> bool {
+        match self.invite_codes.get_mut(code) {
+            Some(invite_code) => {
+                invite_code.revoked = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn login(&mut self, username: &str, password: &str) -> Option<String> {
+        if self.users.get(username).map(String::as_str) != Some(password) {
+            return None;
+        }
+        let token = Uuid::new_v4().to_string();
+        self.tokens.insert(token.clone(), username.to_string());
+        Some(token)
+    }
+
+    pub fn issue_refresh_token(&mut self, username: &str) -> String {
+        let refresh_token = Uuid::new_v4().to_string();
+        self.refresh_tokens
+            .insert(refresh_token.clone(), username.to_string());
+        refresh_token
+    }
+
+    /// Issues a new token for a remembered login, None if the refresh token is unknown.
+    pub fn refresh(&mut self, refresh_token: &str) -> Option<String> {
+        let username = self.refresh_tokens.get(refresh_token)?.clone();
+        let token = Uuid::new_v4().to_string();
+        self.tokens.insert(token.clone(), username);
+        Some(token)
+    }
+
+    /// Revokes the token and the user's own refresh token, those of other users are left alone.
+    pub fn logout(&mut self, username: &str, token: &str, refresh_token: Option<&str>) {
+        self.tokens.remove(token);
+        if let Some(refresh_token) = refresh_token
+            && self.refresh_tokens.get(refresh_token).map(String::as_str) == Some(username)
+        {
+            self.refresh_tokens.remove(refresh_token);
+        }
+    }
+
+    /// Every authenticated request counts as activity for the presence of the user.
+    pub fn authenticate(&mut self, token: &str) -> Option<String> {
+        let username = self.tokens.get(token).cloned()?;
+        self.last_seen.insert(username.clone(), Instant::now());
+        Some(username)
+    }
+
+    /// The next `count` requests fail with the given status, answered with `Retry-After: 0`.
+    pub fn fail_next_requests(&mut self, count: usize, status: StatusCode) {
+        self.injected_failures
+            .extend(std::iter::repeat_n(status, count));
+    }
+
+    /// Invalidates every issued token, used to test session refreshes.
+    pub fn revoke_tokens(&mut self) {
+        self.tokens.clear();
+    }
+
+    /// Forgets every remembered login, used to test sessions which can't be refreshed anymore.
+    pub fn revoke_refresh_tokens(&mut self) {
+        self.refresh_tokens.clear();
+    }
+
+    pub fn create_room(&mut self, username: &str, body: CreateGameBody) -> PrivateRoomInfo {
+        let info = PrivateRoomInfo {
+            uuid: Uuid::new_v4().to_string(),
+            name: body.room.name,
+            public: body.room.public,
+            white: None,
+            black: None,
+        };
+        let mut room = MockRoom::new(
+            &self.engine,
+            info,
+            body.room.time_micros,
+            body.room.increment_micros,
+        );
+        seat_player(&mut room.info, username, body.color);
+
+        let info = room.info.clone();
+        self.rooms.insert(info.uuid.clone(), room);
+        info
+    }
+
+    pub fn create_correspondence_room(
+        &mut self,
+        username: &str,
+        body: CorrespondenceBody,
+    ) -> PrivateRoomInfo {
+        let time_micros = i64::from(body.days_per_move) * MICROS_PER_DAY;
+        let info = PrivateRoomInfo {
+            uuid: Uuid::new_v4().to_string(),
+            name: body.name,
+            public: body.public,
+            white: None,
+            black: None,
+        };
+        let mut room = MockRoom::new(&self.engine, info, Some(time_micros), None);
+        room.days_per_move = Some(body.days_per_move);
+        seat_player(&mut room.info, username, body.color);
+
+        let info = room.info.clone();
+        self.rooms.insert(info.uuid.clone(), room);
+        info
+    }
+
+    /// Running correspondence games of the user, ends the ones whose deadline passed first.
+    pub fn list_correspondence_games(&mut self, username: &str) -> Vec<CorrespondenceGame> {
+        let mut games: Vec<CorrespondenceGame> = self
+            .rooms
+            .values_mut()
+            .filter(|room| room.get_color(username).is_some())
+            .filter_map(|room| {
+                room.expire_deadline();
+                room.get_correspondence_game()
+            })
+            .collect();
+        games.sort_by(|a, b| a.uuid.cmp(&b.uuid));
+        games
+    }
+
+    pub fn list_public_rooms(&self) -> Vec<PublicRoomInfo> {
+        let mut rooms: Vec<PublicRoomInfo> = self
+            .rooms
+            .values()
+            .filter(|room| room.info.public && !room.is_full())
+            .map(|room| PublicRoomInfo {
+                uuid: room.info.uuid.clone(),
+                name: room.info.name.clone(),
+                white: room.info.white.clone(),
+                black: room.info.black.clone(),
+            })
+            .collect();
+        rooms.sort();
+        rooms
+    }
+
+    /// Running public games, the most watched ones first.
+    pub fn list_live_games(&self) -> Vec<LiveGame> {
+        let mut games: Vec<LiveGame> = self
+            .rooms
+            .values()
+            .filter_map(MockRoom::get_live_game)
+            .collect();
+        games.sort_by(|a, b| {
+            b.spectators
+                .cmp(&a.spectators)
+                .then_with(|| a.uuid.cmp(&b.uuid))
+        });
+        games
+    }
+
+    pub fn join_room(&mut self, username: &str, uuid: &str) -> Result<PrivateRoomInfo, JoinError> {
+        let room = self.rooms.get_mut(uuid).ok_or(JoinError::NotFound)?;
+        if room.get_color(username).is_some() {
+            return Ok(room.info.clone());
+        }
+        if room.is_full() {
+            return Err(JoinError::Full);
+        }
+
+        seat_player(&mut room.info, username, ColorPreference::Random);
+        // The clock of the first move starts once both players are seated
+        room.last_move_at = Instant::now();
+        let sync = room.sync_message();
+        room.broadcast(sync);
+        Ok(room.info.clone())
+    }
+
+    /// Removes rooms which never started, running games are left to be resigned.
+    pub fn leave_room(&mut self, username: &str, uuid: &str) {
+        let is_open_own_room = self
+            .rooms
+            .get(uuid)
+            .is_some_and(|room| room.get_color(username).is_some() && !room.is_full());
+        if is_open_own_room {
+            self.rooms.remove(uuid);
+        }
+    }
+
+    pub fn join_queue(&mut self, username: &str, body: QueueBody) {
+        self.leave_queue(username);
+
+        let opponent_index = self.queue.iter().position(|entry| {
+            entry.body.time_micros == body.time_micros
+                && entry.body.increment_micros == body.increment_micros
+                && (entry.body.color == ColorPreference::Random
+                    || body.color == ColorPreference::Random
+                    || entry.body.color != body.color)
+        });
+        let Some(opponent_index) = opponent_index else {
+            self.queue.push(QueueEntry {
+                username: username.to_string(),
+                body,
+            });
+            return;
+        };
+
+        let opponent = self.queue.remove(opponent_index);
+        // A player without preference takes whatever side the other one didn't ask for
+        let opponent_color = match (opponent.body.color, body.color) {
+            (ColorPreference::Random, ColorPreference::White) => ColorPreference::Black,
+            (ColorPreference::Random, ColorPreference::Black) => ColorPreference::White,
+            (color, _) => color,
+        };
+        let create_body = CreateGameBody::new(None, false, None, opponent_color);
+        let mut info = self.create_room(&opponent.username, create_body);
+        if let Some(room) = self.rooms.get_mut(&info.uuid) {
+            room.time_micros = body.time_micros;
+            room.increment_micros = body.increment_micros;
+            room.clock = ClockUpdate {
+                white_time_left_micros: body.time_micros,
+                black_time_left_micros: body.time_micros,
+            };
+            seat_player(&mut room.info, username, body.color);
+            info = room.info.clone();
+        }
+
+        self.queue_matches.insert(opponent.username, info.clone());
+        self.queue_matches.insert(username.to_string(), info);
+    }
+
+    pub fn get_queue_match(&self, username: &str) -> Option<PrivateRoomInfo> {
+        self.queue_matches.get(username).cloned()
+    }
+
+    pub fn leave_queue(&mut self, username: &str) {
+        self.queue.retain(|entry| entry.username != username);
+        self.queue_matches.remove(username);
+    }
+
+    /// Finished games in the order they ended.
+    fn finished_games(&self) -> Vec<&MockRoom> {
+        let mut rooms: Vec<&MockRoom> = self
+            .rooms
+            .values()
+            .filter(|room| room.ended_at.is_some())
+            .collect();
+        rooms.sort_by_key(|room| room.ended_at);
+        rooms
+    }
+
+    /// Ratings are calculated from scratch with the Elo system, every player starts at 1500.
+    /// Replays all finished games in order, ratings are keyed by player and category.
+    fn compute_ratings(&self) -> HashMap<(String, RatingCategory), Rating> {
+        let mut ratings: HashMap<(String, RatingCategory), Rating> = HashMap::new();
+        for record in self
+            .finished_games()
+            .iter()
+            .filter_map(|room| room.get_record())
+        {
+            let summary = &record.summary;
+            let category = RatingCategory::from_time_control(summary.time_control());
+            let mut get_rating = |name: &str| {
+                *ratings
+                    .entry((name.to_string(), category))
+                    .or_insert(Rating {
+                        category,
+                        rating: INITIAL_RATING,
+                        games: 0,
+                    })
+            };
+            let white = get_rating(&summary.white.name);
+            let black = get_rating(&summary.black.name);
+
+            let white_score = match summary.get_outcome(&summary.white.name) {
+                Some(GameOutcome::Win) => 1.0,
+                Some(GameOutcome::Loss) => 0.0,
+                Some(GameOutcome::Draw) => 0.5,
+                None => continue,
+            };
+            let expected = 1.0 / (1.0 + 10f64.powf(f64::from(black.rating - white.rating) / 400.0));
+            let change = (RATING_K_FACTOR * (white_score - expected)).round() as i32;
+            for (name, rating, change) in [
+                (&summary.white.name, white, change),
+                (&summary.black.name, black, -change),
+            ] {
+                ratings.insert(
+                    (name.clone(), category),
+                    Rating {
+                        rating: rating.rating + change,
+                        games: rating.games + 1,
+                        ..rating
+                    },
+                );
+            }
+        }
+        ratings
+    }
+
+    pub fn get_user_profile(&self, username: &str) -> Option<UserProfile> {
+        if !self.users.contains_key(username) {
+            return None;
+        }
+
+        let mut stats = GameStats::default();
+        for summary in self.get_game_history(username) {
+            if let Some(outcome) = summary.get_outcome(username) {
+                stats.add(outcome);
+            }
+        }
+
+        let mut ratings: Vec<Rating> = self
+            .compute_ratings()
+            .into_iter()
+            .filter(|((name, _), _)| name == username)
+            .map(|(_, rating)| rating)
+            .collect();
+        ratings.sort_by_key(|rating| rating.category);
+        Some(UserProfile {
+            name: username.to_string(),
+            ratings,
+            stats,
+        })
+    }
+
+    /// All rated players of the category, the best first and ties ordered by name.
+    pub fn get_leaderboard(&self, category: RatingCategory) -> Vec<LeaderboardEntry> {
+        let mut ratings: Vec<(String, Rating)> = self
+            .compute_ratings()
+            .into_iter()
+            .filter(|((_, rating_category), _)| *rating_category == category)
+            .map(|((name, _), rating)| (name, rating))
+            .collect();
+        ratings.sort_by(|(a_name, a), (b_name, b)| {
+            b.rating.cmp(&a.rating).then_with(|| a_name.cmp(b_name))
+        });
+        ratings
+            .into_iter()
+            .enumerate()
+            .map(|(index, (name, rating))| LeaderboardEntry {
+                rank: index as i64 + 1,
+                name,
+                rating: rating.rating,
+                games: rating.games,
+            })
+            .collect()
+    }
+
+    /// Finished games of the user, newest first.
+    pub fn get_game_history(&self, username: &str) -> Vec<GameSummary> {
+        self.finished_games()
+            .iter()
+            .rev()
+            .filter(|room| room.get_color(username).is_some())
+            .filter_map(|room| room.get_record())
+            .map(|record| record.summary)
+            .collect()
+    }
+
+    pub fn get_game_record(&self, uuid: &str) -> Option<GameRecord> {
+        self.rooms.get(uuid)?.get_record()
+    }
+
+    fn get_presence(&self, username: &str) -> Presence {
+        let is_online = self
+            .last_seen
+            .get(username)
+            .is_some_and(|last_seen| last_seen.elapsed() < PRESENCE_TIMEOUT);
+        let is_playing = self.rooms.values().any(|room| {
+            room.is_full() && room.game_end.is_none() && room.get_color(username).is_some()
+        });
+        match (is_online, is_playing) {
+            (false, _) => Presence::Offline,
+            (true, false) => Presence::Online,
+            (true, true) => Presence::Playing,
+        }
+    }
+
+    fn are_friends(&self, username: &str, other: &str) -> bool {
+        self.friendships.contains(&friend_pair(username, other))
+    }
+
+    pub fn get_friends(&self, username: &str) -> FriendList {
+        let mut friends: Vec<Friend> = self
+            .friendships
+            .iter()
+            .filter_map(|(first, second)| match username {
+                _ if first == username => Some(second),
+                _ if second == username => Some(first),
+                _ => None,
+            })
+            .map(|name| Friend {
+                name: name.clone(),
+                presence: self.get_presence(name),
+            })
+            .collect();
+        friends.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let get_requests = |matches: &dyn Fn(&(String, String)) -> Option<String>| {
+            let mut requests: Vec<PublicUserInfo> = self
+                .friend_requests
+                .iter()
+                .filter_map(matches)
+                .map(|name| PublicUserInfo { name })
+                .collect();
+            requests.sort();
+            requests
+        };
+        FriendList {
+            friends,
+            incoming_requests: get_requests(&|(from, to)| (to == username).then(|| from.clone())),
+            outgoing_requests: get_requests(&|(from, to)| (from == username).then(|| to.clone())),
+        }
+    }
+
+    /// Sends a friend request, or accepts the one the other user already sent.
+    pub fn add_friend(&mut self, username: &str, other: &str) -> Result<(), SocialError> {
+        if !self.users.contains_key(other) {
+            return Err(SocialError::NotFound);
+        }
+        if username == other {
+            return Err(SocialError::Invalid(
+                "You can't befriend yourself".to_string(),
+            ));
+        }
+        if self.are_friends(username, other) {
+            return Ok(());
+        }
+
+        let incoming = (other.to_string(), username.to_string());
+        if self.friend_requests.remove(&incoming) {
+            self.friendships.insert(friend_pair(username, other));
+        } else {
+            self.friend_requests
+                .insert((username.to_string(), other.to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn remove_friend(&mut self, username: &str, other: &str) {
+        self.friendships.remove(&friend_pair(username, other));
+        self.friend_requests
+            .remove(&(username.to_string(), other.to_string()));
+        self.friend_requests
+            .remove(&(other.to_string(), username.to_string()));
+    }
+
+    /// Only friends can be challenged.
+    pub fn send_challenge(
+        &mut self,
+        username: &str,
+        body: ChallengeBody,
+    ) -> Result<Challenge, SocialError> {
+        if !self.users.contains_key(&body.username) {
+            return Err(SocialError::NotFound);
+        }
+        if !self.are_friends(username, &body.username) {
+            return Err(SocialError::Invalid(
+                "You can only challenge your friends".to_string(),
+            ));
+        }
+
+        let challenge = Challenge {
+            uuid: Uuid::new_v4().to_string(),
+            challenger: PublicUserInfo {
+                name: username.to_string(),
+            },
+            challenged: PublicUserInfo {
+                name: body.username,
+            },
+            time_micros: body.time_micros,
+            increment_micros: body.increment_micros,
+            color: body.color,
+            status: ChallengeStatus::Pending,
+        };
+        self.challenges
+            .insert(challenge.uuid.clone(), challenge.clone());
+        Ok(challenge)
+    }
+
+    /// Pending challenges for the user and every challenge the user sent.
+    pub fn get_challenges(&self, username: &str) -> ChallengeList {
+        let mut list = ChallengeList::default();
+        for challenge in self.challenges.values() {
+            if challenge.challenger.name == username {
+                list.outgoing.push(challenge.clone());
+            } else if challenge.challenged.name == username
+                && challenge.status == ChallengeStatus::Pending
+            {
+                list.incoming.push(challenge.clone());
+            }
+        }
+        list
+    }
+
+    pub fn accept_challenge(
+        &mut self,
+        username: &str,
+        uuid: &str,
+    ) -> Result<PrivateRoomInfo, SocialError> {
+        let challenge = self
+            .challenges
+            .get(uuid)
+            .filter(|challenge| {
+                challenge.challenged.name == username
+                    && challenge.status == ChallengeStatus::Pending
+            })
+            .cloned()
+            .ok_or(SocialError::NotFound)?;
+
+        let body = CreateGameBody::new(None, false, challenge.time_control(), challenge.color);
+        let room = self.create_room(&challenge.challenger.name, body);
+        let room = self
+            .join_room(username, &room.uuid)
+            .map_err(|_| SocialError::NotFound)?;
+        if let Some(challenge) = self.challenges.get_mut(uuid) {
+            challenge.status = ChallengeStatus::Accepted { room: room.clone() };
+        }
+        Ok(room)
+    }
+
+    /// The challenged user declines, the challenger withdraws the challenge.
+    pub fn decline_challenge(&mut self, username: &str, uuid: &str) -> Result<(), SocialError> {
+        let challenge = self.challenges.get_mut(uuid).ok_or(SocialError::NotFound)?;
+        if challenge.challenger.name == username {
+            self.challenges.remove(uuid);
+        } else if challenge.challenged.name == username
+            && challenge.status == ChallengeStatus::Pending
+        {
+            challenge.status = ChallengeStatus::Declined;
+        } else {
+            return Err(SocialError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Gives users of the mock server something to join, each one starts a few minutes later.
+    fn schedule_sample_tournaments(&mut self) {
+        let samples = [
+            (
+                "Bullet Arena",
+                TournamentFormat::Arena,
+                TimeControl::new(1, 0),
+            ),
+            (
+                "Blitz Swiss",
+                TournamentFormat::Swiss,
+                TimeControl::new(3, 2),
+            ),
+            (
+                "Rapid Round Robin",
+                TournamentFormat::RoundRobin,
+                TimeControl::new(10, 0),
+            ),
+        ];
+        for (index, (name, format, time_control)) in samples.into_iter().enumerate() {
+            let starts_in = Duration::from_secs(120 * (index as u64 + 1));
+            self.create_tournament(name, format, Some(time_control), starts_in);
+        }
+    }
+
+    /// Schedules a tournament, returns its uuid.
+    pub fn create_tournament(
+        &mut self,
+        name: &str,
+        format: TournamentFormat,
+        time_control: Option<TimeControl>,
+        starts_in: Duration,
+    ) -> String {
+        let uuid = Uuid::new_v4().to_string();
+        let tournament = MockTournament {
+            uuid: uuid.clone(),
+            name: name.to_string(),
+            format,
+            time_control,
+            rounds: (format == TournamentFormat::Swiss).then_some(SWISS_ROUNDS),
+            status: TournamentStatus::Upcoming,
+            current_round: 0,
+            starts_at: Utc::now() + starts_in,
+            ends_at: None,
+            players: Vec::new(),
+            withdrawn: HashSet::new(),
+            pairings: Vec::new(),
+        };
+        self.tournaments.insert(uuid.clone(), tournament);
+        uuid
+    }
+
+    /// Starts due tournaments, pairs the next rounds and finishes the ones which are over.
+    /// The mock server has no background tasks, so this runs whenever tournaments are read.
+    fn update_tournaments(&mut self) {
+        let uuids: Vec<String> = self.tournaments.keys().cloned().collect();
+        for uuid in uuids {
+            if let Some(mut tournament) = self.tournaments.remove(&uuid) {
+                self.update_tournament(&mut tournament);
+                self.tournaments.insert(uuid, tournament);
+            }
+        }
+    }
+
+    fn update_tournament(&mut self, tournament: &mut MockTournament) {
+        let now = Utc::now();
+        match tournament.status {
+            TournamentStatus::Upcoming => {
+                if now < tournament.starts_at || tournament.active_players().len() < 2 {
+                    return;
+                }
+                tournament.status = TournamentStatus::Running;
+                match tournament.format {
+                    TournamentFormat::Swiss => {}
+                    TournamentFormat::RoundRobin => {
+                        let player_count = tournament.players.len() as u32;
+                        tournament.rounds = Some(player_count - 1 + player_count % 2);
+                    }
+                    TournamentFormat::Arena => {
+                        tournament.ends_at = Some(now + ARENA_DURATION);
+                    }
+                }
+                self.pair_next_round(tournament);
+            }
+            TournamentStatus::Running if tournament.format == TournamentFormat::Arena => {
+                if tournament.ends_at.is_some_and(|ends_at| now >= ends_at) {
+                    if !self.has_running_games(tournament) {
+                        tournament.status = TournamentStatus::Finished;
+                    }
+                } else {
+                    self.pair_next_round(tournament);
+                }
+            }
+            TournamentStatus::Running => {
+                if self.has_running_games(tournament) {
+                    return;
+                }
+                let is_last_round = tournament
+                    .rounds
+                    .is_some_and(|rounds| tournament.current_round >= rounds);
+                if is_last_round || tournament.active_players().len() < 2 {
+                    tournament.status = TournamentStatus::Finished;
+                } else {
+                    self.pair_next_round(tournament);
+                }
+            }
+            TournamentStatus::Finished => {}
+        }
+    }
+
+    fn get_pairing_result(&self, pairing: &MockPairing) -> Option<String> {
+        let room = self.rooms.get(pairing.room.as_ref()?)?;
+        match &room.game_end {
+            Some(ServerMessage::GameEnd { result, .. }) => Some(result.clone()),
+            _ => None,
+        }
+    }
+
+    fn has_running_games(&self, tournament: &MockTournament) -> bool {
+        tournament
+            .pairings
+            .iter()
+            .any(|pairing| pairing.room.is_some() && self.get_pairing_result(pairing).is_none())
+    }
+
+    /// Arenas pair everyone who is not playing right now, the others wait for the whole round.
+    fn pair_next_round(&mut self, tournament: &mut MockTournament) {
+        let ranking: Vec<String> = self
+            .get_standings(tournament)
+            .into_iter()
+            .filter(|standing| !standing.withdrawn)
+            .map(|standing| standing.name)
+            .collect();
+        let pairs = match tournament.format {
+            TournamentFormat::Swiss => tournament.pair_swiss(ranking),
+            TournamentFormat::RoundRobin => {
+                tournament.pair_round_robin(tournament.current_round + 1)
+            }
+            TournamentFormat::Arena => {
+                let waiting: Vec<String> = ranking
+                    .into_iter()
+                    .filter(|player| {
+                        !tournament.pairings.iter().any(|pairing| {
+                            pairing.includes(player) && self.get_pairing_result(pairing).is_none()
+                        })
+                    })
+                    .collect();
+                let mut pairs = tournament.pair_swiss(waiting);
+                // Arenas have no byes, the player simply waits for the next pairing
+                pairs.retain(|(_, black)| black.is_some());
+                pairs
+            }
+        };
+        if pairs.is_empty() {
+            return;
+        }
+
+        tournament.current_round += 1;
+        for (white, black) in pairs {
+            let room = black.as_ref().and_then(|black| {
+                let name = format!("{}, round {}", tournament.name, tournament.current_round);
+                let body = CreateGameBody::new(
+                    Some(name),
+                    false,
+                    tournament.time_control,
+                    ColorPreference::White,
+                );
+                let room = self.create_room(&white, body);
+                self.join_room(black, &room.uuid).ok()
+            });
+            tournament.pairings.push(MockPairing {
+                round: tournament.current_round,
+                white,
+                black,
+                room: room.map(|room| room.uuid),
+            });
+        }
+    }
+
+    /// Ordered by points, ties by name.
+    fn get_standings(&self, tournament: &MockTournament) -> Vec<Standing> {
+        let win_points = match tournament.format {
+            TournamentFormat::Arena => ARENA_WIN_POINTS,
+            _ => 1.0,
+        };
+        let mut standings: Vec<Standing> = tournament
+            .players
+            .iter()
+            .map(|player| {
+                let mut standing = Standing {
+                    rank: 0,
+                    name: player.clone(),
+                    points: 0.0,
+                    games: 0,
+                    withdrawn: tournament.withdrawn.contains(player),
+                };
+                for pairing in tournament
+                    .pairings
+                    .iter()
+                    .filter(|pairing| pairing.includes(player))
+                {
+                    if pairing.black.is_none() {
+                        standing.points += win_points;
+                        continue;
+                    }
+                    let Some(result) = self.get_pairing_result(pairing) else {
+                        continue;
+                    };
+                    let is_white = &pairing.white == player;
+                    standing.games += 1;
+                    standing.points += match result.as_str() {
+                        "1-0" if is_white => win_points,
+                        "0-1" if !is_white => win_points,
+                        "1/2-1/2" => win_points / 2.0,
+                        _ => 0.0,
+                    };
+                }
+                standing
+            })
+            .collect();
+        standings.sort_by(|a, b| {
+            b.points
+                .total_cmp(&a.points)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        for (index, standing) in standings.iter_mut().enumerate() {
+            standing.rank = index as i64 + 1;
+        }
+        standings
+    }
+
+    fn get_tournament_summary(
+        &self,
+        tournament: &MockTournament,
+        username: &str,
+    ) -> TournamentSummary {
+        TournamentSummary {
+            uuid: tournament.uuid.clone(),
+            name: tournament.name.clone(),
+            format: tournament.format,
+            status: tournament.status,
+            time_micros: tournament
+                .time_control
+                .map(|time_control| time_control.time_micros()),
+            increment_micros: tournament
+                .time_control
+                .and_then(|time_control| time_control.increment_micros()),
+            rounds: tournament.rounds,
+            current_round: tournament.current_round,
+            player_count: tournament.active_players().len() as u32,
+            joined: tournament.is_active_player(username),
+            starts_at: Some(tournament.starts_at.timestamp()),
+            ends_at: tournament.ends_at.map(|ends_at| ends_at.timestamp()),
+        }
+    }
+
+    /// Running tournaments first, then upcoming ones by their start and finished ones.
+    pub fn list_tournaments(&mut self, username: &str) -> Vec<TournamentSummary> {
+        self.update_tournaments();
+        let mut tournaments: Vec<&MockTournament> = self.tournaments.values().collect();
+        let status_order = |status: TournamentStatus| match status {
+            TournamentStatus::Running => 0,
+            TournamentStatus::Upcoming => 1,
+            TournamentStatus::Finished => 2,
+        };
+        tournaments.sort_by(|a, b| {
+            status_order(a.status)
+                .cmp(&status_order(b.status))
+                .then_with(|| a.starts_at.cmp(&b.starts_at))
+                .then_with(|| a.uuid.cmp(&b.uuid))
+        });
+        tournaments
+            .into_iter()
+            .map(|tournament| self.get_tournament_summary(tournament, username))
+            .collect()
+    }
+
+    pub fn get_tournament(&mut self, username: &str, uuid: &str) -> Option<Tournament> {
+        self.update_tournaments();
+        let tournament = self.tournaments.get(uuid)?;
+        let mut pairings: Vec<Pairing> = tournament
+            .pairings
+            .iter()
+            .map(|pairing| Pairing {
+                round: pairing.round,
+                white: PublicUserInfo {
+                    name: pairing.white.clone(),
+                },
+                black: pairing.black.clone().map(|name| PublicUserInfo { name }),
+                room_uuid: pairing.room.clone(),
+                result: self.get_pairing_result(pairing),
+            })
+            .collect();
+        pairings.sort_by_key(|pairing| Reverse(pairing.round));
+        let own_game = tournament
+            .pairings
+            .iter()
+            .filter(|pairing| {
+                pairing.includes(username) && self.get_pairing_result(pairing).is_none()
+            })
+            .find_map(|pairing| self.rooms.get(pairing.room.as_ref()?))
+            .map(|room| room.info.clone());
+
+        Some(Tournament {
+            summary: self.get_tournament_summary(tournament, username),
+            standings: self.get_standings(tournament),
+            pairings,
+            own_game,
+        })
+    }
+
+    pub fn join_tournament(&mut self, username: &str, uuid: &str) -> Result<(), TournamentError> {
+        self.update_tournaments();
+        let tournament = self
+            .tournaments
+            .get_mut(uuid)
+            .ok_or(TournamentError::NotFound)?;
+        let is_open = match tournament.status {
+            TournamentStatus::Upcoming => true,
+            TournamentStatus::Running => tournament.format == TournamentFormat::Arena,
+            TournamentStatus::Finished => false,
+        };
+        if !is_open {
+            return Err(TournamentError::Rejected(
+                "The tournament can't be joined anymore".to_string(),
+            ));
+        }
+        tournament.withdrawn.remove(username);
+        if !tournament.players.iter().any(|player| player == username) {
+            tournament.players.push(username.to_string());
+        }
+        Ok(())
+    }
+
+    /// Players leave upcoming tournaments entirely, running ones keep their points.
+    pub fn withdraw_from_tournament(
+        &mut self,
+        username: &str,
+        uuid: &str,
+    ) -> Result<(), TournamentError> {
+        self.update_tournaments();
+        let tournament = self
+            .tournaments
+            .get_mut(uuid)
+            .ok_or(TournamentError::NotFound)?;
+        if !tournament.is_active_player(username) {
+            return Err(TournamentError::Rejected(
+                "You are not taking part in this tournament".to_string(),
+            ));
+        }
+        match tournament.status {
+            TournamentStatus::Upcoming => tournament.players.retain(|player| player != username),
+            TournamentStatus::Running => {
+                tournament.withdrawn.insert(username.to_string());
+            }
+            TournamentStatus::Finished => {
+                return Err(TournamentError::Rejected(
+                    "The tournament is already over".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinError {
+    NotFound,
+    Full,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocialError {
+    NotFound,
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountError {
+    WrongPassword,
+    UsernameTaken,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TournamentError {
+    NotFound,
+    Rejected(String),
+}
+
+fn friend_pair(username: &str, other: &str) -> (String, String) {
+    if username < other {
+        (username.to_string(), other.to_string())
+    } else {
+        (other.to_string(), username.to_string())
+    }
+}
+
+/// Puts the player on the preferred side, or any free one if it is taken.
+fn seat_player(info: &mut PrivateRoomInfo, username: &str, color: ColorPreference) {
+    let player = Some(PublicUserInfo {
+        name: username.to_string(),
+    });
+    let prefers_white = match color {
+        ColorPreference::White => true,
+        ColorPreference::Black => false,
+        ColorPreference::Random => Color::random() == Color::White,
+    };
+
+    if (prefers_white || info.black.is_some()) && info.white.is_none() {
+        info.white = player;
+    } else {
+        info.black = player;
+    }
+}
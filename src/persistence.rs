@@ -1,9 +1,15 @@
 use crate::app::GigaChessAppPersist;
-use directories::ProjectDirs;
+use crate::persistence::error::PersistenceResult;
+use directories::{ProjectDirs, UserDirs};
 use log::error;
-use std::path::PathBuf;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 pub mod color;
+pub mod error;
+pub mod profile;
 
 pub trait PersistentObject {
     type PersistentType;
@@ -12,25 +18,37 @@ pub trait PersistentObject {
     fn load_from_state(state: Self::PersistentType) -> Self;
 }
 
+static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Replaces the platform's data directory, has to be called before anything is loaded or saved.
+pub fn set_data_dir_override(directory: PathBuf) {
+    if DATA_DIR_OVERRIDE.set(directory).is_err() {
+        error!("Data directory override was already set");
+    }
+}
+
 fn get_project_dirs() -> ProjectDirs {
     ProjectDirs::from("io.github", "zitronenjoghurt", "giga-chess").unwrap()
 }
 
-fn get_save_dir() -> PathBuf {
-    get_project_dirs().data_dir().to_path_buf()
+pub fn get_data_dir() -> PathBuf {
+    match DATA_DIR_OVERRIDE.get() {
+        Some(directory) => directory.clone(),
+        None => get_project_dirs().data_dir().to_path_buf(),
+    }
 }
 
-fn get_save_file_path() -> PathBuf {
-    get_save_dir().join("save.json")
+fn get_save_file_path(profile: &str) -> PathBuf {
+    profile::get_profile_dir(profile).join("save.json")
 }
 
-pub fn persist(state: GigaChessAppPersist) {
-    let directory = get_save_dir();
+pub fn persist(profile: &str, state: GigaChessAppPersist) {
+    let directory = profile::get_profile_dir(profile);
     if !directory.exists() {
         std::fs::create_dir_all(&directory).unwrap();
     }
 
-    let save_path = get_save_file_path();
+    let save_path = get_save_file_path(profile);
     let Ok(data) = serde_json::to_string_pretty(&state) else {
         error!("Failed to serialize app state to JSON");
         return;
@@ -42,8 +60,8 @@ pub fn persist(state: GigaChessAppPersist) {
     }
 }
 
-pub fn restore() -> Option<GigaChessAppPersist> {
-    let save_path = get_save_file_path();
+pub fn restore(profile: &str) -> Option<GigaChessAppPersist> {
+    let save_path = get_save_file_path(profile);
     if !save_path.exists() {
         return None;
     }
@@ -58,3 +76,26 @@ pub fn restore() -> Option<GigaChessAppPersist> {
 
     Some(state)
 }
+
+pub fn export_to_file<T: Serialize>(path: &Path, value: &T) -> PersistenceResult<()> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+
+    let data = serde_json::to_string_pretty(value)?;
+    std::fs::write(path, data)?;
+    Ok(())
+}
+
+pub fn import_from_file<T: DeserializeOwned>(path: &Path) -> PersistenceResult<T> {
+    let data = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+/// Suggests a location outside the data directory for files meant to be moved between machines.
+pub fn get_default_export_path(file_name: &str) -> PathBuf {
+    let directory = UserDirs::new()
+        .and_then(|dirs| dirs.document_dir().map(Path::to_path_buf))
+        .unwrap_or_else(get_data_dir);
+    directory.join(file_name)
+}
@@ -2,9 +2,15 @@ use crate::persistence::PersistentObject;
 use egui::Color32;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Color32Persist(u32);
 
+impl Color32Persist {
+    pub fn to_hex(self) -> String {
+        format!("#{:08x}", self.0)
+    }
+}
+
 impl PersistentObject for Color32 {
     type PersistentType = Color32Persist;
 
@@ -22,7 +28,7 @@ impl PersistentObject for Color32 {
             (state.0 >> 24) as u8,
             (state.0 >> 16) as u8,
             (state.0 >> 8) as u8,
-            (state.0 >> 0) as u8,
+            state.0 as u8,
         )
     }
 }
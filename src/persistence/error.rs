@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+pub type PersistenceResult<T> = Result<T, PersistenceError>;
+
+#[derive(Debug, Error)]
+pub enum PersistenceError {
+    #[error("Profile '{0}' already exists")]
+    ProfileAlreadyExists(String),
+    #[error("Profile '{0}' does not exist")]
+    ProfileNotFound(String),
+    #[error("Invalid profile name: {0}")]
+    InvalidProfileName(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
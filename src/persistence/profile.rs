@@ -0,0 +1,156 @@
+use crate::persistence::error::{PersistenceError, PersistenceResult};
+use crate::persistence::get_data_dir;
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub const DEFAULT_PROFILE: &str = "Default";
+const MAX_PROFILE_NAME_LENGTH: usize = 32;
+
+/// Remembers which profile was used last, so it can be restored on the next startup.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfileIndex {
+    last_profile: Option<String>,
+}
+
+fn get_profiles_dir() -> PathBuf {
+    get_data_dir().join("profiles")
+}
+
+fn get_profile_index_path() -> PathBuf {
+    get_data_dir().join("profiles.json")
+}
+
+pub fn get_profile_dir(name: &str) -> PathBuf {
+    get_profiles_dir().join(name)
+}
+
+/// Profile names are used as directory names, so they are restricted to a safe subset of characters.
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && name.trim() == name
+        && name.chars().count() <= MAX_PROFILE_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+}
+
+pub fn profile_exists(name: &str) -> bool {
+    get_profile_dir(name).is_dir()
+}
+
+pub fn list_profiles() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(get_profiles_dir()) else {
+        return Vec::new();
+    };
+
+    let mut profiles: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    profiles.sort_by_key(|name| name.to_lowercase());
+    profiles
+}
+
+pub fn create_profile(name: &str) -> PersistenceResult<()> {
+    if !is_valid_profile_name(name) {
+        return Err(PersistenceError::InvalidProfileName(name.to_string()));
+    }
+    if profile_exists(name) {
+        return Err(PersistenceError::ProfileAlreadyExists(name.to_string()));
+    }
+
+    std::fs::create_dir_all(get_profile_dir(name))?;
+    Ok(())
+}
+
+pub fn ensure_profile(name: &str) {
+    if let Err(e) = std::fs::create_dir_all(get_profile_dir(name)) {
+        error!("Failed to create profile directory: {e}");
+    }
+}
+
+pub fn rename_profile(old_name: &str, new_name: &str) -> PersistenceResult<()> {
+    if !is_valid_profile_name(new_name) {
+        return Err(PersistenceError::InvalidProfileName(new_name.to_string()));
+    }
+    if !profile_exists(old_name) {
+        return Err(PersistenceError::ProfileNotFound(old_name.to_string()));
+    }
+    if profile_exists(new_name) {
+        return Err(PersistenceError::ProfileAlreadyExists(new_name.to_string()));
+    }
+
+    std::fs::rename(get_profile_dir(old_name), get_profile_dir(new_name))?;
+    if get_last_profile() == old_name {
+        set_last_profile(new_name);
+    }
+    Ok(())
+}
+
+pub fn delete_profile(name: &str) -> PersistenceResult<()> {
+    if !profile_exists(name) {
+        return Err(PersistenceError::ProfileNotFound(name.to_string()));
+    }
+
+    std::fs::remove_dir_all(get_profile_dir(name))?;
+    Ok(())
+}
+
+/// Returns the profile used last, falling back to the default profile if it does not exist anymore.
+pub fn get_last_profile() -> String {
+    let index: ProfileIndex = std::fs::read_to_string(get_profile_index_path())
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default();
+
+    match index.last_profile {
+        Some(profile) if profile_exists(&profile) => profile,
+        _ => DEFAULT_PROFILE.to_string(),
+    }
+}
+
+pub fn set_last_profile(name: &str) {
+    let index = ProfileIndex {
+        last_profile: Some(name.to_string()),
+    };
+
+    let Ok(data) = serde_json::to_string_pretty(&index) else {
+        error!("Failed to serialize profile index to JSON");
+        return;
+    };
+
+    let directory = get_data_dir();
+    if let Err(e) = std::fs::create_dir_all(&directory) {
+        error!("Failed to create save directory: {e}");
+        return;
+    }
+
+    if let Err(e) = std::fs::write(get_profile_index_path(), data) {
+        error!("Failed to write profile index: {e}");
+    }
+}
+
+/// Moves a save file from before profiles existed into the default profile.
+pub fn migrate_legacy_save() {
+    let legacy_save_path = get_data_dir().join("save.json");
+    if !legacy_save_path.exists() {
+        return;
+    }
+
+    let profile_dir = get_profile_dir(DEFAULT_PROFILE);
+    let target_path = profile_dir.join("save.json");
+    if target_path.exists() {
+        return;
+    }
+
+    if let Err(e) = std::fs::create_dir_all(&profile_dir) {
+        error!("Failed to create default profile directory: {e}");
+        return;
+    }
+
+    if let Err(e) = std::fs::rename(legacy_save_path, target_path) {
+        error!("Failed to migrate legacy save file: {e}");
+    }
+}
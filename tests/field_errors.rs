@@ -0,0 +1,47 @@
+use giga_chess_desktop::api::error::field_errors::FieldErrors;
+
+#[test]
+fn parses_validator_errors() {
+    let body = r#"{
+        "password": [{"code": "length", "message": null, "params": {"min": 8, "max": 100, "value": "short"}}],
+        "username": [{"code": "must be alphanumeric", "message": null, "params": {"value": "a!"}}]
+    }"#;
+
+    let field_errors = FieldErrors::parse(body).unwrap();
+    assert_eq!(
+        field_errors.get("password"),
+        Some(&["Must be between 8 and 100 characters long.".to_string()][..])
+    );
+    assert_eq!(
+        field_errors.get("username"),
+        Some(&["Must be alphanumeric.".to_string()][..])
+    );
+}
+
+#[test]
+fn parses_nested_errors_and_prefers_messages() {
+    let body = r#"{
+        "message": "Validation failed",
+        "errors": {
+            "username": ["Already taken"],
+            "invite_code": [{"code": "invalid", "message": "Unknown invite code", "params": {}}]
+        }
+    }"#;
+
+    let field_errors = FieldErrors::parse(body).unwrap();
+    assert_eq!(
+        field_errors.get("username"),
+        Some(&["Already taken".to_string()][..])
+    );
+    assert_eq!(
+        field_errors.get("invite_code"),
+        Some(&["Unknown invite code".to_string()][..])
+    );
+}
+
+#[test]
+fn ignores_bodies_without_field_errors() {
+    assert_eq!(FieldErrors::parse("Invalid invite code"), None);
+    assert_eq!(FieldErrors::parse(r#"{"message": "Bad request"}"#), None);
+    assert_eq!(FieldErrors::parse("[]"), None);
+}
@@ -0,0 +1,87 @@
+use giga_chess::prelude::*;
+use giga_chess_desktop::game::import::{
+    game_from_fen, game_from_file, game_from_pgn, GameImportError,
+};
+use std::sync::Arc;
+
+const CASTLING_FEN: &str = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+
+fn engine() -> Arc<Engine> {
+    Engine::initialize()
+}
+
+fn board_fen(game: &Game) -> String {
+    let fen = game.get_fen_string();
+    fen.split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[test]
+fn fen_is_imported() {
+    let engine = engine();
+    let game = game_from_fen(&engine, &format!("  {CASTLING_FEN}\n")).unwrap();
+    assert_eq!(board_fen(&game), "r3k2r/8/8/8/8/8/8/R3K2R");
+    assert_eq!(game.side_to_move(), Color::White);
+
+    let result = game_from_fen(&engine, "not a fen");
+    assert!(matches!(result, Err(GameImportError::InvalidFen(_))));
+}
+
+#[test]
+fn pgn_ignores_comments_variations_and_annotations() {
+    let engine = engine();
+    let pgn = r#"[Event "Casual game"]
+[White "alice"]
+[Black "bob"]
+
+1. e4 {best by test} e5 2. Nf3!? (2. f4 exf4) Nc6 $1 ; the main line
+3. Bb5 a6 1/2-1/2"#;
+    let game = game_from_pgn(&engine, pgn).unwrap();
+    assert_eq!(game.move_history().len(), 6);
+    assert_eq!(
+        board_fen(&game),
+        "r1bqkbnr/1ppp1ppp/p1n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R"
+    );
+}
+
+#[test]
+fn castling_written_with_zeros_is_imported() {
+    let engine = engine();
+    let pgn = format!("[FEN \"{CASTLING_FEN}\"]\n\n1. 0-0-0 0-0+ *");
+    let game = game_from_pgn(&engine, &pgn).unwrap();
+    assert_eq!(board_fen(&game), "r4rk1/8/8/8/8/8/8/2KR3R");
+
+    let pgn = format!("[FEN \"{CASTLING_FEN}\"]\n\n1. O-O O-O-O *");
+    let game = game_from_pgn(&engine, &pgn).unwrap();
+    assert_eq!(board_fen(&game), "2kr3r/8/8/8/8/8/8/R4RK1");
+}
+
+#[test]
+fn illegal_moves_are_rejected() {
+    let engine = engine();
+    let result = game_from_pgn(&engine, "1. e4 e5 2. Ke3 *");
+    assert!(matches!(result, Err(GameImportError::IllegalMove(token)) if token == "Ke3"));
+}
+
+#[test]
+fn file_format_is_guessed_from_the_content() {
+    let engine = engine();
+    let dir = std::env::temp_dir().join(format!("giga-chess-import-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let fen_path = dir.join("position.txt");
+    std::fs::write(&fen_path, CASTLING_FEN).unwrap();
+    let game = game_from_file(&engine, &fen_path).unwrap();
+    assert_eq!(board_fen(&game), "r3k2r/8/8/8/8/8/8/R3K2R");
+
+    let pgn_path = dir.join("game.txt");
+    std::fs::write(&pgn_path, "1. d4 d5 *").unwrap();
+    let game = game_from_file(&engine, &pgn_path).unwrap();
+    assert_eq!(game.move_history().len(), 2);
+
+    let result = game_from_file(&engine, &dir.join("missing.pgn"));
+    assert!(matches!(result, Err(GameImportError::Io(_))));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,1218 @@
+use giga_chess::prelude::*;
+use giga_chess_desktop::api::account::{ChangePasswordBody, ChangeUsernameBody, DeleteAccountBody};
+use giga_chess_desktop::api::correspondence::{CorrespondenceBody, MICROS_PER_DAY};
+use giga_chess_desktop::api::error::{ApiError, ApiResult};
+use giga_chess_desktop::api::friends::{ChallengeBody, ChallengeStatus, Presence};
+use giga_chess_desktop::api::invite_codes::{InviteCode, InviteCodeBody, InviteCodeStatus};
+use giga_chess_desktop::api::live::{ClientMessage, LiveEvent, LiveTransport, ServerMessage};
+use giga_chess_desktop::api::login::LoginState;
+use giga_chess_desktop::api::matchmaking::{
+    ColorPreference, CreateGameBody, QueueBody, TimeControl,
+};
+use giga_chess_desktop::api::network::{NetworkSettings, ProxyMode};
+use giga_chess_desktop::api::player_profile::{GameOutcome, RatingCategory};
+use giga_chess_desktop::api::request::{LatestRequest, RequestHandle};
+use giga_chess_desktop::api::retry::RetryPolicy;
+use giga_chess_desktop::api::server_info::{Capability, ServerInfoState, API_VERSION};
+use giga_chess_desktop::api::session::Session;
+use giga_chess_desktop::api::tournaments::{TournamentFormat, TournamentStatus};
+use giga_chess_desktop::api::MultiplayerClient;
+use giga_chess_desktop::app::OnlineGame;
+use giga_chess_desktop::game::uci::find_uci_move;
+use giga_chess_desktop::mock_server::routes::MOCK_SERVER_VERSION;
+use giga_chess_desktop::mock_server::MockServer;
+use giga_chess_desktop::persistence::PersistentObject;
+use reqwest::StatusCode;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn new_client(server: &MockServer) -> MultiplayerClient {
+    let mut client = MultiplayerClient::default();
+    client.set_server_url(server.url());
+    client.set_retry_policy(RetryPolicy {
+        initial_backoff_millis: 10,
+        max_backoff_millis: 50,
+        ..Default::default()
+    });
+    client
+}
+
+/// Issues a request and blocks until its callback was called.
+fn wait_for<T, R>(request: R) -> ApiResult<T>
+where
+    T: Send + 'static,
+    R: FnOnce(Box<dyn FnOnce(ApiResult<T>) + Send>) -> RequestHandle,
+{
+    let (sender, receiver) = mpsc::channel();
+    request(Box::new(move |result| {
+        let _ = sender.send(result);
+    }));
+    receiver
+        .recv_timeout(TIMEOUT)
+        .expect("The request did not finish in time")
+}
+
+/// Registers the user on the server and stores the resulting session in the client.
+fn log_in(server: &MockServer, client: &MultiplayerClient, username: &str, remember: bool) {
+    server
+        .state()
+        .lock()
+        .unwrap()
+        .register(username, "password");
+    let session = if remember {
+        let response = wait_for(|callback| client.login_remembered(username, "password", callback))
+            .expect("Login failed");
+        Session::new(username, response.login, Some(response.refresh_token))
+    } else {
+        let response = wait_for(|callback| client.login(username, "password", callback))
+            .expect("Login failed");
+        Session::new(username, response, None)
+    };
+    client.session().set(LoginState::Success(session));
+}
+
+fn current_token(client: &MultiplayerClient) -> Option<String> {
+    client
+        .session()
+        .lock()
+        .get_session()
+        .map(|session| session.token.clone())
+}
+
+#[test]
+fn register_login_and_ping() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+
+    let response = wait_for(|callback| client.register("alice", "password", "invite", callback))
+        .expect("Registration failed");
+    assert!(!response.token.is_empty());
+
+    let result = wait_for(|callback| client.register("alice", "password", "invite", callback));
+    assert!(matches!(result, Err(ApiError::Collision(_))));
+
+    let response =
+        wait_for(|callback| client.login("alice", "password", callback)).expect("Login failed");
+    client
+        .session()
+        .set(LoginState::Success(Session::new("alice", response, None)));
+    wait_for(|callback| client.ping(callback)).expect("Ping failed");
+}
+
+#[test]
+fn invalid_registration_reports_field_errors() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+
+    let result = wait_for(|callback| client.register("a!", "password", "invite", callback));
+    let Err(ApiError::Validation(field_errors)) = result else {
+        panic!("Expected field errors, got {result:?}");
+    };
+    let username_errors = field_errors.get("username").unwrap();
+    assert!(username_errors.contains(&"Must be between 3 and 50 characters long.".to_string()));
+    assert!(username_errors.contains(&"Must be alphanumeric.".to_string()));
+    assert!(field_errors.get("password").is_none());
+}
+
+#[test]
+fn health_check_reports_version_and_failures() {
+    let server = MockServer::start().unwrap();
+    let client = MultiplayerClient::default();
+
+    let report = wait_for(|callback| client.check_health(server.url(), callback))
+        .expect("Health check failed");
+    assert_eq!(report.version.as_deref(), Some(MOCK_SERVER_VERSION));
+
+    let unreachable_url = server.url().to_string();
+    drop(server);
+    let result = wait_for(|callback| client.check_health(&unreachable_url, callback));
+    assert!(result.is_err());
+}
+
+#[test]
+fn handshake_reports_compatible_server() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    assert_eq!(client.server_info(), ServerInfoState::Unknown);
+
+    let state =
+        wait_for(|callback| client.check_server(move |state| callback(Ok(state.clone())))).unwrap();
+    let ServerInfoState::Compatible(info) = state else {
+        panic!("Expected a compatible server, got {state:?}");
+    };
+    assert_eq!(info.api_version, API_VERSION);
+    assert!(info.supports(Capability::LiveWebsocket));
+    assert_eq!(client.get_effective_live_transport(), LiveTransport::Auto);
+}
+
+#[test]
+fn login_with_wrong_password_is_rejected() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    server.state().lock().unwrap().register("alice", "password");
+
+    let result = wait_for(|callback| client.login("alice", "wrong", callback));
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+}
+
+#[test]
+fn server_url_override_is_not_saved() {
+    let server = MockServer::start().unwrap();
+    let mut client = new_client(&server);
+    client.set_server_url("https://chess.example.com");
+    client.set_server_url_override(Some(server.url().to_string()));
+    log_in(&server, &client, "alice", false);
+
+    wait_for(|callback| client.ping(callback)).expect("Ping against the override failed");
+    let saved = client.save_state();
+    assert_eq!(
+        saved.server_url.as_deref(),
+        Some("https://chess.example.com")
+    );
+
+    // Choosing a server ends the override
+    client.set_server_url("https://other.example.com");
+    assert_eq!(client.get_server_url(), Some("https://other.example.com"));
+    assert!(client.get_server_url_override().is_none());
+}
+
+#[test]
+fn rejected_session_expires_without_refresh_token() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    log_in(&server, &client, "alice", false);
+
+    server.state().lock().unwrap().revoke_tokens();
+    let result = wait_for(|callback| client.ping(callback));
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+    assert!(matches!(*client.session().lock(), LoginState::Expired));
+}
+
+#[test]
+fn rejected_session_is_refreshed_with_refresh_token() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    log_in(&server, &client, "alice", true);
+    let old_token = current_token(&client);
+
+    server.state().lock().unwrap().revoke_tokens();
+    wait_for(|callback| client.ping(callback)).expect("Ping after refresh failed");
+
+    let new_token = current_token(&client);
+    assert!(new_token.is_some());
+    assert_ne!(old_token, new_token);
+
+    server.state().lock().unwrap().revoke_tokens();
+    server.state().lock().unwrap().revoke_refresh_tokens();
+    let result = wait_for(|callback| client.ping(callback));
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+    assert!(matches!(*client.session().lock(), LoginState::Expired));
+}
+
+#[test]
+fn live_connection_survives_repeated_expiries() {
+    let server = MockServer::start().unwrap();
+    let mut alice = new_client(&server);
+    let bob = new_client(&server);
+    alice.set_live_transport(LiveTransport::Polling);
+    log_in(&server, &alice, "alice", true);
+    log_in(&server, &bob, "bob", false);
+
+    let body = CreateGameBody::new(None, false, None, ColorPreference::White);
+    let room = wait_for(|callback| alice.create_game(body, callback)).expect("Create failed");
+    wait_for(|callback| bob.join_game(room.uuid.clone(), callback)).expect("Join failed");
+
+    let connection = alice.connect_live(&room.uuid, || {}).unwrap();
+    wait_for_message(&connection, |message| {
+        matches!(message, ServerMessage::Sync { .. })
+    });
+
+    // Every rejected request refreshes the session and reconnects with a full sync
+    for _ in 0..2 {
+        let old_token = current_token(&alice);
+        server.state().lock().unwrap().revoke_tokens();
+        connection.send(ClientMessage::Chat {
+            text: "Hello".to_string(),
+        });
+        wait_for_message(&connection, |message| {
+            matches!(message, ServerMessage::Sync { .. })
+        });
+        assert_ne!(current_token(&alice), old_token);
+    }
+}
+
+#[test]
+fn logout_revokes_the_refresh_token() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    log_in(&server, &client, "alice", true);
+    let session = client.session().lock().get_session().cloned().unwrap();
+
+    wait_for(|callback| client.logout(callback)).expect("Logout failed");
+    client.session().set(LoginState::Idle);
+
+    // Neither the token nor the refresh token of the old session work anymore
+    client.session().set(LoginState::Success(session));
+    let result = wait_for(|callback| client.ping(callback));
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+    assert!(matches!(*client.session().lock(), LoginState::Expired));
+}
+
+#[test]
+fn refresh_does_not_restore_a_logged_out_session() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    log_in(&server, &client, "alice", true);
+
+    server.state().lock().unwrap().revoke_tokens();
+    server.state().lock().unwrap().response_delay = Some(Duration::from_millis(300));
+    let (sender, receiver) = mpsc::channel();
+    client.ping(move |result| {
+        let _ = sender.send(result);
+    });
+    client.session().set(LoginState::Idle);
+
+    let result = receiver.recv_timeout(TIMEOUT).unwrap();
+    assert!(result.is_err());
+    assert!(matches!(*client.session().lock(), LoginState::Idle));
+}
+
+#[test]
+fn requests_are_routed_through_the_proxy() {
+    let server = MockServer::start().unwrap();
+    let mut client = MultiplayerClient::default();
+    client
+        .set_network_settings(NetworkSettings {
+            proxy_mode: ProxyMode::Custom,
+            proxy_url: server.url().to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+    // The host doesn't exist, only the proxy can answer
+    let (sender, receiver) = mpsc::channel();
+    client.check_health("http://giga-chess.invalid", move |result| {
+        let _ = sender.send(result);
+    });
+    let report = receiver
+        .recv_timeout(TIMEOUT)
+        .unwrap()
+        .expect("Request didn't reach the proxy");
+    assert_eq!(report.version.as_deref(), Some(MOCK_SERVER_VERSION));
+}
+
+#[test]
+fn idempotent_requests_are_retried() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    log_in(&server, &client, "alice", false);
+
+    server
+        .state()
+        .lock()
+        .unwrap()
+        .fail_next_requests(2, StatusCode::SERVICE_UNAVAILABLE);
+    wait_for(|callback| client.list_open_games(1, 20, callback)).expect("Retries failed");
+    assert!(client.retry_status().is_none());
+
+    server
+        .state()
+        .lock()
+        .unwrap()
+        .fail_next_requests(4, StatusCode::SERVICE_UNAVAILABLE);
+    let result = wait_for(|callback| client.list_open_games(1, 20, callback));
+    assert!(matches!(result, Err(ApiError::ServiceUnavailable { .. })));
+}
+
+#[test]
+fn cancelled_retries_clear_the_retry_status() {
+    let server = MockServer::start().unwrap();
+    let mut client = new_client(&server);
+    client.set_retry_policy(RetryPolicy {
+        initial_backoff_millis: 5_000,
+        max_backoff_millis: 5_000,
+        ..Default::default()
+    });
+    log_in(&server, &client, "alice", false);
+
+    // Nothing listens there, so the request waits for its next attempt
+    client.set_server_url("http://127.0.0.1:9");
+    let handle = client.list_open_games(1, 20, |_| {});
+    let deadline = Instant::now() + TIMEOUT;
+    while client.retry_status().is_none() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(client.retry_status().is_some());
+
+    handle.cancel();
+    let deadline = Instant::now() + TIMEOUT;
+    while client.retry_status().is_some() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(client.retry_status().is_none());
+}
+
+#[test]
+fn non_idempotent_requests_are_only_retried_when_rate_limited() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    log_in(&server, &client, "alice", false);
+
+    server
+        .state()
+        .lock()
+        .unwrap()
+        .fail_next_requests(1, StatusCode::SERVICE_UNAVAILABLE);
+    let result = wait_for(|callback| client.ping(callback));
+    assert!(matches!(result, Err(ApiError::ServiceUnavailable { .. })));
+
+    server
+        .state()
+        .lock()
+        .unwrap()
+        .fail_next_requests(2, StatusCode::TOO_MANY_REQUESTS);
+    wait_for(|callback| client.ping(callback)).expect("Rate limited ping was not retried");
+}
+
+#[test]
+fn identical_concurrent_requests_are_deduplicated() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    log_in(&server, &client, "alice", false);
+    server.state().lock().unwrap().response_delay = Some(Duration::from_millis(300));
+
+    // Requests of the same caller share their callback type
+    let (sender, receiver) = mpsc::channel();
+    let list_open_games = |page| {
+        let sender = sender.clone();
+        client.list_open_games(page, 20, move |result| {
+            let _ = sender.send(result.is_ok());
+        })
+    };
+    let first = list_open_games(1);
+    let second = list_open_games(1);
+    let other_page = list_open_games(2);
+
+    assert!(first.is_same(&second));
+    assert!(!first.is_same(&other_page));
+    assert_eq!(receiver.recv_timeout(TIMEOUT), Ok(true));
+    assert_eq!(receiver.recv_timeout(TIMEOUT), Ok(true));
+    assert!(receiver.recv_timeout(Duration::from_millis(500)).is_err());
+}
+
+#[test]
+fn identical_requests_of_different_callers_are_sent_separately() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    log_in(&server, &client, "alice", false);
+    server.state().lock().unwrap().response_delay = Some(Duration::from_millis(300));
+
+    let (first_sender, first_receiver) = mpsc::channel();
+    let first = client.list_open_games(1, 20, move |result| {
+        let _ = first_sender.send(result.is_ok());
+    });
+    let (second_sender, second_receiver) = mpsc::channel();
+    let second = client.list_open_games(1, 20, move |result| {
+        let _ = second_sender.send(result.is_ok());
+    });
+    assert!(!first.is_same(&second));
+
+    // Cancelling one caller's request leaves the other one running
+    first.cancel();
+    assert_eq!(second_receiver.recv_timeout(TIMEOUT), Ok(true));
+    assert!(first_receiver
+        .recv_timeout(Duration::from_millis(500))
+        .is_err());
+}
+
+#[test]
+fn latest_request_cancels_the_previous_one() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    log_in(&server, &client, "alice", false);
+    server.state().lock().unwrap().response_delay = Some(Duration::from_millis(300));
+
+    let (sender, receiver) = mpsc::channel();
+    let list_open_games = |page| {
+        let sender = sender.clone();
+        client.list_open_games(page, 20, move |result| {
+            let _ = sender.send((page, result.is_ok()));
+        })
+    };
+    let mut latest = LatestRequest::default();
+    latest.replace(list_open_games(1));
+    // The same request again keeps running
+    latest.replace(list_open_games(1));
+    latest.replace(list_open_games(2));
+
+    assert_eq!(receiver.recv_timeout(TIMEOUT), Ok((2, true)));
+    assert!(receiver.recv_timeout(Duration::from_millis(500)).is_err());
+}
+
+#[test]
+fn cancelled_requests_drop_their_callback() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    log_in(&server, &client, "alice", false);
+    server.state().lock().unwrap().response_delay = Some(Duration::from_millis(300));
+
+    let (sender, receiver) = mpsc::channel();
+    let request = client.ping(move |result| {
+        let _ = sender.send(result.is_ok());
+    });
+    assert!(!request.is_finished());
+
+    request.cancel();
+    assert!(request.is_finished());
+    assert!(receiver.recv_timeout(Duration::from_millis(600)).is_err());
+
+    // A cancelled request no longer blocks identical ones
+    wait_for(|callback| client.ping(callback)).expect("Ping after cancelling failed");
+}
+
+#[test]
+fn create_list_and_join_game() {
+    let server = MockServer::start().unwrap();
+    let alice = new_client(&server);
+    let bob = new_client(&server);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+
+    let body = CreateGameBody::new(
+        Some("Casual".to_string()),
+        true,
+        Some(TimeControl::new(5, 0)),
+        ColorPreference::White,
+    );
+    let room = wait_for(|callback| alice.create_game(body, callback)).expect("Create failed");
+    assert_eq!(room.white.as_ref().map(|p| p.name.as_str()), Some("alice"));
+
+    let list = wait_for(|callback| bob.list_open_games(1, 20, callback)).expect("List failed");
+    assert_eq!(list.rooms.len(), 1);
+    assert_eq!(list.rooms[0].uuid, room.uuid);
+
+    let joined =
+        wait_for(|callback| bob.join_game(room.uuid.clone(), callback)).expect("Join failed");
+    assert_eq!(joined.black.as_ref().map(|p| p.name.as_str()), Some("bob"));
+
+    let list = wait_for(|callback| bob.list_open_games(1, 20, callback)).expect("List failed");
+    assert!(list.rooms.is_empty());
+}
+
+#[test]
+fn quick_match_pairs_compatible_players() {
+    let server = MockServer::start().unwrap();
+    let alice = new_client(&server);
+    let bob = new_client(&server);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+
+    let time_control = Some(TimeControl::new(3, 2));
+    let body = QueueBody::new(time_control, ColorPreference::Random);
+    wait_for(|callback| alice.join_queue(body, callback)).expect("Queueing failed");
+    let status = wait_for(|callback| alice.get_queue_status(callback)).expect("Status failed");
+    assert!(status.room.is_none());
+
+    let body = QueueBody::new(time_control, ColorPreference::Black);
+    wait_for(|callback| bob.join_queue(body, callback)).expect("Queueing failed");
+
+    let alice_room = wait_for(|callback| alice.get_queue_status(callback))
+        .expect("Status failed")
+        .room
+        .expect("Alice was not matched");
+    let bob_room = wait_for(|callback| bob.get_queue_status(callback))
+        .expect("Status failed")
+        .room
+        .expect("Bob was not matched");
+    assert_eq!(alice_room.uuid, bob_room.uuid);
+    assert_eq!(
+        bob_room.black.as_ref().map(|p| p.name.as_str()),
+        Some("bob")
+    );
+}
+
+/// Waits until the connection received a message matching the predicate and returns it.
+fn wait_for_message<P>(
+    connection: &giga_chess_desktop::api::live::LiveConnection,
+    predicate: P,
+) -> ServerMessage
+where
+    P: Fn(&ServerMessage) -> bool,
+{
+    let deadline = Instant::now() + TIMEOUT;
+    while Instant::now() < deadline {
+        for event in connection.poll_events() {
+            match event {
+                LiveEvent::Message(message) if predicate(&message) => return message,
+                LiveEvent::Closed(reason) => panic!("Connection closed: {reason}"),
+                _ => {}
+            }
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    panic!("No matching message was received in time");
+}
+
+fn play_live_game(transport: LiveTransport) {
+    let server = MockServer::start().unwrap();
+    let mut alice = new_client(&server);
+    let mut bob = new_client(&server);
+    alice.set_live_transport(transport);
+    bob.set_live_transport(transport);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+
+    let body = CreateGameBody::new(None, false, None, ColorPreference::White);
+    let room = wait_for(|callback| alice.create_game(body, callback)).expect("Create failed");
+    wait_for(|callback| bob.join_game(room.uuid.clone(), callback)).expect("Join failed");
+
+    let alice_connection = alice.connect_live(&room.uuid, || {}).unwrap();
+    let bob_connection = bob.connect_live(&room.uuid, || {}).unwrap();
+    wait_for_message(&alice_connection, |message| {
+        matches!(message, ServerMessage::Sync { .. })
+    });
+    wait_for_message(&bob_connection, |message| {
+        matches!(message, ServerMessage::Sync { .. })
+    });
+
+    alice_connection.send(ClientMessage::Move {
+        uci: "e2e4".to_string(),
+    });
+    wait_for_message(
+        &bob_connection,
+        |message| matches!(message, ServerMessage::Move { uci, .. } if uci == "e2e4"),
+    );
+
+    // Moving out of turn is only reported to the sender
+    alice_connection.send(ClientMessage::Move {
+        uci: "d2d4".to_string(),
+    });
+    wait_for_message(&alice_connection, |message| {
+        matches!(message, ServerMessage::Error { .. })
+    });
+
+    bob_connection.send(ClientMessage::Resign);
+    wait_for_message(
+        &alice_connection,
+        |message| matches!(message, ServerMessage::GameEnd { result, .. } if result == "1-0"),
+    );
+}
+
+#[test]
+fn live_game_over_websocket() {
+    play_live_game(LiveTransport::WebSocket);
+}
+
+#[test]
+fn live_game_over_long_polling() {
+    play_live_game(LiveTransport::Polling);
+}
+
+fn chat_in_live_game(transport: LiveTransport) {
+    let server = MockServer::start().unwrap();
+    let mut alice = new_client(&server);
+    let mut bob = new_client(&server);
+    alice.set_live_transport(transport);
+    bob.set_live_transport(transport);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+
+    let body = CreateGameBody::new(None, false, None, ColorPreference::White);
+    let room = wait_for(|callback| alice.create_game(body, callback)).expect("Create failed");
+    wait_for(|callback| bob.join_game(room.uuid.clone(), callback)).expect("Join failed");
+
+    let alice_connection = alice.connect_live(&room.uuid, || {}).unwrap();
+    let bob_connection = bob.connect_live(&room.uuid, || {}).unwrap();
+    wait_for_message(&bob_connection, |message| {
+        matches!(message, ServerMessage::Sync { .. })
+    });
+
+    alice_connection.send(ClientMessage::Chat {
+        text: " Good luck ".to_string(),
+    });
+    wait_for_message(
+        &bob_connection,
+        |message| matches!(message, ServerMessage::Chat(chat) if chat.from == "alice" && chat.text == "Good luck"),
+    );
+
+    // Reconnecting clients get the chat history with the sync
+    let late_connection = bob.connect_live(&room.uuid, || {}).unwrap();
+    wait_for_message(
+        &late_connection,
+        |message| matches!(message, ServerMessage::Sync { chat, .. } if chat.len() == 1),
+    );
+
+    for _ in 0..5 {
+        alice_connection.send(ClientMessage::Chat {
+            text: "Hello".to_string(),
+        });
+    }
+    wait_for_message(&alice_connection, |message| {
+        matches!(
+            message,
+            ServerMessage::RateLimited {
+                retry_after_secs: Some(_),
+                ..
+            }
+        )
+    });
+}
+
+#[test]
+fn chat_over_websocket() {
+    chat_in_live_game(LiveTransport::WebSocket);
+}
+
+#[test]
+fn chat_over_long_polling() {
+    chat_in_live_game(LiveTransport::Polling);
+}
+
+fn spectate_live_game(transport: LiveTransport) {
+    let server = MockServer::start().unwrap();
+    let alice = new_client(&server);
+    let bob = new_client(&server);
+    let mut carol = new_client(&server);
+    carol.set_live_transport(transport);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+    log_in(&server, &carol, "carol", false);
+
+    let body = CreateGameBody::new(None, true, None, ColorPreference::White);
+    let room = wait_for(|callback| alice.create_game(body, callback)).expect("Create failed");
+    let games = wait_for(|callback| carol.list_live_games(1, 10, callback)).unwrap();
+    assert!(games.games.is_empty());
+    wait_for(|callback| bob.join_game(room.uuid.clone(), callback)).expect("Join failed");
+
+    let games = wait_for(|callback| carol.list_live_games(1, 10, callback)).unwrap();
+    assert_eq!(games.pagination.total, 1);
+    let game = &games.games[0];
+    assert_eq!((game.white.name.as_str(), game.spectators), ("alice", 0));
+
+    let alice_connection = alice.connect_live(&room.uuid, || {}).unwrap();
+    let carol_connection = carol.connect_live(&game.uuid, || {}).unwrap();
+    wait_for_message(&alice_connection, |message| {
+        matches!(message, ServerMessage::Spectators { count: 1 })
+    });
+    let games = wait_for(|callback| carol.list_live_games(1, 10, callback)).unwrap();
+    assert_eq!(games.games[0].spectators, 1);
+
+    alice_connection.send(ClientMessage::Move {
+        uci: "e2e4".to_string(),
+    });
+    wait_for_message(
+        &carol_connection,
+        |message| matches!(message, ServerMessage::Move { uci, .. } if uci == "e2e4"),
+    );
+
+    // Spectators can't interfere with the game
+    carol_connection.send(ClientMessage::Move {
+        uci: "e7e5".to_string(),
+    });
+    wait_for_message(&carol_connection, |message| {
+        matches!(message, ServerMessage::Error { .. })
+    });
+}
+
+#[test]
+fn spectating_over_websocket() {
+    spectate_live_game(LiveTransport::WebSocket);
+}
+
+#[test]
+fn spectating_over_long_polling() {
+    spectate_live_game(LiveTransport::Polling);
+}
+
+#[test]
+fn rejected_move_is_taken_back() {
+    let server = MockServer::start().unwrap();
+    let alice = new_client(&server);
+    let bob = new_client(&server);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+
+    let body = CreateGameBody::new(None, false, None, ColorPreference::White);
+    let room = wait_for(|callback| alice.create_game(body, callback)).expect("Create failed");
+    wait_for(|callback| bob.join_game(room.uuid.clone(), callback)).expect("Join failed");
+
+    let engine = Engine::initialize();
+    let connection = alice.connect_live(&room.uuid, || {}).unwrap();
+    let mut online_game = OnlineGame::new(&engine, room.clone(), Some(Color::White), connection);
+    let bob_connection = bob.connect_live(&room.uuid, || {}).unwrap();
+    wait_for_message(&bob_connection, |message| {
+        matches!(message, ServerMessage::Sync { .. })
+    });
+
+    // The game ends on the server before the move of alice arrives
+    bob_connection.send(ClientMessage::Resign);
+    wait_for_game(&engine, &mut online_game, |game| game.game_end.is_some());
+    let chess_move = find_uci_move(&online_game.app_game.game, "e2e4").unwrap();
+    online_game.app_game.game.play_move(&engine, chess_move);
+    online_game.send_move(chess_move);
+
+    wait_for_game(&engine, &mut online_game, |game| game.error.is_some());
+    assert!(online_game.app_game.game.move_history().is_empty());
+}
+
+/// Processes the events of the game until the predicate holds.
+fn wait_for_game<P>(engine: &Arc<Engine>, online_game: &mut OnlineGame, predicate: P)
+where
+    P: Fn(&OnlineGame) -> bool,
+{
+    let deadline = Instant::now() + TIMEOUT;
+    while Instant::now() < deadline {
+        online_game.process_events(engine);
+        if predicate(online_game) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    panic!("The game did not reach the expected state in time");
+}
+
+/// Plays a game in which the black player resigns after the first move.
+fn play_resigned_game(
+    white: &MultiplayerClient,
+    black: &MultiplayerClient,
+    time_control: TimeControl,
+) {
+    let body = CreateGameBody::new(None, false, Some(time_control), ColorPreference::White);
+    let room = wait_for(|callback| white.create_game(body, callback)).expect("Create failed");
+    wait_for(|callback| black.join_game(room.uuid.clone(), callback)).expect("Join failed");
+
+    let white_connection = white.connect_live(&room.uuid, || {}).unwrap();
+    let black_connection = black.connect_live(&room.uuid, || {}).unwrap();
+    wait_for_message(&black_connection, |message| {
+        matches!(message, ServerMessage::Sync { .. })
+    });
+    white_connection.send(ClientMessage::Move {
+        uci: "e2e4".to_string(),
+    });
+    wait_for_message(&black_connection, |message| {
+        matches!(message, ServerMessage::Move { .. })
+    });
+    black_connection.send(ClientMessage::Resign);
+    wait_for_message(&black_connection, |message| {
+        matches!(message, ServerMessage::GameEnd { .. })
+    });
+}
+
+#[test]
+fn finished_games_show_up_in_profiles_and_history() {
+    let server = MockServer::start().unwrap();
+    let alice = new_client(&server);
+    let bob = new_client(&server);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+    play_resigned_game(&alice, &bob, TimeControl::new(5, 0));
+
+    let profile = wait_for(|callback| alice.get_user_profile(None, callback)).unwrap();
+    assert_eq!(profile.name, "alice");
+    assert_eq!(profile.stats.wins, 1);
+    let rating = profile.get_rating(RatingCategory::Blitz).unwrap();
+    assert_eq!((rating.rating, rating.games), (1516, 1));
+
+    let history =
+        wait_for(|callback| alice.get_game_history(Some("bob".to_string()), 1, 10, callback))
+            .unwrap();
+    assert_eq!(history.pagination.total, 1);
+    let game = &history.games[0];
+    assert_eq!(game.get_outcome("bob"), Some(GameOutcome::Loss));
+    assert_eq!(game.time_control(), Some(TimeControl::new(5, 0)));
+
+    let record = wait_for(|callback| bob.get_game_record(game.uuid.clone(), callback)).unwrap();
+    assert_eq!(record.moves, ["e2e4"]);
+
+    let result = wait_for(|callback| alice.get_user_profile(Some("nobody".to_string()), callback));
+    assert!(matches!(result, Err(ApiError::NotFound(_))));
+}
+
+#[test]
+fn friend_requests_presence_and_challenges() {
+    let server = MockServer::start().unwrap();
+    let alice = new_client(&server);
+    let bob = new_client(&server);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+
+    let result = wait_for(|callback| {
+        alice.send_challenge(
+            ChallengeBody::new("bob", None, ColorPreference::Random),
+            callback,
+        )
+    });
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+
+    wait_for(|callback| alice.add_friend("bob", callback)).expect("Request failed");
+    let friends = wait_for(|callback| bob.get_friends(callback)).unwrap();
+    assert_eq!(friends.incoming_requests[0].name, "alice");
+    assert!(friends.friends.is_empty());
+
+    wait_for(|callback| bob.add_friend("alice", callback)).expect("Accept failed");
+    let friends = wait_for(|callback| alice.get_friends(callback)).unwrap();
+    assert!(friends.outgoing_requests.is_empty());
+    assert_eq!(friends.friends[0].name, "bob");
+    assert_eq!(friends.friends[0].presence, Presence::Online);
+
+    let body = ChallengeBody::new("bob", Some(TimeControl::new(3, 2)), ColorPreference::Black);
+    let challenge = wait_for(|callback| alice.send_challenge(body.clone(), callback)).unwrap();
+    let incoming = wait_for(|callback| bob.get_challenges(callback))
+        .unwrap()
+        .incoming;
+    assert_eq!(incoming[0].challenger.name, "alice");
+    assert_eq!(incoming[0].time_control(), Some(TimeControl::new(3, 2)));
+    wait_for(|callback| bob.decline_challenge(challenge.uuid.clone(), callback)).unwrap();
+    let outgoing = wait_for(|callback| alice.get_challenges(callback))
+        .unwrap()
+        .outgoing;
+    assert_eq!(outgoing[0].status, ChallengeStatus::Declined);
+
+    let challenge = wait_for(|callback| alice.send_challenge(body, callback)).unwrap();
+    let room = wait_for(|callback| bob.accept_challenge(challenge.uuid.clone(), callback)).unwrap();
+    assert_eq!(room.white.unwrap().name, "bob");
+    assert_eq!(room.black.unwrap().name, "alice");
+    let outgoing = wait_for(|callback| alice.get_challenges(callback))
+        .unwrap()
+        .outgoing;
+    let accepted = outgoing
+        .iter()
+        .find(|outgoing| outgoing.uuid == challenge.uuid)
+        .unwrap();
+    assert!(
+        matches!(&accepted.status, ChallengeStatus::Accepted { room: accepted_room } if accepted_room.uuid == room.uuid)
+    );
+
+    let friends = wait_for(|callback| bob.get_friends(callback)).unwrap();
+    assert_eq!(friends.friends[0].presence, Presence::Playing);
+
+    wait_for(|callback| bob.remove_friend("alice", callback)).unwrap();
+    let friends = wait_for(|callback| alice.get_friends(callback)).unwrap();
+    assert!(friends.friends.is_empty());
+}
+
+#[test]
+fn leaderboard_ranks_players_per_category() {
+    let server = MockServer::start().unwrap();
+    let alice = new_client(&server);
+    let bob = new_client(&server);
+    let carol = new_client(&server);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+    log_in(&server, &carol, "carol", false);
+    play_resigned_game(&alice, &bob, TimeControl::new(5, 0));
+    play_resigned_game(&carol, &bob, TimeControl::new(3, 2));
+
+    let leaderboard =
+        wait_for(|callback| bob.get_leaderboard(RatingCategory::Blitz, 1, 2, callback)).unwrap();
+    assert_eq!(leaderboard.pagination.total, 3);
+    let names: Vec<&str> = leaderboard
+        .entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect();
+    assert_eq!(names, ["alice", "carol"]);
+    assert_eq!(leaderboard.entries[0].rank, 1);
+    let own_entry = leaderboard.own_entry.unwrap();
+    assert_eq!((own_entry.rank, own_entry.games), (3, 2));
+
+    let second_page =
+        wait_for(|callback| bob.get_leaderboard(RatingCategory::Blitz, 2, 2, callback)).unwrap();
+    assert_eq!(second_page.entries[0].name, "bob");
+
+    let rapid =
+        wait_for(|callback| bob.get_leaderboard(RatingCategory::Rapid, 1, 2, callback)).unwrap();
+    assert!(rapid.entries.is_empty());
+    assert!(rapid.own_entry.is_none());
+}
+
+#[test]
+fn correspondence_games_can_be_resumed() {
+    let server = MockServer::start().unwrap();
+    let alice = new_client(&server);
+    let bob = new_client(&server);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+
+    let body = CorrespondenceBody {
+        name: None,
+        public: false,
+        days_per_move: 0,
+        color: ColorPreference::White,
+    };
+    let result = wait_for(|callback| alice.create_correspondence_game(body.clone(), callback));
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+
+    let body = CorrespondenceBody {
+        days_per_move: 3,
+        ..body
+    };
+    let room = wait_for(|callback| alice.create_correspondence_game(body, callback))
+        .expect("Create failed");
+    let list = wait_for(|callback| alice.list_correspondence_games(callback)).unwrap();
+    assert!(!list.games[0].is_started());
+    wait_for(|callback| bob.join_game(room.uuid.clone(), callback)).expect("Join failed");
+
+    let list = wait_for(|callback| bob.list_correspondence_games(callback)).unwrap();
+    let game = &list.games[0];
+    assert_eq!(game.days_per_move, 3);
+    assert!(game.is_turn_of("alice"));
+    assert_eq!(game.get_opponent("bob").unwrap().name, "alice");
+    assert!(game.deadline.is_some());
+
+    let alice_connection = alice.connect_live(&room.uuid, || {}).unwrap();
+    let sync = wait_for_message(&alice_connection, |message| {
+        matches!(message, ServerMessage::Sync { .. })
+    });
+    assert!(matches!(
+        sync,
+        ServerMessage::Sync {
+            days_per_move: Some(3),
+            ..
+        }
+    ));
+    alice_connection.send(ClientMessage::Move {
+        uci: "e2e4".to_string(),
+    });
+    let moved = wait_for_message(&alice_connection, |message| {
+        matches!(message, ServerMessage::Move { .. })
+    });
+    let ServerMessage::Move { clock, .. } = moved else {
+        unreachable!();
+    };
+    assert_eq!(clock.white_time_left_micros, Some(3 * MICROS_PER_DAY));
+    drop(alice_connection);
+
+    let list = wait_for(|callback| alice.list_correspondence_games(callback)).unwrap();
+    assert!(list.games[0].is_turn_of("bob"));
+    assert_eq!(list.games[0].move_count, 1);
+
+    let bob_connection = bob.connect_live(&room.uuid, || {}).unwrap();
+    let sync = wait_for_message(&bob_connection, |message| {
+        matches!(message, ServerMessage::Sync { .. })
+    });
+    let ServerMessage::Sync { moves, .. } = sync else {
+        unreachable!();
+    };
+    assert_eq!(moves, ["e2e4"]);
+}
+
+/// Connects the white player of the room and resigns the game.
+fn resign_as_white(server: &MockServer, players: [(&MultiplayerClient, &str); 2], room_uuid: &str) {
+    let white_name = {
+        let state = server.state().lock().unwrap();
+        state.rooms[room_uuid].info.white.clone().unwrap().name
+    };
+    let (white, _) = players
+        .into_iter()
+        .find(|(_, name)| *name == white_name)
+        .unwrap();
+    let connection = white.connect_live(room_uuid, || {}).unwrap();
+    wait_for_message(&connection, |message| {
+        matches!(message, ServerMessage::Sync { .. })
+    });
+    connection.send(ClientMessage::Resign);
+    wait_for_message(&connection, |message| {
+        matches!(message, ServerMessage::GameEnd { .. })
+    });
+}
+
+#[test]
+fn swiss_tournament_pairs_rounds_and_tracks_standings() {
+    let server = MockServer::start().unwrap();
+    let alice = new_client(&server);
+    let bob = new_client(&server);
+    let carol = new_client(&server);
+    log_in(&server, &alice, "alice", false);
+    log_in(&server, &bob, "bob", false);
+    log_in(&server, &carol, "carol", false);
+    let uuid = server.state().lock().unwrap().create_tournament(
+        "Club Swiss",
+        TournamentFormat::Swiss,
+        Some(TimeControl::new(5, 0)),
+        Duration::ZERO,
+    );
+
+    wait_for(|callback| carol.join_tournament(uuid.clone(), callback)).expect("Join failed");
+    wait_for(|callback| carol.withdraw_from_tournament(uuid.clone(), callback))
+        .expect("Withdraw failed");
+    wait_for(|callback| alice.join_tournament(uuid.clone(), callback)).expect("Join failed");
+    let tournament = wait_for(|callback| alice.get_tournament(uuid.clone(), callback)).unwrap();
+    assert_eq!(tournament.summary.status, TournamentStatus::Upcoming);
+    assert!(tournament.summary.joined);
+    assert!(tournament.own_game.is_none());
+
+    wait_for(|callback| bob.join_tournament(uuid.clone(), callback)).expect("Join failed");
+
+    let list = wait_for(|callback| bob.list_tournaments(1, 10, callback)).unwrap();
+    let summary = list
+        .tournaments
+        .iter()
+        .find(|summary| summary.uuid == uuid)
+        .unwrap();
+    assert_eq!(summary.status, TournamentStatus::Running);
+    assert_eq!((summary.player_count, summary.current_round), (2, 1));
+
+    let result = wait_for(|callback| carol.join_tournament(uuid.clone(), callback));
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+
+    let tournament = wait_for(|callback| alice.get_tournament(uuid.clone(), callback)).unwrap();
+    let room = tournament.own_game.expect("No game was paired");
+    let bob_tournament = wait_for(|callback| bob.get_tournament(uuid.clone(), callback)).unwrap();
+    assert_eq!(
+        bob_tournament.own_game.map(|room| room.uuid),
+        Some(room.uuid.clone())
+    );
+    resign_as_white(&server, [(&alice, "alice"), (&bob, "bob")], &room.uuid);
+
+    let tournament = wait_for(|callback| alice.get_tournament(uuid.clone(), callback)).unwrap();
+    assert_eq!(tournament.summary.current_round, 2);
+    assert_eq!(tournament.pairings[0].round, 2);
+    assert_eq!(tournament.pairings[1].result.as_deref(), Some("0-1"));
+    let leader = &tournament.standings[0];
+    assert_eq!((leader.rank, leader.points, leader.games), (1, 1.0, 1));
+    let second_room = tournament.own_game.expect("No game was paired");
+    assert_ne!(second_room.uuid, room.uuid);
+
+    wait_for(|callback| alice.withdraw_from_tournament(uuid.clone(), callback))
+        .expect("Withdraw failed");
+    resign_as_white(
+        &server,
+        [(&alice, "alice"), (&bob, "bob")],
+        &second_room.uuid,
+    );
+    let tournament = wait_for(|callback| bob.get_tournament(uuid.clone(), callback)).unwrap();
+    assert_eq!(tournament.summary.status, TournamentStatus::Finished);
+    assert!(tournament
+        .standings
+        .iter()
+        .any(|standing| standing.withdrawn));
+    assert!(tournament.own_game.is_none());
+}
+
+#[test]
+fn admins_manage_invite_codes() {
+    let server = MockServer::start().unwrap();
+    let admin = new_client(&server);
+    let alice = new_client(&server);
+    log_in(&server, &admin, "admin", false);
+    log_in(&server, &alice, "alice", false);
+
+    let account = wait_for(|callback| admin.get_account(callback)).unwrap();
+    assert!(account.is_admin());
+    let account = wait_for(|callback| alice.get_account(callback)).unwrap();
+    assert!(!account.is_admin());
+    let result = wait_for(|callback| alice.list_invite_codes(callback));
+    assert!(matches!(result, Err(ApiError::Forbidden(_))));
+
+    let body = InviteCodeBody {
+        expires_in_days: Some(7),
+        max_uses: Some(1),
+    };
+    let invite_code =
+        wait_for(|callback| admin.create_invite_code(body, callback)).expect("Create failed");
+    assert!(invite_code.is_active());
+    assert!(invite_code.expires_at.is_some());
+
+    let bob = new_client(&server);
+    wait_for(|callback| bob.register("bob", "password", invite_code.code.clone(), callback))
+        .expect("Registration failed");
+    let result =
+        wait_for(|callback| bob.register("carol", "password", invite_code.code.clone(), callback));
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+
+    let unlimited =
+        wait_for(|callback| admin.create_invite_code(InviteCodeBody::default(), callback))
+            .expect("Create failed");
+    wait_for(|callback| admin.revoke_invite_code(unlimited.code.clone(), callback))
+        .expect("Revoke failed");
+    let result =
+        wait_for(|callback| bob.register("carol", "password", unlimited.code.clone(), callback));
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+
+    let list = wait_for(|callback| admin.list_invite_codes(callback)).unwrap();
+    let statuses: Vec<InviteCodeStatus> = list.codes.iter().map(InviteCode::get_status).collect();
+    assert_eq!(list.codes.len(), 2);
+    assert!(statuses.contains(&InviteCodeStatus::UsedUp));
+    assert!(statuses.contains(&InviteCodeStatus::Revoked));
+}
+
+#[test]
+fn accounts_can_be_changed_and_deleted() {
+    let server = MockServer::start().unwrap();
+    let client = new_client(&server);
+    let bob = new_client(&server);
+    log_in(&server, &client, "alice", false);
+    log_in(&server, &bob, "bob", false);
+
+    let body = ChangePasswordBody {
+        password: "wrong password".to_string(),
+        new_password: "new password".to_string(),
+    };
+    let result = wait_for(|callback| client.change_password(body, callback));
+    let Err(ApiError::Validation(field_errors)) = result else {
+        panic!("Expected field errors, got {result:?}");
+    };
+    assert!(field_errors.get("password").is_some());
+
+    let body = ChangePasswordBody {
+        password: "password".to_string(),
+        new_password: "short".to_string(),
+    };
+    let result = wait_for(|callback| client.change_password(body, callback));
+    let Err(ApiError::Validation(field_errors)) = result else {
+        panic!("Expected field errors, got {result:?}");
+    };
+    assert!(field_errors.get("new_password").is_some());
+
+    let body = ChangePasswordBody {
+        password: "password".to_string(),
+        new_password: "new password".to_string(),
+    };
+    wait_for(|callback| client.change_password(body, callback)).expect("Password change failed");
+    let result = wait_for(|callback| client.login("alice", "password", callback));
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+
+    let body = ChangeUsernameBody {
+        username: "no spaces".to_string(),
+        password: "new password".to_string(),
+    };
+    let result = wait_for(|callback| client.change_username(body, callback));
+    let Err(ApiError::Validation(field_errors)) = result else {
+        panic!("Expected field errors, got {result:?}");
+    };
+    assert!(field_errors.get("username").is_some());
+
+    let body = ChangeUsernameBody {
+        username: "bob".to_string(),
+        password: "new password".to_string(),
+    };
+    let result = wait_for(|callback| client.change_username(body, callback));
+    assert!(matches!(result, Err(ApiError::Collision(_))));
+
+    let body = ChangeUsernameBody {
+        username: "carol".to_string(),
+        password: "new password".to_string(),
+    };
+    let account =
+        wait_for(|callback| client.change_username(body, callback)).expect("Rename failed");
+    assert_eq!(account.name, "carol");
+    // The token survives the rename
+    wait_for(|callback| client.ping(callback)).expect("Ping failed");
+    wait_for(|callback| bob.login("carol", "new password", callback)).expect("Login failed");
+
+    let body = DeleteAccountBody {
+        password: "password".to_string(),
+    };
+    let result = wait_for(|callback| client.delete_account(body, callback));
+    assert!(matches!(result, Err(ApiError::Validation(_))));
+
+    let body = DeleteAccountBody {
+        password: "new password".to_string(),
+    };
+    wait_for(|callback| client.delete_account(body, callback)).expect("Deletion failed");
+    let result = wait_for(|callback| bob.login("carol", "new password", callback));
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+}
@@ -0,0 +1,67 @@
+use giga_chess_api_types::response::user_info::PublicUserInfo;
+use giga_chess_desktop::api::matchmaking::TimeControl;
+use giga_chess_desktop::api::player_profile::{GameOutcome, GameSummary, RatingCategory};
+
+#[test]
+fn rating_category_follows_estimated_duration() {
+    let category = |minutes, increment| {
+        RatingCategory::from_time_control(Some(TimeControl::new(minutes, increment)))
+    };
+    assert_eq!(category(1, 0), RatingCategory::Bullet);
+    assert_eq!(category(3, 2), RatingCategory::Blitz);
+    assert_eq!(category(10, 0), RatingCategory::Rapid);
+    assert_eq!(category(15, 10), RatingCategory::Rapid);
+    assert_eq!(category(30, 0), RatingCategory::Classical);
+    assert_eq!(category(u32::MAX, u32::MAX), RatingCategory::Classical);
+    assert_eq!(
+        RatingCategory::from_time_control(None),
+        RatingCategory::Unlimited
+    );
+}
+
+#[test]
+fn outcome_depends_on_the_player() {
+    let game = GameSummary {
+        uuid: "game".to_string(),
+        white: PublicUserInfo {
+            name: "alice".to_string(),
+        },
+        black: PublicUserInfo {
+            name: "bob".to_string(),
+        },
+        time_micros: Some(180_000_000),
+        increment_micros: Some(2_000_000),
+        result: "0-1".to_string(),
+        reason: "Checkmate".to_string(),
+        ended_at: 0,
+    };
+    assert_eq!(game.get_outcome("alice"), Some(GameOutcome::Loss));
+    assert_eq!(game.get_outcome("bob"), Some(GameOutcome::Win));
+    assert_eq!(game.get_outcome("carol"), None);
+    assert_eq!(game.get_opponent("alice").unwrap().name, "bob");
+    assert_eq!(game.time_control(), Some(TimeControl::new(3, 2)));
+}
+
+#[test]
+fn unknown_results_have_no_outcome() {
+    let game = |result: &str| GameSummary {
+        uuid: "game".to_string(),
+        white: PublicUserInfo {
+            name: "alice".to_string(),
+        },
+        black: PublicUserInfo {
+            name: "bob".to_string(),
+        },
+        time_micros: None,
+        increment_micros: None,
+        result: result.to_string(),
+        reason: String::new(),
+        ended_at: 0,
+    };
+    assert_eq!(
+        game("1/2-1/2").get_outcome("alice"),
+        Some(GameOutcome::Draw)
+    );
+    assert_eq!(game("*").get_outcome("alice"), None);
+    assert_eq!(game("").get_outcome("bob"), None);
+}
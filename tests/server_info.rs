@@ -0,0 +1,41 @@
+use giga_chess_desktop::api::server_info::{Capability, ServerInfo, ServerInfoState, API_VERSION};
+
+fn server_info(api_version: &str, capabilities: &[Capability]) -> ServerInfo {
+    ServerInfo {
+        api_version: api_version.to_string(),
+        version: None,
+        capabilities: capabilities.to_vec(),
+    }
+}
+
+#[test]
+fn compatibility_follows_cargo_rules() {
+    assert!(server_info(API_VERSION, &[]).check_compatibility().is_ok());
+    assert!(server_info("0.1.7", &[]).check_compatibility().is_ok());
+    assert!(server_info("v0.1.2-beta", &[])
+        .check_compatibility()
+        .is_ok());
+    assert!(server_info("0.2.0", &[]).check_compatibility().is_err());
+    assert!(server_info("1.0.0", &[]).check_compatibility().is_err());
+    assert!(server_info("latest", &[]).check_compatibility().is_err());
+}
+
+#[test]
+fn unknown_capabilities_are_ignored() {
+    let info: ServerInfo = serde_json::from_str(
+        r#"{"api_version": "0.1.0", "capabilities": ["queue", "time_travel"]}"#,
+    )
+    .unwrap();
+    assert!(info.supports(Capability::Queue));
+    assert!(!info.supports(Capability::OpenGames));
+}
+
+#[test]
+fn missing_capabilities_are_only_known_after_the_handshake() {
+    let compatible = ServerInfoState::Compatible(server_info(API_VERSION, &[Capability::Queue]));
+    assert!(compatible.supports(Capability::Queue));
+    assert!(!compatible.supports(Capability::LiveWebsocket));
+
+    assert!(ServerInfoState::Unknown.supports(Capability::LiveWebsocket));
+    assert!(!ServerInfoState::Incompatible(String::new()).supports(Capability::Queue));
+}
@@ -0,0 +1,49 @@
+use giga_chess_desktop::api::session::{deserialize_parked_sessions, Session};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+struct ParkedSessions {
+    #[serde(deserialize_with = "deserialize_parked_sessions")]
+    sessions: HashMap<String, Session>,
+}
+
+#[test]
+fn passwords_of_older_saves_are_dropped() {
+    let saved = r#"{
+        "token": "token",
+        "username": "alice",
+        "expires_at": null,
+        "credentials": { "username": "alice", "password": "secret" }
+    }"#;
+    let session: Session = serde_json::from_str(saved).unwrap();
+    assert_eq!(session.username.as_deref(), Some("alice"));
+    assert!(session.refresh_token.is_none());
+
+    let resaved = serde_json::to_string(&session).unwrap();
+    assert!(!resaved.contains("secret"));
+}
+
+#[test]
+fn bare_tokens_are_still_loaded() {
+    let session: Session = serde_json::from_str(r#""token""#).unwrap();
+    assert_eq!(session.token, "token");
+    assert!(session.username.is_none());
+    assert!(session.refresh_token.is_none());
+}
+
+#[test]
+fn parked_login_states_of_older_saves_are_reduced_to_sessions() {
+    let saved = r#"{ "sessions": {
+        "http://a": { "Success": { "token": "a", "username": "alice", "expires_at": null } },
+        "http://b": "Expired",
+        "http://c": { "token": "c", "username": "carol", "expires_at": null, "refresh_token": "r" }
+    } }"#;
+    let parked: ParkedSessions = serde_json::from_str(saved).unwrap();
+    assert_eq!(parked.sessions.len(), 2);
+    assert_eq!(parked.sessions["http://a"].token, "a");
+    assert_eq!(
+        parked.sessions["http://c"].refresh_token.as_deref(),
+        Some("r")
+    );
+}